@@ -1,5 +1,10 @@
 use clap::{Parser, ColorChoice, CommandFactory, FromArgMatches};
+use std::ffi::OsString;
 use std::path::PathBuf;
+use crate::error::{NailError, NailResult};
+use crate::utils::config::{apply_config, Config};
+use crate::utils::io::ReadOptions;
+use crate::utils::{RuntimeOptions, SessionOptions, DEFAULT_BATCH_SIZE};
 
 #[derive(Parser)]
 #[command(name = "nail")]
@@ -19,10 +24,15 @@ use std::path::PathBuf;
 pub struct Cli {
 	#[command(subcommand)]
 	pub command: crate::commands::Commands,
+	
+	#[arg(long, global = true, value_enum, default_value = "text", help = "How errors are printed to stderr")]
+	pub error_format: ErrorFormat,
 }
 
 impl Cli {
-	pub fn parse_with_width() -> Self {
+	/// Parses the command line with defaults from the config files and `NAIL_*` variables filled in.
+	/// On failure, returns the error together with the format it should be reported in.
+	pub fn parse_with_width() -> Result<Self, (NailError, ErrorFormat)> {
 		let width = if let Some((w, _)) = term_size::dimensions() {
 			Some(w.max(80).min(200))
 		} else {
@@ -34,12 +44,36 @@ impl Cli {
 			cmd = cmd.term_width(w);
 		}
 		
-		Self::from_arg_matches(&cmd.get_matches()).unwrap()
+		let raw_args: Vec<OsString> = std::env::args_os().collect();
+		let config = Config::load()
+			.map_err(|e| (e, ErrorFormat::from_args(&raw_args).unwrap_or_default()))?;
+		let env: Vec<(String, String)> = std::env::vars().collect();
+		let args = apply_config(&cmd, &config, raw_args.clone(), &env);
+		
+		let matches = match cmd.clone().try_get_matches_from(args) {
+			Ok(matches) => matches,
+			Err(e) => {
+				// Config defaults are only layered onto arguments that parse, so usage errors
+				// take the format from the raw arguments, then from a global config setting
+				let error_format = ErrorFormat::from_args(&raw_args)
+					.or_else(|| ErrorFormat::from_config(&config, &cmd, &env))
+					.unwrap_or_default();
+				// Help and version requests are not errors; JSON consumers still get usage errors as JSON
+				if error_format == ErrorFormat::Json && e.use_stderr() {
+					let rendered = e.to_string();
+					let message = rendered.lines().next().unwrap_or_default().trim_start_matches("error: ");
+					return Err((NailError::InvalidArgument(message.to_string()), error_format));
+				}
+				e.exit()
+			},
+		};
+		
+		Ok(Self::from_arg_matches(&matches).unwrap())
 	}
 }
 
 
-#[derive(clap::Args, Clone)]
+#[derive(clap::Args, Clone, Default)]
 pub struct CommonArgs {
 	#[arg(help = "Input file")]
 	pub input: PathBuf,
@@ -56,8 +90,20 @@ pub struct CommonArgs {
 	#[arg(short, long, help = "Enable verbose output")]
 	pub verbose: bool,
 	
-	#[arg(short, long, help = "Number of parallel jobs")]
-	pub jobs: Option<usize>,
+	#[command(flatten)]
+	pub read: ReadArgs,
+	
+	#[arg(long, help = "Text encoding for CSV/TSV output files (default: utf-8)")]
+	pub output_encoding: Option<String>,
+	
+	#[arg(long, help = "Print the logical and physical plan instead of running the command", conflicts_with = "explain_analyze")]
+	pub explain: bool,
+	
+	#[arg(long, help = "Run the command and print the physical plan with per-operator metrics (rows, time, spills, pruning) instead of the result")]
+	pub explain_analyze: bool,
+	
+	#[arg(long = "where", value_name = "CONDITION", help = "Only use input rows matching CONDITION (same syntax as 'filter -c'); Parquet row groups that cannot match are skipped")]
+	pub where_clause: Option<String>,
 }
 
 impl CommonArgs {
@@ -66,6 +112,147 @@ impl CommonArgs {
 			eprintln!("{}", message);
 		}
 	}
+	
+	/// Whether --explain or --explain-analyze asked for the plan instead of the result.
+	pub fn explain_requested(&self) -> bool {
+		self.explain || self.explain_analyze
+	}
+	
+	/// Fails when a plan was requested from a command that has no query plan to show.
+	pub fn reject_explain(&self, command: &str) -> NailResult<()> {
+		if self.explain_requested() {
+			return Err(NailError::InvalidArgument(format!(
+				"--explain and --explain-analyze are not supported by '{}'",
+				command
+			)));
+		}
+		Ok(())
+	}
+	
+	pub fn read_options(&self) -> ReadOptions {
+		ReadOptions {
+			where_clause: self.where_clause.clone(),
+			..self.read.read_options(self.verbose)
+		}
+	}
+	
+	pub fn session_options(&self) -> SessionOptions {
+		self.read.session_options()
+	}
+	
+	pub fn runtime_options(&self) -> RuntimeOptions {
+		self.read.runtime_options()
+	}
+}
+
+/// Input parsing, session and runtime options, shared by [`CommonArgs`] and the commands
+/// that read files without the rest of it.
+#[derive(clap::Args, Clone, Default)]
+pub struct ReadArgs {
+	#[arg(short, long, help = "Number of parallel jobs")]
+	pub jobs: Option<usize>,
+	
+	#[arg(long, help = "How to handle malformed CSV/NDJSON rows", value_enum, default_value = "fail")]
+	pub on_bad_rows: BadRowPolicy,
+	
+	#[arg(long, help = "Sidecar file for quarantined rows (default: <input>.rejects.csv)")]
+	pub rejects_file: Option<PathBuf>,
+	
+	#[arg(long, help = "Input text encoding for CSV/TSV (e.g. utf-8, windows-1252, latin1, utf-16le, auto)")]
+	pub encoding: Option<String>,
+	
+	#[arg(long, help = "Field delimiter for CSV/TSV input (e.g. ';', '|', '\\t')", value_parser = parse_single_byte)]
+	pub delimiter: Option<u8>,
+	
+	#[arg(long, help = "Quote character for CSV/TSV input (default: '\"')", value_parser = parse_single_byte)]
+	pub quote: Option<u8>,
+	
+	#[arg(long, help = "CSV/TSV input has no header row (columns are named column_1, column_2, ...)")]
+	pub no_header: bool,
+	
+	#[arg(long, help = "JSON schema file for CSV/TSV/JSON input (as written by 'nail schema -f json' or 'nail sniff')")]
+	pub schema: Option<PathBuf>,
+	
+	#[arg(long, help = "Memory limit for sorts, aggregations and joins before spilling to disk (e.g. 512M, 8G)", value_parser = parse_memory_size)]
+	pub memory_limit: Option<usize>,
+	
+	#[arg(long, help = "Directory for spill files (default: system temp directory)")]
+	pub temp_dir: Option<PathBuf>,
+	
+	#[arg(long, help = "Rows per record batch (default: 8192)")]
+	pub batch_size: Option<usize>,
+	
+	#[arg(long, help = "Collect file statistics when reading (slower reads, better planning)")]
+	pub collect_stats: bool,
+	
+	#[arg(long, help = "Disable Parquet row group pruning")]
+	pub no_pruning: bool,
+}
+
+impl ReadArgs {
+	pub fn read_options(&self, verbose: bool) -> ReadOptions {
+		ReadOptions {
+			on_bad_rows: self.on_bad_rows.clone(),
+			rejects_file: self.rejects_file.clone(),
+			encoding: self.encoding.clone(),
+			delimiter: self.delimiter,
+			quote: self.quote,
+			no_header: self.no_header,
+			schema_file: self.schema.clone(),
+			session: self.session_options(),
+			verbose,
+			where_clause: None,
+		}
+	}
+	
+	pub fn session_options(&self) -> SessionOptions {
+		SessionOptions {
+			jobs: self.jobs,
+			batch_size: self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE),
+			collect_statistics: self.collect_stats,
+			parquet_pruning: !self.no_pruning,
+		}
+	}
+	
+	pub fn runtime_options(&self) -> RuntimeOptions {
+		RuntimeOptions {
+			memory_limit: self.memory_limit,
+			temp_dir: self.temp_dir.clone(),
+		}
+	}
+}
+
+/// Parses a single ASCII character argument, accepting `\t` and `tab` for tabs.
+pub fn parse_single_byte(value: &str) -> Result<u8, String> {
+	match value {
+		"\\t" | "tab" | "\t" => Ok(b'\t'),
+		_ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+		_ => Err(format!("expected a single ASCII character, got '{}'", value)),
+	}
+}
+
+/// Parses a byte size such as `1024`, `512M`, `8G` or `1.5GiB` (binary multiples).
+pub fn parse_memory_size(value: &str) -> Result<usize, String> {
+	let trimmed = value.trim();
+	let split = trimmed.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(trimmed.len());
+	let (number, unit) = trimmed.split_at(split);
+	let number: f64 = number.parse()
+		.map_err(|_| format!("invalid memory size '{}', expected e.g. 512M or 8G", value))?;
+	
+	let multiplier: u64 = match unit.trim().to_ascii_uppercase().trim_end_matches("IB").trim_end_matches('B') {
+		"" => 1,
+		"K" => 1 << 10,
+		"M" => 1 << 20,
+		"G" => 1 << 30,
+		"T" => 1 << 40,
+		_ => return Err(format!("invalid memory size unit in '{}', use K, M, G or T", value)),
+	};
+	
+	let bytes = (number * multiplier as f64) as usize;
+	if bytes == 0 {
+		return Err("memory limit must be greater than zero".to_string());
+	}
+	Ok(bytes)
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -75,4 +262,43 @@ pub enum OutputFormat {
 	Csv,
 	Parquet,
 	Xlsx,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ErrorFormat {
+	#[default]
+	Text,
+	Json,
+}
+
+impl ErrorFormat {
+	/// Reads `--error-format` from raw arguments, so errors raised before or during
+	/// parsing are reported in the requested format too.
+	pub fn from_args<S: AsRef<std::ffi::OsStr>>(args: &[S]) -> Option<Self> {
+		let args: Vec<OsString> = args.iter().map(|a| a.as_ref().to_os_string()).collect();
+		let value = args.iter().enumerate().rev().find_map(|(i, arg)| {
+			let arg = arg.to_str()?;
+			match arg.strip_prefix("--error-format") {
+				Some("") => args.get(i + 1).and_then(|v| v.to_str()),
+				Some(rest) => rest.strip_prefix('='),
+				None => None,
+			}
+		});
+		value.and_then(|v| <Self as clap::ValueEnum>::from_str(v, false).ok())
+	}
+	
+	/// Reads a global `error-format` default from the config files or `NAIL_ERROR_FORMAT`.
+	pub fn from_config(config: &Config, root: &clap::Command, env: &[(String, String)]) -> Option<Self> {
+		config.settings(root, None, env).into_iter()
+			.find(|s| s.command.is_none() && s.key == "error-format")
+			.and_then(|s| <Self as clap::ValueEnum>::from_str(&s.value, false).ok())
+	}
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum BadRowPolicy {
+	#[default]
+	Fail,
+	Skip,
+	Quarantine,
 }
\ No newline at end of file
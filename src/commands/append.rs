@@ -1,12 +1,12 @@
 use clap::Args;
-use datafusion::prelude::*;
 use datafusion::common::DFSchemaRef;
 use std::path::PathBuf;
 use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
+use crate::utils::io::{read_data_with_options, ReadOptions};
 use crate::utils::output::OutputHandler;
-use crate::utils::column::resolve_column_name;
 use crate::cli::CommonArgs;
+use crate::ops::{append, AppendOptions};
+use crate::ops::append::schemas_compatible;
 
 #[derive(Args, Clone)]
 pub struct AppendArgs {
@@ -23,10 +23,12 @@ pub struct AppendArgs {
 pub async fn execute(args: AppendArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading base table from: {}", args.common.input.display()));
 	
-	let mut base_df = read_data(&args.common.input).await?;
+	let mut base_df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
 	let base_schema: DFSchemaRef = base_df.schema().clone().into();
 	
 	let append_files: Vec<&str> = args.files.split(',').map(|s| s.trim()).collect();
+	// Appended files get their own default rejects sidecar
+	let secondary_options = ReadOptions { rejects_file: None, ..args.common.read_options() };
 	
 	args.common.log_if_verbose(&format!("Appending {} files", append_files.len()));
 	
@@ -35,7 +37,7 @@ pub async fn execute(args: AppendArgs) -> NailResult<()> {
 		
 		args.common.log_if_verbose(&format!("Appending: {}", path.display()));
 		
-		let append_df = read_data(&path).await?;
+		let append_df = read_data_with_options(&path, &secondary_options).await?;
 		let append_schema: DFSchemaRef = append_df.schema().clone().into();
 		
 		if !args.ignore_schema && !schemas_compatible(&base_schema, &append_schema) {
@@ -45,13 +47,9 @@ pub async fn execute(args: AppendArgs) -> NailResult<()> {
 			)));
 		}
 		
-		let aligned_df = if args.ignore_schema {
-			align_schemas(&append_df, &base_schema, args.common.jobs).await?
-		} else {
-			append_df
-		};
-		
-		base_df = base_df.union(aligned_df)?;
+		base_df = append(base_df, append_df, AppendOptions {
+			ignore_schema: args.ignore_schema,
+		}).await?;
 	}
 	
 	let total_rows = base_df.clone().count().await?;
@@ -63,76 +61,10 @@ pub async fn execute(args: AppendArgs) -> NailResult<()> {
 	Ok(())
 }
 
-fn schemas_compatible(schema1: &datafusion::common::DFSchemaRef, schema2: &datafusion::common::DFSchemaRef) -> bool {
-	if schema1.fields().len() != schema2.fields().len() {
-		return false;
-	}
-	
-	for (field1, field2) in schema1.fields().iter().zip(schema2.fields().iter()) {
-		if field1.name() != field2.name() || field1.data_type() != field2.data_type() {
-			return false;
-		}
-	}
-	
-	true
-}
-
-async fn align_schemas(df: &DataFrame, target_schema: &datafusion::common::DFSchemaRef, jobs: Option<usize>) -> NailResult<DataFrame> {
-	let ctx = crate::utils::create_context_with_jobs(jobs).await?;
-	let table_name = "temp_table";
-	ctx.register_table(table_name, df.clone().into_view())?;
-	
-	let current_schema: DFSchemaRef = df.schema().clone().into();
-	let mut select_exprs = Vec::new();
-	
-	for target_field in target_schema.fields() {
-		let target_name = target_field.name();
-		
-		if let Ok(resolved_name) = resolve_column_name(&current_schema, target_name) {
-			select_exprs.push(Expr::Column(datafusion::common::Column::new(None::<String>, &resolved_name)));
-		} else {
-			// For missing columns, use typed NULL values instead of default values
-			let null_expr = match target_field.data_type() {
-				datafusion::arrow::datatypes::DataType::Int64 => {
-					lit(datafusion::scalar::ScalarValue::Int64(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Float64 => {
-					lit(datafusion::scalar::ScalarValue::Float64(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Int32 => {
-					lit(datafusion::scalar::ScalarValue::Int32(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Float32 => {
-					lit(datafusion::scalar::ScalarValue::Float32(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Utf8 => {
-					lit(datafusion::scalar::ScalarValue::Utf8(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Boolean => {
-					lit(datafusion::scalar::ScalarValue::Boolean(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Date32 => {
-					lit(datafusion::scalar::ScalarValue::Date32(None)).alias(target_name)
-				},
-				datafusion::arrow::datatypes::DataType::Date64 => {
-					lit(datafusion::scalar::ScalarValue::Date64(None)).alias(target_name)
-				},
-				_ => {
-					// For unknown types, try to create a null of the correct type
-					lit(datafusion::scalar::ScalarValue::try_from(target_field.data_type()).unwrap_or(datafusion::scalar::ScalarValue::Null)).alias(target_name)
-				},
-			};
-			select_exprs.push(null_expr);
-		}
-	}
-	
-	let result = ctx.table(table_name).await?.select(select_exprs)?;
-	Ok(result)
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use datafusion::prelude::*;
 	use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
 	use datafusion::arrow::array::{Int64Array, StringArray};
 	use datafusion::arrow::record_batch::RecordBatch;
@@ -162,88 +94,6 @@ mod tests {
 		Ok(df)
 	}
 	
-	#[test]
-	fn test_schemas_compatible() {
-		use datafusion::common::DFSchema;
-		use datafusion::arrow::datatypes::{DataType, Field};
-		
-		// Create compatible schemas by converting from Arrow schemas
-		let arrow_schema1 = ArrowSchema::new(vec![
-			Field::new("id", DataType::Int64, false),
-			Field::new("name", DataType::Utf8, false),
-		]);
-		let schema1 = Arc::new(DFSchema::try_from(arrow_schema1).unwrap());
-		
-		let arrow_schema2 = ArrowSchema::new(vec![
-			Field::new("id", DataType::Int64, false),
-			Field::new("name", DataType::Utf8, false),
-		]);
-		let schema2 = Arc::new(DFSchema::try_from(arrow_schema2).unwrap());
-		
-		assert!(schemas_compatible(&schema1, &schema2));
-		
-		// Test incompatible schemas - different number of fields
-		let arrow_schema3 = ArrowSchema::new(vec![
-			Field::new("id", DataType::Int64, false),
-		]);
-		let schema3 = Arc::new(DFSchema::try_from(arrow_schema3).unwrap());
-		
-		assert!(!schemas_compatible(&schema1, &schema3));
-		
-		// Test incompatible schemas - different field names
-		let arrow_schema4 = ArrowSchema::new(vec![
-			Field::new("id", DataType::Int64, false),
-			Field::new("description", DataType::Utf8, false),
-		]);
-		let schema4 = Arc::new(DFSchema::try_from(arrow_schema4).unwrap());
-		
-		assert!(!schemas_compatible(&schema1, &schema4));
-		
-		// Test incompatible schemas - different data types
-		let arrow_schema5 = ArrowSchema::new(vec![
-			Field::new("id", DataType::Int64, false),
-			Field::new("name", DataType::Float64, false),
-		]);
-		let schema5 = Arc::new(DFSchema::try_from(arrow_schema5).unwrap());
-		
-		assert!(!schemas_compatible(&schema1, &schema5));
-	}
-	
-	#[tokio::test]
-	async fn test_align_schemas() {
-		// Create a DataFrame with missing columns
-		let df = create_test_dataframe(vec![1, 2, 3], vec!["a", "b", "c"]).await.unwrap();
-		
-		// Create target schema with additional column
-		use datafusion::common::DFSchema;
-		use datafusion::arrow::datatypes::{DataType, Field};
-		
-		let arrow_target_schema = ArrowSchema::new(vec![
-			Field::new("id", DataType::Int64, false),
-			Field::new("name", DataType::Utf8, false),
-			Field::new("score", DataType::Float64, false),
-		]);
-		let target_schema = Arc::new(DFSchema::try_from(arrow_target_schema).unwrap());
-		
-		let aligned_df = align_schemas(&df, &target_schema, None).await.unwrap();
-		let aligned_schema = aligned_df.schema();
-		
-		// Check that aligned schema has all target fields
-		assert_eq!(aligned_schema.fields().len(), 3);
-		assert!(aligned_schema.field_with_name(None, "id").is_ok());
-		assert!(aligned_schema.field_with_name(None, "name").is_ok());
-		assert!(aligned_schema.field_with_name(None, "score").is_ok());
-		
-		// Check that default values are added for missing column
-		let batches = aligned_df.collect().await.unwrap();
-		assert_eq!(batches[0].num_rows(), 3);
-		
-		let score_array = batches[0].column(2).as_any().downcast_ref::<datafusion::arrow::array::Float64Array>().unwrap();
-		assert_eq!(score_array.value(0), 0.0);
-		assert_eq!(score_array.value(1), 0.0);
-		assert_eq!(score_array.value(2), 0.0);
-	}
-	
 	#[tokio::test]
 	async fn test_execute_with_compatible_schemas() {
 		let temp_dir = tempdir().unwrap();
@@ -262,11 +112,7 @@ mod tests {
 		let args = AppendArgs {
 			common: CommonArgs {
 				input: base_path.clone(),
-				output: None,
-				format: None,
-				verbose: false,
-				jobs: None,
-				random: None,
+				..CommonArgs::default()
 			},
 			files: append_path.to_string_lossy().to_string(),
 			ignore_schema: false,
@@ -309,11 +155,7 @@ mod tests {
 		let args = AppendArgs {
 			common: CommonArgs {
 				input: base_path.clone(),
-				output: None,
-				format: None,
-				verbose: false,
-				jobs: None,
-				random: None,
+				..CommonArgs::default()
 			},
 			files: append_path.to_string_lossy().to_string(),
 			ignore_schema: false,
@@ -327,11 +169,7 @@ mod tests {
 		let args_ignore = AppendArgs {
 			common: CommonArgs {
 				input: base_path.clone(),
-				output: None,
-				format: None,
-				verbose: false,
-				jobs: None,
-				random: None,
+				..CommonArgs::default()
 			},
 			files: append_path.to_string_lossy().to_string(),
 			ignore_schema: true,
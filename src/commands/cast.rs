@@ -0,0 +1,92 @@
+use clap::Args;
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{self, cast, CastOptions, CastReport};
+use crate::ops::cast::parse_cast_specs;
+
+#[derive(Args, Clone)]
+pub struct CastArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+	
+	#[arg(short, long, value_name = "COLUMN=TYPE,...", help = "Columns and target types, e.g. 'price=float64,zip=utf8,ts=timestamp[ms]'.\n\
+		Columns accept selectors ('amount_*=float64').\n\
+		Types: int8..int64, uint8..uint64, float32, float64, decimal(p,s), utf8, bool,\n\
+		date, timestamp (microseconds), timestamp[s|ms|us|ns] or timestamp[ms, UTC]")]
+	pub columns: String,
+	
+	#[arg(long, value_enum, default_value = "fail", help = "Values that cannot be converted: fail, turn into null, or keep the column as strings")]
+	pub on_error: CastErrorPolicy,
+	
+	#[arg(long, help = "Numbers in text use ',' for decimals and '.' or spaces for thousands (1.234,56)")]
+	pub decimal_comma: bool,
+}
+
+/// What to do with values that cannot be converted to the target type.
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum CastErrorPolicy {
+	/// Stop with an error listing the failing values.
+	#[default]
+	Fail,
+	/// Replace the failing values with nulls.
+	Null,
+	/// Leave a column with failing values as strings.
+	KeepAsString,
+}
+
+impl From<CastErrorPolicy> for ops::CastErrorPolicy {
+	fn from(cast_error_policy: CastErrorPolicy) -> Self {
+		match cast_error_policy {
+			CastErrorPolicy::Fail => ops::CastErrorPolicy::Fail,
+			CastErrorPolicy::Null => ops::CastErrorPolicy::Null,
+			CastErrorPolicy::KeepAsString => ops::CastErrorPolicy::KeepAsString,
+		}
+	}
+}
+
+pub async fn execute(args: CastArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+	
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+	
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "cast").await?;
+	
+	Ok(())
+}
+
+/// Applies the cast operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &CastArgs) -> NailResult<DataFrame> {
+	let columns = parse_cast_specs(&args.columns)?;
+	args.common.log_if_verbose(&format!("Casting columns: {:?}", columns));
+	
+	let (result_df, reports) = cast(df, CastOptions {
+		columns,
+		on_error: args.on_error.clone().into(),
+		decimal_comma: args.decimal_comma,
+	}).await?;
+	
+	report_failures(&reports, &args.common);
+	
+	Ok(result_df)
+}
+
+/// Warns about values that could not be converted; only reached with --on-error null or keep-as-string.
+fn report_failures(reports: &[CastReport], common: &CommonArgs) {
+	for report in reports {
+		if report.failed > 0 {
+			let outcome = if report.kept_as_string { "column kept as Utf8" } else { "set to null" };
+			eprintln!(
+				"Warning: {} value(s) in '{}' could not be converted to {} ({}), e.g. {}",
+				report.failed, report.column, report.target, outcome,
+				report.examples.iter().map(|e| format!("'{}'", e)).collect::<Vec<_>>().join(", ")
+			);
+		} else {
+			common.log_if_verbose(&format!("Cast '{}' to {}", report.column, report.target));
+		}
+	}
+}
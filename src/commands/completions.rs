@@ -0,0 +1,191 @@
+use clap::{Args, Command, CommandFactory};
+use clap_complete::Shell;
+use std::io::Write;
+use std::path::PathBuf;
+use crate::cli::Cli;
+use crate::error::NailResult;
+use crate::utils::io::read_data;
+
+/// Argument ids whose values are column names of the input file.
+const COLUMN_ARG_IDS: &[&str] = &["columns", "key", "stratify_by", "stratified_by", "sort_by", "index", "values"];
+
+/// Commands where `-c` takes expressions rather than column names.
+const EXPRESSION_COMMANDS: &[&str] = &["create"];
+
+const ZSH_REGISTRATION: &str = "if [ \"$funcstack[1]\" = \"_nail\" ]; then\n    _nail \"$@\"\nelse\n    compdef _nail nail\nfi\n";
+
+#[derive(Args, Clone)]
+pub struct CompletionsArgs {
+	#[arg(help = "Shell to generate the completion script for", value_enum, required_unless_present = "list_columns")]
+	pub shell: Option<Shell>,
+
+	#[arg(long, hide = true, value_name = "FILE", help = "Print the column names of FILE, one per line (used by the completion scripts)")]
+	pub list_columns: Option<PathBuf>,
+}
+
+pub async fn execute(args: CompletionsArgs) -> NailResult<()> {
+	if let Some(path) = &args.list_columns {
+		// Completion must never print errors into the user's prompt
+		if let Ok(df) = read_data(path).await {
+			for field in df.schema().fields() {
+				println!("{}", field.name());
+			}
+		}
+		return Ok(());
+	}
+
+	let Some(shell) = args.shell else {
+		return Ok(());
+	};
+
+	let mut cmd = Cli::command();
+	let mut script = Vec::new();
+	clap_complete::generate(shell, &mut cmd, "nail", &mut script);
+
+	let options = column_options(&cmd);
+	match shell {
+		Shell::Bash => script.extend_from_slice(bash_columns(&options).as_bytes()),
+		Shell::Zsh => {
+			// Our wrapper takes over registration, so drop the generated one
+			let generated = String::from_utf8_lossy(&script).replace(ZSH_REGISTRATION, "");
+			script = generated.into_bytes();
+			script.extend_from_slice(zsh_columns(&options).as_bytes());
+		},
+		Shell::Fish => script.extend_from_slice(fish_columns(&options).as_bytes()),
+		_ => {},
+	}
+
+	std::io::stdout().write_all(&script)?;
+	Ok(())
+}
+
+/// Column-valued options per subcommand: (command, short flag, long flag).
+fn column_options(root: &Command) -> Vec<(String, Option<char>, String)> {
+	root.get_subcommands()
+		.filter(|sub| !EXPRESSION_COMMANDS.contains(&sub.get_name()))
+		.flat_map(|sub| {
+			sub.get_arguments()
+				.filter(|arg| COLUMN_ARG_IDS.contains(&arg.get_id().as_str()))
+				.filter_map(|arg| Some((sub.get_name().to_string(), arg.get_short(), arg.get_long()?.to_string())))
+				.collect::<Vec<_>>()
+		})
+		.collect()
+}
+
+fn case_patterns(options: &[(String, Option<char>, String)]) -> String {
+	options.iter()
+		.flat_map(|(command, short, long)| {
+			let mut patterns = vec![format!("{}:--{}", command, long)];
+			if let Some(short) = short {
+				patterns.push(format!("{}:-{}", command, short));
+			}
+			patterns
+		})
+		.collect::<Vec<_>>()
+		.join("|")
+}
+
+fn bash_columns(options: &[(String, Option<char>, String)]) -> String {
+	format!(r#"
+_nail_columns() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "${{COMP_WORDS[1]}}:${{prev}}" in
+        {patterns})
+            local word input=""
+            for word in "${{COMP_WORDS[@]:2}}"; do
+                if [[ -f "$word" ]]; then input="$word"; break; fi
+            done
+            if [[ -n "$input" ]]; then
+                local prefix="" IFS=$'\n'
+                [[ "$cur" == *,* ]] && prefix="${{cur%,*}},"
+                COMPREPLY=( $(compgen -P "$prefix" -W "$(nail completions --list-columns "$input" 2>/dev/null)" -- "${{cur##*,}}") )
+                compopt -o nospace 2>/dev/null
+                return 0
+            fi
+            ;;
+    esac
+    _nail "$@"
+}}
+
+if [[ "${{BASH_VERSINFO[0]}}" -eq 4 && "${{BASH_VERSINFO[1]}}" -ge 4 || "${{BASH_VERSINFO[0]}}" -gt 4 ]]; then
+    complete -F _nail_columns -o nosort -o bashdefault -o default nail
+else
+    complete -F _nail_columns -o bashdefault -o default nail
+fi
+"#, patterns = case_patterns(options))
+}
+
+fn zsh_columns(options: &[(String, Option<char>, String)]) -> String {
+	format!(r#"
+_nail_columns() {{
+    case "${{words[2]}}:${{words[CURRENT-1]}}" in
+        {patterns})
+            local word input=""
+            for word in "${{(@)words[3,-1]}}"; do
+                if [[ -f "$word" ]]; then input="$word"; break; fi
+            done
+            if [[ -n "$input" ]]; then
+                local -a columns
+                columns=("${{(@f)$(nail completions --list-columns "$input" 2>/dev/null)}}")
+                compset -P '*,'
+                compadd -S '' -a columns
+                return
+            fi
+            ;;
+    esac
+    _nail "$@"
+}}
+
+compdef _nail_columns nail
+if [ "$funcstack[1]" = "_nail" ]; then
+    _nail_columns "$@"
+fi
+"#, patterns = case_patterns(options))
+}
+
+fn fish_columns(options: &[(String, Option<char>, String)]) -> String {
+	let mut script = String::from(r#"
+function __nail_columns
+    set -l token (commandline -ct)
+    set -l prefix (string replace -r '[^,]*$' '' -- $token)
+    for word in (commandline -opc)[3..-1]
+        if test -f $word
+            for column in (nail completions --list-columns $word 2>/dev/null)
+                echo $prefix$column
+            end
+            return
+        end
+    end
+end
+"#);
+	for (command, short, long) in options {
+		let short = short.map(|s| format!(" -s {}", s)).unwrap_or_default();
+		script.push_str(&format!(
+			"complete -c nail -n \"__fish_seen_subcommand_from {}\"{} -l {} -f -r -a \"(__nail_columns)\"\n",
+			command, short, long
+		));
+	}
+	script
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_command_tree_is_valid() {
+		// Completion and man page generation build every subcommand, so clashes would panic there
+		Cli::command().debug_assert();
+	}
+
+	#[test]
+	fn test_column_options() {
+		let options = column_options(&Cli::command());
+		let has = |command: &str, long: &str| options.iter().any(|(c, _, l)| c == command && l == long);
+		assert!(has("select", "columns"));
+		assert!(has("sort", "column"));
+		assert!(has("merge", "key"));
+		assert!(has("sample", "stratify-by"));
+		assert!(!options.iter().any(|(c, _, _)| c == "create"));
+	}
+}
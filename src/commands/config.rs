@@ -0,0 +1,71 @@
+use clap::{Args, CommandFactory, Subcommand};
+use std::path::Path;
+use crate::cli::Cli;
+use crate::error::{NailError, NailResult};
+use crate::utils::config::Config;
+
+#[derive(Args, Clone)]
+pub struct ConfigArgs {
+	#[command(subcommand)]
+	pub action: ConfigAction,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ConfigAction {
+	#[command(about = "Show the effective settings and where each one comes from")]
+	Show {
+		#[arg(help = "Only show the settings that apply to this command")]
+		command: Option<String>,
+	},
+}
+
+pub async fn execute(args: ConfigArgs) -> NailResult<()> {
+	match args.action {
+		ConfigAction::Show { command } => show(command.as_deref()),
+	}
+}
+
+fn show(command: Option<&str>) -> NailResult<()> {
+	let root = Cli::command();
+	if let Some(name) = command {
+		if root.find_subcommand(name).is_none() {
+			return Err(NailError::InvalidArgument(format!("Unknown command '{}'", name)));
+		}
+	}
+
+	let config = Config::load()?;
+	println!("User config:    {}", describe_path(config.user_path.as_deref()));
+	println!("Project config: {}", describe_path(config.project_path.as_deref()));
+	println!();
+
+	let env: Vec<(String, String)> = std::env::vars().collect();
+	let settings = config.settings(&root, command, &env);
+	if settings.is_empty() {
+		println!("No settings; built-in defaults apply.");
+		return Ok(());
+	}
+
+	let entries: Vec<(String, String)> = settings.iter()
+		.map(|s| {
+			let key = match &s.command {
+				Some(command) => format!("{}.{}", command, s.key),
+				None => s.key.clone(),
+			};
+			(format!("{} = {}", key, s.value), s.source.to_string())
+		})
+		.collect();
+	let width = entries.iter().map(|(entry, _)| entry.len()).max().unwrap_or(0);
+	for (entry, source) in entries {
+		println!("{:<width$}  # {}", entry, source, width = width);
+	}
+
+	Ok(())
+}
+
+fn describe_path(path: Option<&Path>) -> String {
+	match path {
+		Some(path) if path.is_file() => path.display().to_string(),
+		Some(path) => format!("{} (not found)", path.display()),
+		None => "(none)".to_string(),
+	}
+}
@@ -1,8 +1,11 @@
 use clap::Args;
 use std::path::PathBuf;
-use crate::error::NailResult;
-use crate::utils::io::{read_data, write_data};
-use crate::utils::{detect_file_format};
+use crate::error::{NailError, NailResult};
+use crate::cli::ReadArgs;
+use crate::utils::io::{read_data_with_options, write_data, ReadOptions};
+use crate::utils::{detect_file_format, FileFormat};
+use crate::utils::encoding::transcode_file;
+use crate::utils::output::explain_plan;
 
 #[derive(Args, Clone)]
 pub struct ConvertArgs {
@@ -23,8 +26,20 @@ pub struct ConvertArgs {
 	#[arg(short, long, help = "Enable verbose output")]
 	pub verbose: bool,
 	
-	#[arg(short, long, help = "Number of parallel jobs")]
-	pub jobs: Option<usize>,
+	#[command(flatten)]
+	pub read: ReadArgs,
+	
+	#[arg(long, help = "Text encoding for CSV/TSV output (default: utf-8)")]
+	pub output_encoding: Option<String>,
+	
+	#[arg(long, help = "Print the logical and physical plan instead of converting", conflicts_with = "explain_analyze")]
+	pub explain: bool,
+	
+	#[arg(long, help = "Run the conversion plan and print it with per-operator metrics instead of writing the output")]
+	pub explain_analyze: bool,
+	
+	#[arg(long = "where", value_name = "CONDITION", help = "Only convert rows matching CONDITION (same syntax as 'filter -c')")]
+	pub where_clause: Option<String>,
 }
 
 pub async fn execute(args: ConvertArgs) -> NailResult<()> {
@@ -39,7 +54,21 @@ pub async fn execute(args: ConvertArgs) -> NailResult<()> {
 		eprintln!("Input format: {:?}, Output format: {:?}", input_format, output_format);
 	}
 	
-	let df = read_data(&args.input).await?;
+	if args.output_encoding.is_some() && !matches!(output_format, FileFormat::Csv | FileFormat::Tsv) {
+		return Err(NailError::InvalidArgument(format!(
+			"--output-encoding only applies to CSV and TSV output, not {:?}", output_format
+		)));
+	}
+	
+	let read_options = ReadOptions {
+		where_clause: args.where_clause.clone(),
+		..args.read.read_options(args.verbose)
+	};
+	let df = read_data_with_options(&args.input, &read_options).await?;
+	
+	if args.explain || args.explain_analyze {
+		return explain_plan(&df, args.explain_analyze).await;
+	}
 	
 	let rows = df.clone().count().await?;
 	let cols = df.schema().fields().len();
@@ -49,6 +78,13 @@ pub async fn execute(args: ConvertArgs) -> NailResult<()> {
 	
 	write_data(&df, &args.output, Some(&output_format)).await?;
 	
+	if let Some(encoding) = &args.output_encoding {
+		transcode_file(&args.output, encoding)?;
+		if args.verbose {
+			eprintln!("Output encoded as {}", encoding);
+		}
+	}
+	
 	if args.verbose {
 		eprintln!("Conversion completed successfully");
 	}
@@ -104,7 +140,11 @@ mod tests {
 			output: output_path.clone(),
 			random: None,
 			verbose: false,
-			jobs: None,
+			read: ReadArgs::default(),
+			output_encoding: None,
+			explain: false,
+			explain_analyze: false,
+			where_clause: None,
 		};
 		
 		// Execute conversion
@@ -142,7 +182,11 @@ mod tests {
 			output: output_path.clone(),
 			random: None,
 			verbose: false,
-			jobs: None,
+			read: ReadArgs::default(),
+			output_encoding: None,
+			explain: false,
+			explain_analyze: false,
+			where_clause: None,
 		};
 		
 		// Execute conversion
@@ -177,7 +221,11 @@ mod tests {
 			output: output_path.clone(),
 			random: None,
 			verbose: true, // Test verbose output
-			jobs: Some(2), // Test jobs parameter
+			read: ReadArgs { jobs: Some(2), ..ReadArgs::default() }, // Test jobs parameter
+			output_encoding: None,
+			explain: false,
+			explain_analyze: false,
+			where_clause: None,
 		};
 		
 		// Execute conversion
@@ -205,7 +253,11 @@ mod tests {
 			output: output_path.clone(),
 			random: None,
 			verbose: false,
-			jobs: None,
+			read: ReadArgs::default(),
+			output_encoding: None,
+			explain: false,
+			explain_analyze: false,
+			where_clause: None,
 		};
 		
 		// Execute conversion should fail
@@ -229,7 +281,11 @@ mod tests {
 			output: output_path.clone(),
 			random: Some(42), // Test random seed
 			verbose: false,
-			jobs: None,
+			read: ReadArgs::default(),
+			output_encoding: None,
+			explain: false,
+			explain_analyze: false,
+			where_clause: None,
 		};
 		
 		// Execute conversion
@@ -268,7 +324,11 @@ mod tests {
 			output: output_path.clone(),
 			random: None,
 			verbose: false,
-			jobs: None,
+			read: ReadArgs::default(),
+			output_encoding: None,
+			explain: false,
+			explain_analyze: false,
+			where_clause: None,
 		};
 		
 		// Execute conversion should work with empty data
@@ -1,9 +1,9 @@
 use clap::Args;
-use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
+use crate::error::NailResult;
+use crate::ops::{self, correlations, CorrelationsOptions};
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
 use crate::cli::CommonArgs;
-use crate::utils::stats::{calculate_correlations, CorrelationType, select_columns_by_pattern};
 use clap::ValueEnum;
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -35,99 +35,38 @@ pub struct CorrelationsArgs {
 	pub digits: usize,
 }
 
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum CorrelationType {
+	Pearson,
+	Kendall,
+	Spearman,
+}
+
+impl From<CorrelationType> for ops::CorrelationType {
+	fn from(correlation_type: CorrelationType) -> Self {
+		match correlation_type {
+			CorrelationType::Pearson => ops::CorrelationType::Pearson,
+			CorrelationType::Kendall => ops::CorrelationType::Kendall,
+			CorrelationType::Spearman => ops::CorrelationType::Spearman,
+		}
+	}
+}
+
 pub async fn execute(args: CorrelationsArgs) -> NailResult<()> {
     args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
     
-    let df = read_data(&args.common.input).await?;
-    let schema = df.schema();
-    
-    let target_columns = if let Some(col_spec) = &args.columns {
-        let selected = select_columns_by_pattern(schema.clone().into(), col_spec)?;
-        
-        let mut numeric_columns = Vec::new();
-        let mut non_numeric_columns = Vec::new();
-        
-        for col_name in &selected {
-            if let Ok(field) = schema.field_with_name(None, col_name) {
-                match field.data_type() {
-                    datafusion::arrow::datatypes::DataType::Int64 | 
-                    datafusion::arrow::datatypes::DataType::Float64 | 
-                    datafusion::arrow::datatypes::DataType::Int32 | 
-                    datafusion::arrow::datatypes::DataType::Float32 |
-                    datafusion::arrow::datatypes::DataType::Int16 |
-                    datafusion::arrow::datatypes::DataType::Int8 |
-                    datafusion::arrow::datatypes::DataType::UInt64 |
-                    datafusion::arrow::datatypes::DataType::UInt32 |
-                    datafusion::arrow::datatypes::DataType::UInt16 |
-                    datafusion::arrow::datatypes::DataType::UInt8 => {
-                        numeric_columns.push(col_name.clone());
-                    },
-                    _ => {
-                        non_numeric_columns.push((col_name.clone(), field.data_type().clone()));
-                    }
-                }
-            }
-        }
-        
-        if !non_numeric_columns.is_empty() {
-            let non_numeric_info: Vec<String> = non_numeric_columns.iter()
-                .map(|(name, dtype)| format!("'{}' ({:?})", name, dtype))
-                .collect();
-            return Err(NailError::Statistics(
-                format!("Correlation requires numeric columns only. Non-numeric columns found: {}. Available numeric columns: {:?}", 
-                    non_numeric_info.join(", "), numeric_columns)
-            ));
-        }
-        
-        if numeric_columns.len() < 2 {
-            return Err(NailError::Statistics(
-                format!("Need at least 2 numeric columns for correlation. Found {} numeric columns: {:?}", 
-                    numeric_columns.len(), numeric_columns)
-            ));
-        }
-        
-        numeric_columns
-    } else {
-        let numeric_columns: Vec<String> = schema.fields().iter()
-            .filter(|f| matches!(f.data_type(), 
-                datafusion::arrow::datatypes::DataType::Int64 | 
-                datafusion::arrow::datatypes::DataType::Float64 | 
-                datafusion::arrow::datatypes::DataType::Int32 | 
-                datafusion::arrow::datatypes::DataType::Float32 |
-                datafusion::arrow::datatypes::DataType::Int16 |
-                datafusion::arrow::datatypes::DataType::Int8 |
-                datafusion::arrow::datatypes::DataType::UInt64 |
-                datafusion::arrow::datatypes::DataType::UInt32 |
-                datafusion::arrow::datatypes::DataType::UInt16 |
-                datafusion::arrow::datatypes::DataType::UInt8
-            ))
-            .map(|f| f.name().clone())
-            .collect();
-            
-        if numeric_columns.len() < 2 {
-            return Err(NailError::Statistics(
-                format!("Need at least 2 numeric columns for correlation. Found {} numeric columns: {:?}", 
-                    numeric_columns.len(), numeric_columns)
-            ));
-        }
-        
-        numeric_columns
-    };
+    let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
     
-    args.common.log_if_verbose(&format!("Computing {:?} correlations for {} numeric columns: {:?}", 
-        args.correlation_type, target_columns.len(), target_columns));
+    args.common.log_if_verbose(&format!("Computing {:?} correlations", args.correlation_type));
     args.common.log_if_verbose(&format!("Using {} decimal places for correlation values", args.digits));
     
-    let include_tests = args.stats_tests.as_ref().map(|v| !v.is_empty()).unwrap_or(false);
-
-let corr_df = calculate_correlations(
-        &df, 
-        &target_columns, 
-        &args.correlation_type,
-        args.correlation_matrix,
-        include_tests,
-        args.digits
-    ).await?;
+    let corr_df = correlations(df, CorrelationsOptions {
+        columns: args.columns.clone(),
+        correlation_type: args.correlation_type.clone().into(),
+        matrix: args.correlation_matrix,
+        include_tests: args.stats_tests.as_ref().is_some_and(|tests| !tests.is_empty()),
+        digits: args.digits,
+    }).await?;
     
     let output_handler = OutputHandler::new(&args.common);
     output_handler.handle_output(&corr_df, "correlations").await?;
@@ -208,11 +147,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: Some("x,y,z".to_string()),
             correlation_type: CorrelationType::Pearson,
@@ -239,11 +174,9 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
                 verbose: true, // Test verbose output
-                jobs: Some(2),
-                random: None,
+                read: crate::cli::ReadArgs { jobs: Some(2), ..Default::default() },
+                ..CommonArgs::default()
             },
             columns: None, // Use all numeric columns
             correlation_type: CorrelationType::Pearson,
@@ -270,11 +203,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: None,
             correlation_type: CorrelationType::Pearson,
@@ -301,11 +230,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: None,
             correlation_type: CorrelationType::Spearman,
@@ -332,11 +257,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: None,
             correlation_type: CorrelationType::Kendall,
@@ -363,11 +284,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: Some("x,y,category".to_string()), // category is non-numeric
             correlation_type: CorrelationType::Pearson,
@@ -402,11 +319,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: None,
             correlation_type: CorrelationType::Pearson,
@@ -434,11 +347,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: Some("x,y".to_string()), // Select specific columns
             correlation_type: CorrelationType::Pearson,
@@ -465,11 +374,7 @@ mod tests {
         let args = CorrelationsArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None,
-                format: None,
-                verbose: false,
-                jobs: None,
-                random: None,
+                ..CommonArgs::default()
             },
             columns: None,
             correlation_type: CorrelationType::Pearson,
@@ -1,9 +1,10 @@
 use clap::Args;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::parquet_utils::{get_parquet_row_count_fast, can_use_fast_metadata};
-use crate::utils::output::OutputHandler;
+use crate::utils::output::{explain_plan, OutputHandler};
 use crate::cli::CommonArgs;
+use datafusion::functions_aggregate::expr_fn::count;
 use datafusion::prelude::*;
 
 #[derive(Args, Clone)]
@@ -15,13 +16,19 @@ pub struct CountArgs {
 pub async fn execute(args: CountArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 	
-	// Use fast metadata reading for Parquet files
-	let row_count = if can_use_fast_metadata(&args.common.input) {
+	if args.common.explain_requested() {
+		let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+		let count_df = df.aggregate(vec![], vec![count(lit(1)).alias("row_count")])?;
+		return explain_plan(&count_df, args.common.explain_analyze).await;
+	}
+	
+	// Use fast metadata reading for Parquet files unless rows are filtered
+	let row_count = if can_use_fast_metadata(&args.common.input) && args.common.where_clause.is_none() {
 		args.common.log_if_verbose("Using fast Parquet metadata for counting");
 		get_parquet_row_count_fast(&args.common.input).await?
 	} else {
 		args.common.log_if_verbose("Using DataFusion for counting");
-		let df = read_data(&args.common.input).await?;
+		let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
 		df.clone().count().await.map_err(crate::error::NailError::DataFusion)?
 	};
 	
@@ -126,11 +133,7 @@ mod tests {
         let args = CountArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
@@ -143,9 +146,7 @@ mod tests {
                 input: args.common.input.clone(),
                 output: Some(output_path.clone()),
                 format: Some(crate::cli::OutputFormat::Json),
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
@@ -167,9 +168,7 @@ mod tests {
                 input: input_path,
                 output: Some(output_path.clone()),
                 format: Some(crate::cli::OutputFormat::Json),
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
@@ -186,11 +185,7 @@ mod tests {
         let args = CountArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None, // No output file specified
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
@@ -206,11 +201,8 @@ mod tests {
         let args = CountArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
                 verbose: true, // Enable verbose mode
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
@@ -229,9 +221,7 @@ mod tests {
                 input: input_path,
                 output: Some(output_path.clone()),
                 format: Some(crate::cli::OutputFormat::Csv),
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
@@ -250,11 +240,7 @@ mod tests {
         let args = CountArgs {
             common: CommonArgs {
                 input: nonexistent_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
         };
 
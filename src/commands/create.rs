@@ -1,97 +1,64 @@
 use clap::Args;
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
 use crate::cli::CommonArgs;
-use crate::error::{NailError, NailResult};
+use crate::error::NailResult;
+use crate::ops::{create, CreateOptions};
+use crate::ops::create::parse_column_specs;
+use datafusion::prelude::DataFrame;
 
 #[derive(Args, Clone)]
 pub struct CreateArgs {
     #[command(flatten)]
     pub common: CommonArgs,
 
-    #[arg(short = 'c', long = "column", 
-          help = "Column creation specs (name=expression), comma-separated.\n\
-                  Supported operators:\n\
-                  • Arithmetic: +, -, *, / (e.g., 'total=price*quantity')\n\
-                  • Comparison: >, < (e.g., 'is_expensive=price>100')\n\
-                  • Parentheses: () for grouping (e.g., 'result=(a+b)*c')\n\
-                  • Column references: Use existing column names\n\
-                  • Constants: Numeric values (integers and floats)\n\
-                  Examples:\n\
-                  • Simple: 'doubled=value*2'\n\
-                  • Complex: 'profit=(revenue-cost)/revenue*100'\n\
-                  • Conditional: 'high_value=amount>1000'")]
-    pub columns: Option<String>,
-
-    #[arg(short = 'r', long = "row", help = "Row filter expression")]
+    #[arg(short = 'c', long = "column", value_name = "NAME=EXPR", action = clap::ArgAction::Append,
+          help = "Column to create as name=expression; repeat -c for several columns.\n\
+                  Expressions are SQL and may use columns created by earlier -c options.\n\
+                  • Arithmetic and comparison: 'total=price*quantity', 'is_big=amount >= 1000'\n\
+                  • Conditional: \"tier=if(score > 80, 'gold', 'std')\", 'case when ... then ... else ... end'\n\
+                  • Nulls: 'amount=coalesce(amount, 0)', 'nullif(x, 0)'\n\
+                  • Strings: upper, lower, trim, length, substr, concat, ||, replace, split_part\n\
+                  • Math: abs, round(x, 2), floor, ceil, sqrt, power, ln, log10, %\n\
+                  • Dates: date_part('year', d), date_trunc('month', ts), to_date, to_timestamp, now()\n\
+                  • Regex: regexp_like(s, 'p'), regexp_replace(s, 'p', 'r'), regexp_match(s, 'p')\n\
+                  Several specs may share one -c when separated by ', name='")]
+    pub columns: Vec<String>,
+
+    #[arg(short = 'r', long = "row", alias = "row-filter", help = "Row filter expression (SQL), applied before the columns are created")]
     pub row_filter: Option<String>,
 }
 
 pub async fn execute(args: CreateArgs) -> NailResult<()> {
     args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 
-    let ctx = crate::utils::create_context_with_jobs(args.common.jobs).await?;
-    let df = read_data(&args.common.input).await?;
-    ctx.register_table("t", df.clone().into_view())?;
-    
-    let mut result_df = df;
+    let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+    let result_df = transform(df, &args).await?;
 
-    // Apply row filter if specified
+    let output_handler = OutputHandler::new(&args.common);
+    output_handler.handle_output(&result_df, "create").await?;
+
+    Ok(())
+}
+
+/// Applies the create operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &CreateArgs) -> NailResult<DataFrame> {
     if let Some(row_expr) = &args.row_filter {
         args.common.log_if_verbose(&format!("Applying row filter: {}", row_expr));
-        // Use SQL for row filtering
-        let filter_sql = format!("SELECT * FROM t WHERE {}", row_expr);
-        result_df = ctx.sql(&filter_sql).await
-            .map_err(|e| NailError::InvalidArgument(format!("Invalid row filter expression: {}", e)))?;
-        // Re-register the filtered data - need to deregister first
-        ctx.deregister_table("t")?;
-        ctx.register_table("t", result_df.clone().into_view())?;
     }
 
-    // Parse and apply column creation specs
-    if let Some(col_specs) = &args.columns {
-        let mut column_map = Vec::new();
-        for pair in col_specs.split(',') {
-            let parts: Vec<&str> = pair.split('=').collect();
-            if parts.len() != 2 {
-                return Err(NailError::InvalidArgument(format!("Invalid column spec: {}", pair)));
-            }
-            let name = parts[0].trim();
-            let expr = parts[1].trim();
-            column_map.push((name.to_string(), expr.to_string()));
-        }
-
-        args.common.log_if_verbose(&format!("Creating columns: {:?}", column_map));
-
-        // Validate column names don't already exist
-        let existing_columns: Vec<String> = result_df.schema().fields().iter()
-            .map(|f| f.name().clone()).collect();
-        
-        for (name, _) in &column_map {
-            if existing_columns.contains(name) {
-                return Err(NailError::InvalidArgument(format!("Column '{}' already exists", name)));
-            }
-        }
-
-        // Build SQL select list
-        let mut select_list = vec!["*".to_string()];
-        
-        for (name, expr_str) in &column_map {
-            select_list.push(format!("({}) AS \"{}\"", expr_str, name));
-        }
-        
-        let sql = format!("SELECT {} FROM t", select_list.join(", "));
-        args.common.log_if_verbose(&format!("Executing SQL: {}", sql));
-        
-        result_df = ctx.sql(&sql).await
-            .map_err(|e| NailError::InvalidArgument(format!("Invalid column expression: {}", e)))?;
+    let mut columns = Vec::new();
+    for spec in &args.columns {
+        columns.extend(parse_column_specs(spec)?);
+    }
+    if !columns.is_empty() {
+        args.common.log_if_verbose(&format!("Creating columns: {:?}", columns));
     }
 
-    // Write or display result
-    let output_handler = OutputHandler::new(&args.common);
-    output_handler.handle_output(&result_df, "create").await?;
-
-    Ok(())
+    create(df, CreateOptions {
+        columns,
+        row_filter: args.row_filter.clone(),
+    }).await
 }
 
 #[cfg(test)]
@@ -158,12 +125,9 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("doubled=value*2".to_string()),
+            columns: vec!["doubled=value*2".to_string()],
             row_filter: None,
         };
 
@@ -187,12 +151,9 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("high_value=value>300".to_string()),
+            columns: vec!["high_value=value>300".to_string()],
             row_filter: None,
         };
 
@@ -215,12 +176,9 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("doubled=value*2,id_plus_one=id+1".to_string()),
+            columns: vec!["doubled=value*2,id_plus_one=id+1".to_string()],
             row_filter: None,
         };
 
@@ -245,12 +203,9 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("doubled=value*2".to_string()),
+            columns: vec!["doubled=value*2".to_string()],
             row_filter: Some("id>2".to_string()),
         };
 
@@ -270,13 +225,9 @@ mod tests {
         let args = CreateArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("id=value*2".to_string()), // 'id' already exists
+            columns: vec!["id=value*2".to_string()], // 'id' already exists
             row_filter: None,
         };
 
@@ -292,13 +243,9 @@ mod tests {
         let args = CreateArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("invalid_spec".to_string()), // Missing '='
+            columns: vec!["invalid_spec".to_string()], // Missing '='
             row_filter: None,
         };
 
@@ -314,13 +261,9 @@ mod tests {
         let args = CreateArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("test=nonexistent_column*2".to_string()),
+            columns: vec!["test=nonexistent_column*2".to_string()],
             row_filter: None,
         };
 
@@ -336,13 +279,9 @@ mod tests {
         let args = CreateArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
-            columns: Some("doubled=value*2".to_string()),
+            columns: vec!["doubled=value*2".to_string()],
             row_filter: Some("nonexistent_column>5".to_string()),
         };
 
@@ -0,0 +1,171 @@
+use clap::Args;
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{self, dates, DateFormatReport, DatesOptions};
+
+#[derive(Args, Clone)]
+pub struct DatesArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(short, long, help = "Columns to parse or transform")]
+	pub columns: String,
+
+	#[arg(long = "date-format", value_name = "FORMAT", help = "Format of string columns, e.g. 'dd/mm/yyyy', 'yyyy-mm-dd HH:mm:ss' or '%d.%m.%Y %H:%M'.\n\
+		Detected from the values when omitted")]
+	pub date_format: Option<String>,
+
+	#[arg(long, value_enum, default_value = "auto", help = "Result type for parsed strings (auto: date unless the format has a time)")]
+	pub to: DateTarget,
+
+	#[arg(long, value_name = "TZ", help = "Time zone of naive timestamps (default: UTC), e.g. 'Europe/Paris'")]
+	pub from_tz: Option<String>,
+
+	#[arg(long, value_name = "TZ", help = "Convert timestamps to this time zone")]
+	pub tz: Option<String>,
+
+	#[arg(long, value_enum, value_name = "UNIT", help = "Truncate values to the start of the unit")]
+	pub truncate: Option<DateUnit>,
+
+	#[arg(long, value_enum, value_delimiter = ',', value_name = "PARTS",
+		help = "Extract parts into new columns <column>_<part> (comma-separated)")]
+	pub extract: Vec<DatePart>,
+
+	#[arg(long, help = "Write results to new columns <column><SUFFIX> instead of replacing the columns")]
+	pub suffix: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum DateTarget {
+	/// Date when the format has no time of day, timestamp otherwise.
+	#[default]
+	Auto,
+	Date,
+	Timestamp,
+}
+
+impl From<DateTarget> for ops::DateTarget {
+	fn from(date_target: DateTarget) -> Self {
+		match date_target {
+			DateTarget::Auto => ops::DateTarget::Auto,
+			DateTarget::Date => ops::DateTarget::Date,
+			DateTarget::Timestamp => ops::DateTarget::Timestamp,
+		}
+	}
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DateUnit {
+	Second,
+	Minute,
+	Hour,
+	Day,
+	/// Monday of the ISO week.
+	Week,
+	Month,
+	Quarter,
+	Year,
+}
+
+impl From<DateUnit> for ops::DateUnit {
+	fn from(date_unit: DateUnit) -> Self {
+		match date_unit {
+			DateUnit::Second => ops::DateUnit::Second,
+			DateUnit::Minute => ops::DateUnit::Minute,
+			DateUnit::Hour => ops::DateUnit::Hour,
+			DateUnit::Day => ops::DateUnit::Day,
+			DateUnit::Week => ops::DateUnit::Week,
+			DateUnit::Month => ops::DateUnit::Month,
+			DateUnit::Quarter => ops::DateUnit::Quarter,
+			DateUnit::Year => ops::DateUnit::Year,
+		}
+	}
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DatePart {
+	Year,
+	Quarter,
+	Month,
+	Day,
+	Hour,
+	Minute,
+	Second,
+	/// ISO 8601 week number.
+	Week,
+	/// ISO weekday, 1 = Monday to 7 = Sunday.
+	Weekday,
+	#[value(name = "day_of_year", alias = "day-of-year")]
+	DayOfYear,
+	#[value(name = "is_weekend", alias = "is-weekend")]
+	IsWeekend,
+	/// Seconds since 1970-01-01 UTC.
+	Epoch,
+}
+
+impl From<DatePart> for ops::DatePart {
+	fn from(date_part: DatePart) -> Self {
+		match date_part {
+			DatePart::Year => ops::DatePart::Year,
+			DatePart::Quarter => ops::DatePart::Quarter,
+			DatePart::Month => ops::DatePart::Month,
+			DatePart::Day => ops::DatePart::Day,
+			DatePart::Hour => ops::DatePart::Hour,
+			DatePart::Minute => ops::DatePart::Minute,
+			DatePart::Second => ops::DatePart::Second,
+			DatePart::Week => ops::DatePart::Week,
+			DatePart::Weekday => ops::DatePart::Weekday,
+			DatePart::DayOfYear => ops::DatePart::DayOfYear,
+			DatePart::IsWeekend => ops::DatePart::IsWeekend,
+			DatePart::Epoch => ops::DatePart::Epoch,
+		}
+	}
+}
+
+pub async fn execute(args: DatesArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "dates").await?;
+
+	Ok(())
+}
+
+/// Applies the date operations to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &DatesArgs) -> NailResult<DataFrame> {
+	let options = DatesOptions {
+		columns: args.columns.clone(),
+		format: args.date_format.clone(),
+		to: args.to.clone().into(),
+		from_tz: args.from_tz.clone(),
+		tz: args.tz.clone(),
+		truncate: args.truncate.map(Into::into),
+		extract: args.extract.iter().copied().map(Into::into).collect(),
+		suffix: args.suffix.clone(),
+	};
+	args.common.log_if_verbose(&format!("Applying date operations: {:?}", options));
+
+	let (result_df, reports) = dates(df, options).await?;
+	report_detected_formats(&reports, &args.common);
+
+	Ok(result_df)
+}
+
+fn report_detected_formats(reports: &[DateFormatReport], common: &CommonArgs) {
+	for report in reports {
+		if report.ambiguous {
+			eprintln!(
+				"Warning: dates in column '{}' could be month-first or day-first; assuming month-first ({}). Use --date-format to choose",
+				report.column, report.format
+			);
+		} else {
+			common.log_if_verbose(&format!("Detected format {} for column '{}'", report.format, report.column));
+		}
+	}
+}
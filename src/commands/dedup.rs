@@ -1,10 +1,10 @@
 use clap::Args;
 use datafusion::prelude::*;
-use std::collections::HashSet;
 use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
 use crate::cli::CommonArgs;
+use crate::ops::{dedup, DedupMode, DedupOptions};
 
 #[derive(Args, Clone)]
 pub struct DedupArgs {
@@ -27,22 +27,37 @@ pub struct DedupArgs {
 pub async fn execute(args: DedupArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
 	
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "dedup").await?;
+	
+	Ok(())
+}
+
+/// Applies the dedup operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &DedupArgs) -> NailResult<DataFrame> {
 	if !args.row_wise && !args.col_wise {
 		return Err(NailError::InvalidArgument(
 			"Must specify either --row-wise or --col-wise".to_string()
 		));
 	}
 	
-	let result_df = if args.row_wise {
+	let mode = if args.row_wise {
 		args.common.log_if_verbose("Removing duplicate rows");
-		deduplicate_rows(&df, args.columns.as_deref(), &args.keep, args.common.jobs).await?
+		DedupMode::Rows
 	} else {
 		args.common.log_if_verbose("Removing duplicate columns");
-		deduplicate_columns(&df, &args.keep).await?
+		DedupMode::Columns
 	};
 	
+	let result_df = dedup(df.clone(), DedupOptions {
+		mode,
+		columns: args.columns.clone(),
+		keep: args.keep.clone(),
+	}).await?;
+	
 	if args.common.verbose {
 		let original_rows = df.clone().count().await?;
 		let new_rows = result_df.clone().count().await?;
@@ -58,228 +73,9 @@ pub async fn execute(args: DedupArgs) -> NailResult<()> {
 		}
 	}
 	
-	let output_handler = OutputHandler::new(&args.common);
-	output_handler.handle_output(&result_df, "dedup").await?;
-	
-	Ok(())
-}
-
-async fn deduplicate_rows(df: &DataFrame, columns: Option<&str>, keep: &str, jobs: Option<usize>) -> NailResult<DataFrame> {
-	let ctx = crate::utils::create_context_with_jobs(jobs).await?;
-	let table_name = "temp_table";
-	ctx.register_table(table_name, df.clone().into_view())?;
-	
-	let schema = df.schema();
-	let dedup_cols = if let Some(col_spec) = columns {
-		// Convert DFSchemaRef to DFSchemaRef for pattern matching
-		crate::utils::stats::select_columns_by_pattern(schema.clone().into(), col_spec)?
-	} else {
-		schema.fields().iter().map(|f| f.name().clone()).collect()
-	};
-	
-	if dedup_cols.is_empty() {
-		// Nothing to dedup on, return original
-		return Ok(df.clone());
-	}
-	
-	let keep_first = match keep {
-		"first" => true,
-		"last" => false,
-		_ => return Err(NailError::InvalidArgument("keep must be 'first' or 'last'".to_string())),
-	};
-	
-	// We need a column to define original order. If none exists, we add one.
-	// Use ROW_NUMBER() OVER() to add row numbers
-	let df_with_rn = ctx.sql(&format!(
-		"SELECT *, ROW_NUMBER() OVER() as __row_num_for_dedup__ FROM {}", 
-		table_name
-	)).await?;
-	
-	// Register the dataframe with row numbers
-	ctx.register_table("temp_with_rn", df_with_rn.clone().into_view())?;
-	
-	// Build the window function expression
-	let partition_by_cols: Vec<String> = dedup_cols.iter()
-		.map(|c| format!("\"{}\"", c))
-		.collect();
-	let partition_by_str = partition_by_cols.join(", ");
-	
-	// Order by determines which row is 'first' or 'last'
-	let order_by = if keep_first {
-		"__row_num_for_dedup__ ASC"
-	} else {
-		"__row_num_for_dedup__ DESC"
-	};
-	
-	// Get original column names (excluding our temp column)
-	let original_columns: Vec<String> = df.schema().fields().iter()
-		.map(|f| format!("\"{}\"", f.name()))
-		.collect();
-	let select_columns = original_columns.join(", ");
-	
-	// Build the deduplication query
-	let dedup_sql = format!(
-		"WITH ranked AS (
-			SELECT {}, 
-				   ROW_NUMBER() OVER (PARTITION BY {} ORDER BY {}) as rn
-			FROM temp_with_rn
-		)
-		SELECT {} FROM ranked WHERE rn = 1",
-		select_columns,
-		partition_by_str,
-		order_by,
-		select_columns
-	);
-	
-	let result_df = ctx.sql(&dedup_sql).await?;
 	Ok(result_df)
 }
 
-async fn deduplicate_columns(df: &DataFrame, keep: &str) -> NailResult<DataFrame> {
-	let schema = df.schema();
-	let field_names: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
-	
-	// First, check for duplicate column names
-	let mut seen_names = HashSet::new();
-	let mut name_duplicates = Vec::new();
-	
-	for field_name in &field_names {
-		if !seen_names.contains(field_name) {
-			seen_names.insert(field_name.clone());
-		} else {
-			name_duplicates.push(field_name.clone());
-		}
-	}
-	
-	// Error out if there are duplicate column names to prevent silent data loss
-	if !name_duplicates.is_empty() {
-		return Err(NailError::InvalidArgument(format!(
-			"Duplicate column names found: {:?}. This could result in data loss. \
-			Please use the 'rename' command to resolve column name conflicts before deduplicating.",
-			name_duplicates
-		)));
-	}
-	
-	// Now check for columns with identical content
-	let batches = df.clone().collect().await?;
-	if batches.is_empty() {
-		return Ok(df.clone());
-	}
-	
-	let mut unique_columns = Vec::new();
-	let mut processed_columns = HashSet::new();
-	
-	for (i, field_name) in field_names.iter().enumerate() {
-		if processed_columns.contains(field_name) {
-			continue;
-		}
-		
-		let mut duplicate_group = vec![field_name.clone()];
-		
-		// Find columns with identical content
-		for (j, other_field_name) in field_names.iter().enumerate() {
-			if i != j && !processed_columns.contains(other_field_name) {
-				if columns_have_identical_content(&batches, field_name, other_field_name)? {
-					duplicate_group.push(other_field_name.clone());
-				}
-			}
-		}
-		
-		// Choose which column to keep from the duplicate group
-		let column_to_keep = if keep == "first" {
-			duplicate_group.first().unwrap().clone()
-		} else if keep == "last" {
-			duplicate_group.last().unwrap().clone()
-		} else {
-			return Err(NailError::InvalidArgument(
-				"Keep must be either 'first' or 'last'".to_string()
-			));
-		};
-		
-		unique_columns.push(col(&column_to_keep));
-		
-		// Mark all columns in this group as processed
-		for col_name in duplicate_group {
-			processed_columns.insert(col_name);
-		}
-	}
-	
-	let result = df.clone().select(unique_columns)?;
-	Ok(result)
-}
-
-fn columns_have_identical_content(
-	batches: &[datafusion::arrow::record_batch::RecordBatch],
-	col1: &str,
-	col2: &str,
-) -> NailResult<bool> {
-	use datafusion::arrow::array::Array;
-	
-	for batch in batches {
-		let array1 = batch.column_by_name(col1)
-			.ok_or_else(|| NailError::InvalidArgument(format!("Column '{}' not found", col1)))?;
-		let array2 = batch.column_by_name(col2)
-			.ok_or_else(|| NailError::InvalidArgument(format!("Column '{}' not found", col2)))?;
-		
-		// Check if arrays have the same length
-		if array1.len() != array2.len() {
-			return Ok(false);
-		}
-		
-		// Check if arrays have the same data type
-		if array1.data_type() != array2.data_type() {
-			return Ok(false);
-		}
-		
-		// Compare all values
-		for i in 0..array1.len() {
-			let val1_is_null = array1.is_null(i);
-			let val2_is_null = array2.is_null(i);
-			
-			if val1_is_null != val2_is_null {
-				return Ok(false);
-			}
-			
-			if !val1_is_null {
-				// Both are non-null, compare values
-				match array1.data_type() {
-					datafusion::arrow::datatypes::DataType::Int64 => {
-						let arr1 = array1.as_any().downcast_ref::<datafusion::arrow::array::Int64Array>().unwrap();
-						let arr2 = array2.as_any().downcast_ref::<datafusion::arrow::array::Int64Array>().unwrap();
-						if arr1.value(i) != arr2.value(i) {
-							return Ok(false);
-						}
-					},
-					datafusion::arrow::datatypes::DataType::Float64 => {
-						let arr1 = array1.as_any().downcast_ref::<datafusion::arrow::array::Float64Array>().unwrap();
-						let arr2 = array2.as_any().downcast_ref::<datafusion::arrow::array::Float64Array>().unwrap();
-						if (arr1.value(i) - arr2.value(i)).abs() > f64::EPSILON {
-							return Ok(false);
-						}
-					},
-					datafusion::arrow::datatypes::DataType::Utf8 => {
-						let arr1 = array1.as_any().downcast_ref::<datafusion::arrow::array::StringArray>().unwrap();
-						let arr2 = array2.as_any().downcast_ref::<datafusion::arrow::array::StringArray>().unwrap();
-						if arr1.value(i) != arr2.value(i) {
-							return Ok(false);
-						}
-					},
-					_ => {
-						// For other types, use string comparison
-						let val1 = format!("{:?}", array1.slice(i, 1));
-						let val2 = format!("{:?}", array2.slice(i, 1));
-						if val1 != val2 {
-							return Ok(false);
-						}
-					}
-				}
-			}
-		}
-	}
-	
-	Ok(true)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,10 +172,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
@@ -406,10 +199,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
@@ -436,10 +226,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
@@ -466,10 +253,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: false,
             col_wise: true,
@@ -493,11 +277,8 @@ mod tests {
         let args = DedupArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
                 verbose: true,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
@@ -516,11 +297,7 @@ mod tests {
         let args = DedupArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: false, // Neither mode enabled
             col_wise: false,
@@ -540,11 +317,7 @@ mod tests {
         let args = DedupArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
@@ -590,10 +363,7 @@ mod tests {
             common: CommonArgs {
                 input: file_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
@@ -620,10 +390,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             row_wise: true,
             col_wise: false,
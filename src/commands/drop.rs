@@ -1,11 +1,10 @@
 use clap::Args;
 use datafusion::prelude::*;
-use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
-use crate::utils::column::resolve_column_name;
-use crate::commands::select::{select_columns_by_pattern, parse_row_specification};
 use crate::cli::CommonArgs;
+use crate::ops::{drop, DropOptions};
 
 #[derive(Args, Clone)]
 pub struct DropArgs {
@@ -15,41 +14,15 @@ pub struct DropArgs {
 	#[arg(short, long, help = "Column names or regex patterns to drop (comma-separated)")]
 	pub columns: Option<String>,
 	
-	#[arg(short, long, help = "Row numbers/ranges to drop (e.g., 1,3,5-10) OR column conditions (e.g., 'name=John', 'age>25', 'status!=active,score<=50'). Without -o/--output, acts as dry run showing remaining records")]
+	#[arg(short, long, help = "Row numbers/ranges to drop (e.g., 1,3,5-10) OR column conditions (e.g., 'name=John', 'age>25', 'not active', 'status!=active,score<=50'). Without -o/--output, acts as dry run showing remaining records")]
 	pub rows: Option<String>,
 }
 
 pub async fn execute(args: DropArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
-	let mut result_df = df;
-	
-	if let Some(col_spec) = &args.columns {
-		let schema = result_df.schema();
-		let columns_to_drop = select_columns_by_pattern(schema.clone().into(), col_spec)?;
-		
-		args.common.log_if_verbose(&format!("Dropping {} columns: {:?}", columns_to_drop.len(), columns_to_drop));
-		
-		let remaining_columns: Vec<Expr> = result_df.schema().fields().iter()
-			.filter(|f| !columns_to_drop.contains(f.name()))
-			.map(|f| Expr::Column(datafusion::common::Column::new(None::<String>, f.name())))
-			.collect();
-		
-		result_df = result_df.select(remaining_columns)?;
-	}
-	
-	if let Some(row_spec) = &args.rows {
-		// Check if it's a column condition or row indices
-		if is_column_condition(row_spec) {
-			args.common.log_if_verbose(&format!("Dropping rows based on column conditions: {}", row_spec));
-			result_df = drop_rows_by_conditions(&result_df, row_spec, args.common.jobs).await?;
-		} else {
-			let row_indices = parse_row_specification(row_spec)?;
-			args.common.log_if_verbose(&format!("Dropping {} rows", row_indices.len()));
-			result_df = drop_rows_by_indices(&result_df, &row_indices, args.common.jobs).await?;
-		}
-	}
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
 	
 	let output_handler = OutputHandler::new(&args.common);
 	output_handler.handle_output(&result_df, "drop").await?;
@@ -57,104 +30,19 @@ pub async fn execute(args: DropArgs) -> NailResult<()> {
 	Ok(())
 }
 
-async fn drop_rows_by_indices(df: &DataFrame, indices: &[usize], jobs: Option<usize>) -> NailResult<DataFrame> {
-	let ctx = crate::utils::create_context_with_jobs(jobs).await?;
-	
-	let table_name = "temp_table";
-	ctx.register_table(table_name, df.clone().into_view())?;
-	
-	let indices_str = indices.iter()
-		.map(|&i| (i + 1).to_string())
-		.collect::<Vec<_>>()
-		.join(",");
-	
-	// Get the original column names and quote them to preserve case
-	let original_columns: Vec<String> = df.schema().fields().iter()
-		.map(|f| format!("\"{}\"", f.name()))
-		.collect();
-	
-	let sql = format!(
-		"SELECT {} FROM (SELECT {}, ROW_NUMBER() OVER() as rn FROM {}) WHERE rn NOT IN ({})",
-		original_columns.join(", "),
-		original_columns.join(", "),
-		table_name, 
-		indices_str
-	);
-	
-	let result = ctx.sql(&sql).await?;
-	Ok(result)
-}
-
-fn is_column_condition(spec: &str) -> bool {
-	// Check if the spec contains any comparison operators
-	spec.contains('=') || spec.contains('<') || spec.contains('>')
-}
-
-async fn drop_rows_by_conditions(df: &DataFrame, conditions: &str, jobs: Option<usize>) -> NailResult<DataFrame> {
-	let ctx = crate::utils::create_context_with_jobs(jobs).await?;
-	let table_name = "temp_table";
-	ctx.register_table(table_name, df.clone().into_view())?;
-	
-	let schema = df.schema().clone().into();
-	let mut filter_conditions = Vec::new();
-	
-	for condition in conditions.split(',') {
-		let condition = condition.trim();
-		let filter_expr = parse_condition_with_schema(condition, &schema).await?;
-		filter_conditions.push(filter_expr);
+/// Applies the drop operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &DropArgs) -> NailResult<DataFrame> {
+	if let Some(col_spec) = &args.columns {
+		args.common.log_if_verbose(&format!("Dropping columns: {}", col_spec));
 	}
-	
-	// Combine all conditions with AND (rows matching ALL conditions will be dropped)
-	let combined_filter = filter_conditions.into_iter()
-		.reduce(|acc, expr| acc.and(expr))
-		.unwrap();
-	
-	// Apply NOT to the combined filter to drop matching rows
-	let drop_filter = combined_filter.not();
-	
-	let result = ctx.table(table_name).await?.filter(drop_filter)?;
-	Ok(result)
-}
-
-async fn parse_condition_with_schema(condition: &str, schema: &datafusion::common::DFSchemaRef) -> NailResult<Expr> {
-	let operators = [">=", "<=", "!=", "=", ">", "<"];
-	
-	for op in &operators {
-		if let Some(pos) = condition.find(op) {
-			let column_name_input = condition[..pos].trim();
-			let value_str = condition[pos + op.len()..].trim();
-			
-			// Use the centralized column resolution utility
-			let actual_column_name = resolve_column_name(schema, column_name_input)?;
-			
-			let value_expr = if let Ok(int_val) = value_str.parse::<i64>() {
-				lit(int_val)
-			} else if let Ok(float_val) = value_str.parse::<f64>() {
-				lit(float_val)
-			} else if value_str.eq_ignore_ascii_case("true") {
-				lit(true)
-			} else if value_str.eq_ignore_ascii_case("false") {
-				lit(false)
-			} else {
-				lit(value_str)
-			};
-			
-			// Use quoted column name to preserve case sensitivity
-			let column_expr = Expr::Column(datafusion::common::Column::new(None::<String>, &actual_column_name));
-			
-			return Ok(match *op {
-				"=" => column_expr.eq(value_expr),
-				"!=" => column_expr.not_eq(value_expr),
-				">" => column_expr.gt(value_expr),
-				">=" => column_expr.gt_eq(value_expr),
-				"<" => column_expr.lt(value_expr),
-				"<=" => column_expr.lt_eq(value_expr),
-				_ => unreachable!(),
-			});
-		}
+	if let Some(row_spec) = &args.rows {
+		args.common.log_if_verbose(&format!("Dropping rows: {}", row_spec));
 	}
 	
-	Err(NailError::InvalidArgument(format!("Invalid condition: {}", condition)))
+	drop(df, DropOptions {
+		columns: args.columns.clone(),
+		rows: args.rows.clone(),
+	}).await
 }
 
 #[cfg(test)]
@@ -221,10 +109,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("value,category".to_string()),
             rows: None,
@@ -252,10 +137,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("val.*,cat.*".to_string()),
             rows: None,
@@ -281,10 +163,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("1,3,5".to_string()),
@@ -309,10 +188,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("2-4".to_string()),
@@ -337,10 +213,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("value".to_string()),
             rows: Some("1,5".to_string()),
@@ -363,11 +236,8 @@ mod tests {
         let args = DropArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
                 verbose: true,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("value".to_string()),
             rows: None,
@@ -387,10 +257,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: None,
@@ -417,10 +284,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("10,20".to_string()), // Indices beyond the data
@@ -445,10 +309,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("name".to_string()),
             rows: None,
@@ -473,10 +334,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("3".to_string()),
@@ -498,11 +356,7 @@ mod tests {
         let args = DropArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("nonexistent_column".to_string()),
             rows: None,
@@ -524,10 +378,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: Some("".to_string()), // Empty column specification
             rows: None,
@@ -552,10 +403,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("".to_string()), // Empty row specification
@@ -576,10 +424,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("1,10,3".to_string()), // Mix of valid and out-of-range indices
@@ -604,10 +449,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("name=Alice".to_string()), // Drop rows where name equals Alice
@@ -632,10 +474,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("category!=A".to_string()), // Drop rows where category is not A
@@ -660,10 +499,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("value>250".to_string()), // Drop rows where value > 250
@@ -688,10 +524,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("id<=2".to_string()), // Drop rows where id <= 2
@@ -716,10 +549,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("category=A,value>=300".to_string()), // Drop rows where category=A AND value>=300
@@ -741,11 +571,7 @@ mod tests {
         let args = DropArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None, // No output file = dry run
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("name=Alice".to_string()),
@@ -763,11 +589,7 @@ mod tests {
         let args = DropArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("nonexistent_column=value".to_string()),
@@ -784,11 +606,7 @@ mod tests {
         let args = DropArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             columns: None,
             rows: Some("invalid_format_without_operator".to_string()),
@@ -797,18 +615,4 @@ mod tests {
         let result = execute(args).await;
         assert!(result.is_err());
     }
-
-    #[test]
-    fn test_is_column_condition() {
-        assert!(is_column_condition("name=Alice"));
-        assert!(is_column_condition("age>25"));
-        assert!(is_column_condition("score<=100"));
-        assert!(is_column_condition("status!=active"));
-        assert!(is_column_condition("value>=50"));
-        assert!(is_column_condition("count<10"));
-        
-        assert!(!is_column_condition("1,2,3"));
-        assert!(!is_column_condition("5-10"));
-        assert!(!is_column_condition("just_text"));
-    }
 }
\ No newline at end of file
@@ -0,0 +1,45 @@
+use clap::Args;
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{explode, ExplodeOptions};
+
+#[derive(Args, Clone)]
+pub struct ExplodeArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(short, long, help = "List columns to explode; several columns are exploded in parallel")]
+	pub columns: String,
+
+	#[arg(long, value_name = "NAME", help = "Add a column with each element's 0-based position in its list")]
+	pub position: Option<String>,
+
+	#[arg(long, help = "Drop rows whose lists are null or empty (by default they are kept with nulls)")]
+	pub drop_empty: bool,
+}
+
+pub async fn execute(args: ExplodeArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "explode").await?;
+
+	Ok(())
+}
+
+/// Applies the explode operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &ExplodeArgs) -> NailResult<DataFrame> {
+	args.common.log_if_verbose(&format!("Exploding columns: {}", args.columns));
+
+	explode(df, ExplodeOptions {
+		columns: args.columns.clone(),
+		position: args.position.clone(),
+		drop_empty: args.drop_empty,
+	}).await
+}
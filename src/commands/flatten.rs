@@ -0,0 +1,46 @@
+use clap::Args;
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{flatten, FlattenOptions};
+
+#[derive(Args, Clone)]
+pub struct FlattenArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(short, long, help = "Struct columns to flatten (default: all struct columns)")]
+	pub columns: Option<String>,
+
+	#[arg(long, default_value = ".", help = "Separator between parent and child names")]
+	pub separator: String,
+
+	#[arg(long, value_name = "N", help = "Number of nesting levels to expand (default: all)")]
+	pub depth: Option<usize>,
+}
+
+pub async fn execute(args: FlattenArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "flatten").await?;
+
+	Ok(())
+}
+
+/// Applies the flatten operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &FlattenArgs) -> NailResult<DataFrame> {
+	let result_df = flatten(df, FlattenOptions {
+		columns: args.columns.clone(),
+		separator: args.separator.clone(),
+		depth: args.depth,
+	}).await?;
+	args.common.log_if_verbose(&format!("Flattened to {} columns", result_df.schema().fields().len()));
+
+	Ok(result_df)
+}
@@ -1,11 +1,10 @@
 use clap::Args;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
-use crate::utils::output::OutputHandler;
-use crate::utils::stats::select_columns_by_pattern;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::{explain_plan, OutputHandler};
 use crate::cli::CommonArgs;
+use crate::ops::{frequency, FrequencyOptions};
 use datafusion::prelude::*;
-use datafusion::functions_aggregate::expr_fn::count;
 use arrow::array::Array;
 use datafusion::arrow::array::*;
 use datafusion::arrow::datatypes::DataType;
@@ -35,35 +34,19 @@ pub async fn execute(args: FrequencyArgs) -> NailResult<()> {
     args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
     args.common.log_if_verbose(&format!("Analyzing frequency for columns: {}", args.columns));
 
-    let df = read_data(&args.common.input).await?;
+    let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
     
-    // Parse and resolve column names using the standard utility
-    let schema = df.schema().clone().into();
-    let resolved_column_names = select_columns_by_pattern(schema, &args.columns)?;
-    
-    if resolved_column_names.is_empty() {
-        return Err(crate::error::NailError::InvalidArgument(
-            "No column names provided".to_string()
-        ));
-    }
+    let frequency_df = frequency(df, FrequencyOptions { columns: args.columns.clone() }).await?;
+    let resolved_column_names: Vec<String> = frequency_df.schema().fields().iter()
+        .map(|f| f.name().clone())
+        .filter(|name| name != "frequency")
+        .collect();
 
     args.common.log_if_verbose(&format!("Computing frequency table for {} column(s)", resolved_column_names.len()));
 
-    // Build the frequency query using resolved column names
-    let mut group_by_cols = Vec::new();
-    
-    for col_name in &resolved_column_names {
-        // Use qualified column name to avoid case sensitivity issues on Linux
-        group_by_cols.push(col(format!("\"{}\"", col_name)));
+    if args.common.explain_requested() {
+        return explain_plan(&frequency_df, args.common.explain_analyze).await;
     }
-    
-    // Execute the frequency query
-    let frequency_df = df
-        .aggregate(group_by_cols, vec![count(lit(1)).alias("frequency")])?
-        .sort(vec![
-            // Sort by frequency descending
-            col("frequency").sort(false, true),
-        ])?;
 
     // Calculate sum of all frequencies for percentage calculation
     let sum_freq_df = frequency_df
@@ -1,9 +1,11 @@
 use clap::Args;
 
 use crate::error::NailResult;
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
 use crate::cli::CommonArgs;
+use crate::ops::{head, HeadOptions};
+use datafusion::prelude::DataFrame;
 
 #[derive(Args, Clone)]
 pub struct HeadArgs {
@@ -17,13 +19,17 @@ pub struct HeadArgs {
 pub async fn execute(args: HeadArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
-	let limited_df = df.limit(0, Some(args.number))?;
-	
-	args.common.log_if_verbose(&format!("Displaying first {} rows", args.number));
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let limited_df = transform(df, &args).await?;
 	
 	let output_handler = OutputHandler::new(&args.common);
 	output_handler.handle_output(&limited_df, "head").await?;
 	
 	Ok(())
+}
+
+/// Applies the head operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &HeadArgs) -> NailResult<DataFrame> {
+	args.common.log_if_verbose(&format!("Keeping first {} rows", args.number));
+	head(df, HeadOptions { number: args.number }).await
 }
\ No newline at end of file
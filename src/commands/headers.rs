@@ -1,8 +1,8 @@
 use clap::Args;
 use regex::Regex;
 use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
-use crate::utils::output::OutputHandler;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::{explain_plan, OutputHandler};
 use crate::cli::CommonArgs;
 use datafusion::prelude::*;
 
@@ -18,7 +18,11 @@ pub struct HeadersArgs {
 pub async fn execute(args: HeadersArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading schema from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	if args.common.explain_requested() {
+		return explain_plan(&df, args.common.explain_analyze).await;
+	}
+	
 	let schema = df.schema();
 	let field_names: Vec<String> = schema.fields().iter()
 		.map(|f| f.name().clone())
@@ -1,9 +1,10 @@
 use clap::Args;
 use datafusion::prelude::*;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
 use crate::cli::CommonArgs;
+use crate::ops::{add_id, IdOptions};
 
 #[derive(Args, Clone)]
 pub struct IdArgs {
@@ -23,14 +24,8 @@ pub struct IdArgs {
 pub async fn execute(args: IdArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
-	
-	let result_df = if args.create {
-		args.common.log_if_verbose(&format!("Creating ID column '{}' with prefix '{}'", args.id_col_name, args.prefix));
-		add_id_column(&df, &args.id_col_name, &args.prefix, args.common.jobs).await?
-	} else {
-		df
-	};
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
 	
 	let output_handler = OutputHandler::new(&args.common);
 	output_handler.handle_output(&result_df, "id").await?;
@@ -38,39 +33,17 @@ pub async fn execute(args: IdArgs) -> NailResult<()> {
 	Ok(())
 }
 
-async fn add_id_column(df: &DataFrame, col_name: &str, prefix: &str, jobs: Option<usize>) -> NailResult<DataFrame> {
-	let ctx = crate::utils::create_context_with_jobs(jobs).await?;
-	let table_name = "temp_table";
-	ctx.register_table(table_name, df.clone().into_view())?;
-	
-	// Check if column already exists
-	let schema = df.schema();
-	if schema.field_with_name(None, col_name).is_ok() {
-		return Err(crate::error::NailError::InvalidArgument(
-			format!("Column '{}' already exists. Use --id-col-name to specify a different name.", col_name)
-		));
+/// Applies the id operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &IdArgs) -> NailResult<DataFrame> {
+	if !args.create {
+		return Ok(df);
 	}
 	
-	let id_col = if prefix.is_empty() {
-		"ROW_NUMBER() OVER()".to_string()
-	} else {
-		format!("CONCAT('{}', ROW_NUMBER() OVER())", prefix)
-	};
-	
-	let columns: Vec<String> = df.schema().fields().iter()
-		.map(|f| format!("\"{}\"", f.name()))
-		.collect();
-	
-	let sql = format!(
-		"SELECT {} as \"{}\", {} FROM {}",
-		id_col,
-		col_name,
-		columns.join(", "),
-		table_name
-	);
-	
-	let result = ctx.sql(&sql).await?;
-	Ok(result)
+	args.common.log_if_verbose(&format!("Creating ID column '{}' with prefix '{}'", args.id_col_name, args.prefix));
+	add_id(df, IdOptions {
+		column: args.id_col_name.clone(),
+		prefix: args.prefix.clone(),
+	}).await
 }
 
 #[cfg(test)]
@@ -128,10 +101,7 @@ mod tests {
 			common: CommonArgs {
 				input: input_path,
 				output: Some(output_path.clone()),
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "id".to_string(),
@@ -161,10 +131,7 @@ mod tests {
 			common: CommonArgs {
 				input: input_path,
 				output: Some(output_path.clone()),
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "row_".to_string(),
@@ -191,10 +158,7 @@ mod tests {
 			common: CommonArgs {
 				input: input_path,
 				output: Some(output_path.clone()),
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "".to_string(), // Empty prefix
@@ -220,10 +184,7 @@ mod tests {
 			common: CommonArgs {
 				input: input_path,
 				output: Some(output_path.clone()),
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: false, // Don't create ID column
 			prefix: "id".to_string(),
@@ -267,11 +228,7 @@ mod tests {
 		let args = IdArgs {
 			common: CommonArgs {
 				input: file_path,
-				output: None,
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "id".to_string(),
@@ -290,11 +247,8 @@ mod tests {
 		let args = IdArgs {
 			common: CommonArgs {
 				input: input_path,
-				output: None,
-				format: None,
-				random: None,
 				verbose: true,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "test_".to_string(),
@@ -315,10 +269,8 @@ mod tests {
 			common: CommonArgs {
 				input: input_path,
 				output: Some(output_path.clone()),
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: Some(2), // Test with specific job count
+				read: crate::cli::ReadArgs { jobs: Some(2), ..Default::default() }, // Test with specific job count
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "parallel_".to_string(),
@@ -362,10 +314,7 @@ mod tests {
 			common: CommonArgs {
 				input: file_path,
 				output: Some(output_path.clone()),
-				format: None,
-				random: None,
-				verbose: false,
-				jobs: None,
+				..CommonArgs::default()
 			},
 			create: true,
 			prefix: "id".to_string(),
@@ -0,0 +1,92 @@
+use clap::Args;
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{self, json_extract, JsonExtractOptions, JsonExtractReport};
+use crate::ops::json_extract::split_paths;
+
+#[derive(Args, Clone)]
+pub struct JsonExtractArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(short, long, help = "String column holding JSON documents")]
+	pub column: String,
+
+	#[arg(long, value_name = "PATHS", required_unless_present = "infer", conflicts_with = "infer",
+		help = "Paths to extract into columns <column>.<path>, comma-separated, e.g. '$.user.id,$.items[0].sku'")]
+	pub paths: Option<String>,
+
+	#[arg(long, help = "Infer the fields from the documents and expand every nested object into columns")]
+	pub infer: bool,
+
+	#[arg(long, value_name = "N", default_value = "1000", help = "Number of documents used to infer column types")]
+	pub sample: usize,
+
+	#[arg(long, value_enum, default_value = "fail", help = "Invalid JSON or values not matching the inferred types: fail, or leave the row's columns null")]
+	pub on_error: InvalidJsonPolicy,
+
+	#[arg(long, help = "Keep the JSON column next to the extracted columns")]
+	pub keep: bool,
+}
+
+/// What to do with values that are not valid JSON or do not fit the inferred types.
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum InvalidJsonPolicy {
+	/// Stop with an error showing the invalid values.
+	#[default]
+	Fail,
+	/// Leave the extracted columns null for those rows.
+	Null,
+}
+
+impl From<InvalidJsonPolicy> for ops::InvalidJsonPolicy {
+	fn from(invalid_json_policy: InvalidJsonPolicy) -> Self {
+		match invalid_json_policy {
+			InvalidJsonPolicy::Fail => ops::InvalidJsonPolicy::Fail,
+			InvalidJsonPolicy::Null => ops::InvalidJsonPolicy::Null,
+		}
+	}
+}
+
+pub async fn execute(args: JsonExtractArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "json-extract").await?;
+
+	Ok(())
+}
+
+/// Applies the JSON extraction to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &JsonExtractArgs) -> NailResult<DataFrame> {
+	let options = JsonExtractOptions {
+		column: args.column.clone(),
+		paths: args.paths.as_deref().map(split_paths).unwrap_or_default(),
+		infer: args.infer,
+		sample: args.sample,
+		on_error: args.on_error.clone().into(),
+		keep: args.keep,
+	};
+	args.common.log_if_verbose(&format!("Extracting JSON with {:?}", options));
+
+	let (result_df, report) = json_extract(df, options).await?;
+	report_invalid(&report, &args.column);
+
+	Ok(result_df)
+}
+
+/// Warns about rows left null; only reached with --on-error null.
+fn report_invalid(report: &JsonExtractReport, column: &str) {
+	if report.invalid > 0 {
+		eprintln!(
+			"Warning: {} value(s) in '{}' could not be extracted (set to null), e.g. {}",
+			report.invalid, column, report.examples.join("; ")
+		);
+	}
+}
@@ -0,0 +1,35 @@
+use clap::{Args, CommandFactory};
+use std::io::Write;
+use std::path::PathBuf;
+use crate::cli::Cli;
+use crate::error::NailResult;
+
+#[derive(Args, Clone)]
+pub struct ManpageArgs {
+	#[arg(short, long, help = "Directory to write nail.1 and one page per subcommand to (prints nail.1 to stdout by default)")]
+	pub output_dir: Option<PathBuf>,
+
+	#[arg(short, long, help = "Enable verbose output")]
+	pub verbose: bool,
+}
+
+pub async fn execute(args: ManpageArgs) -> NailResult<()> {
+	let cmd = Cli::command();
+
+	match &args.output_dir {
+		Some(dir) => {
+			std::fs::create_dir_all(dir)?;
+			clap_mangen::generate_to(cmd, dir)?;
+			if args.verbose {
+				eprintln!("Man pages written to {}", dir.display());
+			}
+		},
+		None => {
+			let mut page = Vec::new();
+			clap_mangen::Man::new(cmd).render(&mut page)?;
+			std::io::stdout().write_all(&page)?;
+		},
+	}
+
+	Ok(())
+}
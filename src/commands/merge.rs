@@ -1,10 +1,12 @@
 use clap::Args;
 use datafusion::prelude::*;
 use std::path::PathBuf;
-use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
+use crate::error::NailResult;
+use crate::utils::io::{read_data_with_options, ReadOptions};
 use crate::utils::output::OutputHandler;
 use crate::cli::CommonArgs;
+use crate::ops::{merge, MergeOptions};
+use crate::ops::merge::parse_key_mapping;
 
 #[derive(Args, Clone)]
 pub struct MergeArgs {
@@ -31,8 +33,8 @@ pub async fn execute(args: MergeArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading left table from: {}", args.common.input.display()));
 	args.common.log_if_verbose(&format!("Reading right table from: {}", args.right.display()));
 	
-	let left_df = read_data(&args.common.input).await?;
-	let right_df = read_data(&args.right).await?;
+	let left_df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let right_df = read_data_with_options(&args.right, &ReadOptions { rejects_file: None, where_clause: None, ..args.common.read_options() }).await?;
 	
 	let join_type = if args.left_join {
 		JoinType::Left
@@ -42,47 +44,15 @@ pub async fn execute(args: MergeArgs) -> NailResult<()> {
 		JoinType::Inner
 	};
 	
-	let (left_key, right_key) = if let Some(key_mapping) = &args.key_mapping {
-		parse_key_mapping(key_mapping)?
-	} else if let Some(key) = &args.key {
-		// Handle case-insensitive key matching
-		let left_schema = left_df.schema();
-		let right_schema = right_df.schema();
-		
-		let actual_left_key = left_schema.fields().iter()
-			.find(|f| f.name().to_lowercase() == key.to_lowercase())
-			.map(|f| f.name().clone())
-			.ok_or_else(|| {
-				let available_cols: Vec<String> = left_schema.fields().iter()
-					.map(|f| f.name().clone())
-					.collect();
-				NailError::ColumnNotFound(format!(
-					"Join key '{}' not found in left table. Available columns: {:?}", 
-					key, available_cols
-				))
-			})?;
-			
-		let actual_right_key = right_schema.fields().iter()
-			.find(|f| f.name().to_lowercase() == key.to_lowercase())
-			.map(|f| f.name().clone())
-			.ok_or_else(|| {
-				let available_cols: Vec<String> = right_schema.fields().iter()
-					.map(|f| f.name().clone())
-					.collect();
-				NailError::ColumnNotFound(format!(
-					"Join key '{}' not found in right table. Available columns: {:?}", 
-					key, available_cols
-				))
-			})?;
-			
-		(actual_left_key, actual_right_key)
-	} else {
-		return Err(NailError::InvalidArgument("Either --key or --key-mapping must be specified".to_string()));
-	};
+	let key_mapping = args.key_mapping.as_deref().map(parse_key_mapping).transpose()?;
 	
-	args.common.log_if_verbose(&format!("Performing {:?} join on left.{} = right.{}", join_type, left_key, right_key));
+	args.common.log_if_verbose(&format!("Performing {:?} join", join_type));
 	
-	let result_df = perform_join(&left_df, &right_df, &left_key, &right_key, join_type, args.common.jobs).await?;
+	let result_df = merge(left_df, right_df, MergeOptions {
+		join_type,
+		key: args.key.clone(),
+		key_mapping,
+	}).await?;
 	
 	let output_handler = OutputHandler::new(&args.common);
 	output_handler.handle_output(&result_df, "merge").await?;
@@ -90,62 +60,6 @@ pub async fn execute(args: MergeArgs) -> NailResult<()> {
 	Ok(())
 }
 
-fn parse_key_mapping(mapping: &str) -> NailResult<(String, String)> {
-	let parts: Vec<&str> = mapping.split('=').collect();
-	if parts.len() != 2 {
-		return Err(NailError::InvalidArgument("Key mapping must be in format 'left_col=right_col'".to_string()));
-	}
-	Ok((parts[0].trim().to_string(), parts[1].trim().to_string()))
-}
-
-async fn perform_join(
-	left_df: &DataFrame,
-	right_df: &DataFrame,
-	left_key: &str,
-	right_key: &str,
-	join_type: JoinType,
-	jobs: Option<usize>,
-) -> NailResult<DataFrame> {
-	let ctx = crate::utils::create_context_with_jobs(jobs).await?;
-	
-	ctx.register_table("left_table", left_df.clone().into_view())?;
-	ctx.register_table("right_table", right_df.clone().into_view())?;
-	
-	let left_schema = left_df.schema();
-	let right_schema = right_df.schema();
-	
-	let mut left_cols = Vec::new();
-	let mut right_cols = Vec::new();
-	
-	for field in left_schema.fields() {
-		left_cols.push(format!("l.\"{}\"", field.name()));
-	}
-	
-	for field in right_schema.fields() {
-		if field.name() != right_key {
-			right_cols.push(format!("r.\"{}\" as \"r_{}\"", field.name(), field.name()));
-		}
-	}
-	
-	let join_clause = match join_type {
-		JoinType::Inner => "INNER JOIN",
-		JoinType::Left => "LEFT JOIN",
-		JoinType::Right => "RIGHT JOIN",
-		_ => "INNER JOIN",
-	};
-	
-	let sql = format!(
-		"SELECT {} FROM left_table l {} right_table r ON l.\"{}\" = r.\"{}\"",
-		[left_cols, right_cols].concat().join(", "),
-		join_clause,
-		left_key,
-		right_key
-	);
-	
-	let result = ctx.sql(&sql).await?;
-	Ok(result)
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -156,11 +70,7 @@ mod tests {
 		let args = MergeArgs {
 			common: CommonArgs {
 				input: PathBuf::from("left.parquet"),
-				output: None,
-				format: None,
-				random: None,
-				jobs: None,
-				verbose: false,
+				..CommonArgs::default()
 			},
 			right: PathBuf::from("right.parquet"),
 			left_join: false,
@@ -184,8 +94,9 @@ mod tests {
 				output: Some(PathBuf::from("merged.parquet")),
 				format: Some(crate::cli::OutputFormat::Parquet),
 				random: Some(123),
-				jobs: Some(8),
+				read: crate::cli::ReadArgs { jobs: Some(8), ..Default::default() },
 				verbose: true,
+				..CommonArgs::default()
 			},
 			right: PathBuf::from("table2.csv"),
 			left_join: true,
@@ -199,7 +110,7 @@ mod tests {
 		assert!(!args.right_join);
 		assert_eq!(args.key, None);
 		assert_eq!(args.key_mapping, Some("user_id=id".to_string()));
-		assert_eq!(args.common.jobs, Some(8));
+		assert_eq!(args.common.read.jobs, Some(8));
 		assert!(args.common.verbose);
 	}
 
@@ -208,11 +119,7 @@ mod tests {
 		let args = MergeArgs {
 			common: CommonArgs {
 				input: PathBuf::from("data.json"),
-				output: None,
-				format: None,
-				random: None,
-				jobs: None,
-				verbose: false,
+				..CommonArgs::default()
 			},
 			right: PathBuf::from("lookup.json"),
 			left_join: false,
@@ -228,47 +135,12 @@ mod tests {
 		assert_eq!(args.key_mapping, None);
 	}
 
-	#[test]
-	fn test_parse_key_mapping_valid() {
-		let result = parse_key_mapping("left_col=right_col");
-		assert!(result.is_ok());
-		let (left, right) = result.unwrap();
-		assert_eq!(left, "left_col");
-		assert_eq!(right, "right_col");
-	}
-
-	#[test]
-	fn test_parse_key_mapping_with_spaces() {
-		let result = parse_key_mapping("  left_col  =  right_col  ");
-		assert!(result.is_ok());
-		let (left, right) = result.unwrap();
-		assert_eq!(left, "left_col");
-		assert_eq!(right, "right_col");
-	}
-
-	#[test]
-	fn test_parse_key_mapping_invalid() {
-		let result = parse_key_mapping("invalid_format");
-		assert!(result.is_err());
-		assert!(result.unwrap_err().to_string().contains("Key mapping must be in format"));
-	}
-
-	#[test]
-	fn test_parse_key_mapping_empty() {
-		let result = parse_key_mapping("");
-		assert!(result.is_err());
-	}
-
 	#[test]
 	fn test_merge_args_clone() {
 		let args = MergeArgs {
 			common: CommonArgs {
 				input: PathBuf::from("test.parquet"),
-				output: None,
-				format: None,
-				random: None,
-				jobs: None,
-				verbose: false,
+				..CommonArgs::default()
 			},
 			right: PathBuf::from("right.parquet"),
 			left_join: true,
@@ -18,7 +18,7 @@ pub struct MetadataArgs {
     pub common: CommonArgs,
     
     #[arg(long, help = "Show detailed schema information")]
-    pub schema: bool,
+    pub schema_info: bool,
     
     #[arg(long, help = "Show row group information")]
     pub row_groups: bool,
@@ -29,8 +29,8 @@ pub struct MetadataArgs {
     #[arg(long, help = "Show compression information")]
     pub compression: bool,
     
-    #[arg(long, help = "Show encoding information")]
-    pub encoding: bool,
+    #[arg(long, help = "Show column encoding information")]
+    pub encodings: bool,
     
     #[arg(long, help = "Show statistics information")]
     pub statistics: bool,
@@ -43,6 +43,8 @@ pub struct MetadataArgs {
 }
 
 pub async fn execute(args: MetadataArgs) -> NailResult<()> {
+    args.common.reject_explain("metadata")?;
+    
     // Check if input file is a parquet file
     if !args.common.input.extension().map_or(false, |ext| ext == "parquet") {
         return Err(NailError::UnsupportedFormat(
@@ -50,6 +52,18 @@ pub async fn execute(args: MetadataArgs) -> NailResult<()> {
         ));
     }
     
+    // --encoding and --schema used to be this command's flags and now configure CSV/TSV input
+    if args.common.read.encoding.is_some() {
+        return Err(NailError::InvalidArgument(
+            "--encoding sets the text encoding of CSV/TSV input; use --encodings to show column encodings".to_string()
+        ));
+    }
+    if args.common.read.schema.is_some() {
+        return Err(NailError::InvalidArgument(
+            "--schema names a schema file for CSV/TSV/JSON input; use --schema-info to show schema details".to_string()
+        ));
+    }
+    
     args.common.log_if_verbose(&format!(
         "Reading metadata from: {}",
         args.common.input.display()
@@ -65,7 +79,7 @@ pub async fn execute(args: MetadataArgs) -> NailResult<()> {
     collect_basic_metadata(metadata, &mut metadata_items);
     
     // Schema information
-    if args.schema || args.all {
+    if args.schema_info || args.all {
         collect_schema_metadata(metadata, &mut metadata_items);
     }
     
@@ -85,7 +99,7 @@ pub async fn execute(args: MetadataArgs) -> NailResult<()> {
     }
     
     // Encoding information
-    if args.encoding || args.all {
+    if args.encodings || args.all {
         collect_encoding_metadata(metadata, &mut metadata_items);
     }
     
@@ -480,17 +494,13 @@ mod tests {
         let args = MetadataArgs {
             common: crate::cli::CommonArgs {
                 input: temp_file.path().to_path_buf(),
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..crate::cli::CommonArgs::default()
             },
-            schema: false,
+            schema_info: false,
             row_groups: false,
             column_chunks: false,
             compression: false,
-            encoding: false,
+            encodings: false,
             statistics: false,
             all: false,
             detailed: false,
@@ -506,17 +516,13 @@ mod tests {
         let args = MetadataArgs {
             common: crate::cli::CommonArgs {
                 input: temp_file.path().to_path_buf(),
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..crate::cli::CommonArgs::default()
             },
-            schema: false,
+            schema_info: false,
             row_groups: false,
             column_chunks: false,
             compression: false,
-            encoding: false,
+            encodings: false,
             statistics: false,
             all: true,
             detailed: true,
@@ -532,17 +538,13 @@ mod tests {
         let args = MetadataArgs {
             common: crate::cli::CommonArgs {
                 input: temp_file.path().to_path_buf(),
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..crate::cli::CommonArgs::default()
             },
-            schema: false,
+            schema_info: false,
             row_groups: false,
             column_chunks: false,
             compression: false,
-            encoding: false,
+            encodings: false,
             statistics: false,
             all: false,
             detailed: false,
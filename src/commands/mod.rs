@@ -8,12 +8,14 @@ pub mod metadata;
 pub mod preview;
 pub mod schema;
 pub mod size;
+pub mod sniff;
 pub mod tail;
 
 // Data Analysis
 pub mod correlations;
 pub mod frequency;
 pub mod outliers;
+pub mod sql;
 pub mod stats;
 
 // Data Manipulation
@@ -28,17 +30,29 @@ pub mod sort;
 
 // Data Transformation
 pub mod binning;
+pub mod cast;
+pub mod dates;
 pub mod dedup;
+pub mod explode;
+pub mod flatten;
 pub mod id;
+pub mod json_extract;
+pub mod nest;
 pub mod pivot;
 pub mod sample;
 pub mod shuffle;
+pub mod strings;
 
 // Data Combination
 pub mod append;
 pub mod merge;
 pub mod split;
 
+// Pipelines
+pub mod pipe;
+pub mod run;
+pub mod shell;
+
 // Format Conversion
 pub mod convert;
 
@@ -46,6 +60,9 @@ pub mod convert;
 pub mod optimize;
 
 // Utility
+pub mod completions;
+pub mod config;
+pub mod manpage;
 pub mod update;
 
 #[derive(Subcommand)]
@@ -56,6 +73,15 @@ pub enum Commands {
 	#[command(about = "Bin continuous variables into categories")]
 	Binning(binning::BinningArgs),
 	
+	#[command(about = "Change column types")]
+	Cast(cast::CastArgs),
+	
+	#[command(about = "Generate shell completion scripts")]
+	Completions(completions::CompletionsArgs),
+	
+	#[command(about = "Show settings from config files and NAIL_* environment variables")]
+	Config(config::ConfigArgs),
+	
 	#[command(about = "Convert between file formats")]
 	Convert(convert::ConvertArgs),
 	
@@ -68,18 +94,27 @@ pub enum Commands {
 	#[command(about = "Create new columns with expressions")]
 	Create(create::CreateArgs),
 	
+	#[command(about = "Parse dates, convert time zones, truncate and extract date parts")]
+	Dates(dates::DatesArgs),
+	
 	#[command(about = "Remove duplicate rows or columns")]
 	Dedup(dedup::DedupArgs),
 	
 	#[command(about = "Remove columns or rows")]
 	Drop(drop::DropArgs),
 	
+	#[command(about = "Turn list elements into rows")]
+	Explode(explode::ExplodeArgs),
+	
 	#[command(about = "Fill missing values")]
 	Fill(fill::FillArgs),
 	
 	#[command(about = "Filter rows by conditions")]
 	Filter(filter::FilterArgs),
 	
+	#[command(about = "Expand struct columns into one column per field")]
+	Flatten(flatten::FlattenArgs),
+	
 	#[command(about = "Calculate frequency distributions")]
 	Frequency(frequency::FrequencyArgs),
 	
@@ -92,12 +127,21 @@ pub enum Commands {
 	#[command(about = "Add unique identifier column")]
 	Id(id::IdArgs),
 	
+	#[command(about = "Extract fields from JSON string columns into typed columns")]
+	JsonExtract(json_extract::JsonExtractArgs),
+	
+	#[command(about = "Generate man pages")]
+	Manpage(manpage::ManpageArgs),
+	
 	#[command(about = "Join two datasets")]
 	Merge(merge::MergeArgs),
 	
 	#[command(about = "Show Parquet file metadata")]
 	Metadata(metadata::MetadataArgs),
 	
+	#[command(about = "Pack columns into structs and collect rows into lists by key")]
+	Nest(nest::NestArgs),
+	
 	#[command(about = "Optimize Parquet files for better performance")]
 	Optimize(optimize::OptimizeArgs),
 	
@@ -107,12 +151,18 @@ pub enum Commands {
 	#[command(about = "Create pivot tables with aggregations")]
 	Pivot(pivot::PivotArgs),
 	
+	#[command(about = "Chain commands on one input: 'filter ... | fill ... | dedup ...'")]
+	Pipe(pipe::PipeArgs),
+	
 	#[command(about = "Preview random N rows")]
 	Preview(preview::PreviewArgs),
 	
 	#[command(about = "Rename columns")]
 	Rename(rename::RenameArgs),
 	
+	#[command(about = "Run a pipeline recipe file (YAML)")]
+	Run(run::RunArgs),
+	
 	#[command(about = "Extract data samples")]
 	Sample(sample::SampleArgs),
 	
@@ -125,24 +175,87 @@ pub enum Commands {
 	#[command(about = "Select specific columns or rows")]
 	Select(select::SelectArgs),
 	
+	#[command(about = "Explore a file interactively with the table kept in memory")]
+	Shell(shell::ShellArgs),
+	
 	#[command(about = "Randomly shuffle rows")]
 	Shuffle(shuffle::ShuffleArgs),
 	
 	#[command(about = "Show data size information")]
 	Size(size::SizeArgs),
 	
+	#[command(about = "Detect CSV dialect and column types")]
+	Sniff(sniff::SniffArgs),
+	
 	#[command(about = "Sort data by columns with various strategies")]
 	Sort(sort::SortArgs),
 	
 	#[command(about = "Split data into multiple files")]
 	Split(split::SplitArgs),
 	
+	#[command(about = "Run SQL queries over one or more files")]
+	Sql(sql::SqlArgs),
+	
 	#[command(about = "Calculate descriptive statistics")]
 	Stats(stats::StatsArgs),
 	
+	#[command(about = "Clean and reshape text columns: trim, case, replace, extract, split, pad")]
+	Strings(strings::StringsArgs),
+	
 	#[command(about = "Display last N rows")]
 	Tail(tail::TailArgs),
 	
 	#[command(about = "Check for newer versions")]
 	Update(update::UpdateArgs),
 }
+
+impl Commands {
+	/// Shared arguments of the selected subcommand, if it takes them.
+	pub fn common_args(&self) -> Option<&crate::cli::CommonArgs> {
+		match self {
+			Commands::Append(args) => Some(&args.common),
+			Commands::Binning(args) => Some(&args.common),
+			Commands::Cast(args) => Some(&args.common),
+			Commands::Dates(args) => Some(&args.common),
+			Commands::Explode(args) => Some(&args.common),
+			Commands::Flatten(args) => Some(&args.common),
+			Commands::Nest(args) => Some(&args.common),
+			Commands::JsonExtract(args) => Some(&args.common),
+			Commands::Strings(args) => Some(&args.common),
+			Commands::Correlations(args) => Some(&args.common),
+			Commands::Count(args) => Some(&args.common),
+			Commands::Create(args) => Some(&args.common),
+			Commands::Dedup(args) => Some(&args.common),
+			Commands::Drop(args) => Some(&args.common),
+			Commands::Fill(args) => Some(&args.common),
+			Commands::Filter(args) => Some(&args.common),
+			Commands::Frequency(args) => Some(&args.common),
+			Commands::Head(args) => Some(&args.common),
+			Commands::Headers(args) => Some(&args.common),
+			Commands::Id(args) => Some(&args.common),
+			Commands::Merge(args) => Some(&args.common),
+			Commands::Metadata(args) => Some(&args.common),
+			Commands::Optimize(args) => Some(&args.common),
+			Commands::Outliers(args) => Some(&args.common),
+			Commands::Pivot(args) => Some(&args.common),
+			Commands::Pipe(args) => Some(&args.common),
+			Commands::Preview(args) => Some(&args.common),
+			Commands::Rename(args) => Some(&args.common),
+			Commands::Run(args) => Some(&args.common),
+			Commands::Sample(args) => Some(&args.common),
+			Commands::Schema(args) => Some(&args.common),
+			Commands::Search(args) => Some(&args.common),
+			Commands::Select(args) => Some(&args.common),
+			Commands::Shell(args) => Some(&args.common),
+			Commands::Shuffle(args) => Some(&args.common),
+			Commands::Size(args) => Some(&args.common),
+			Commands::Sniff(args) => Some(&args.common),
+			Commands::Sort(args) => Some(&args.common),
+			Commands::Split(args) => Some(&args.common),
+			Commands::Stats(args) => Some(&args.common),
+			Commands::Tail(args) => Some(&args.common),
+			Commands::Completions(_) | Commands::Config(_) | Commands::Convert(_) | Commands::Manpage(_)
+			| Commands::Sql(_) | Commands::Update(_) => None,
+		}
+	}
+}
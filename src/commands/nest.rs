@@ -0,0 +1,56 @@
+use clap::{ArgAction, Args};
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{nest, NestOptions};
+use crate::ops::nest::parse_pack_specs;
+
+#[derive(Args, Clone)]
+pub struct NestArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(long, value_name = "NAME=COLUMNS", action = ArgAction::Append,
+		help = "Pack columns into a struct column, e.g. 'item=sku,qty,price'. Repeatable")]
+	pub pack: Vec<String>,
+
+	#[arg(long, help = "Keep the packed columns next to the struct column")]
+	pub keep: bool,
+
+	#[arg(long, value_name = "KEYS", help = "Group rows by these key columns")]
+	pub by: Option<String>,
+
+	#[arg(long, value_name = "COLUMNS", requires = "by", help = "Columns to collect into lists per group (default: all non-key columns)")]
+	pub collect: Option<String>,
+
+	#[arg(long, value_name = "COLUMNS", requires = "by", help = "Order list elements by these columns (without it the element order is unspecified)")]
+	pub order_by: Option<String>,
+}
+
+pub async fn execute(args: NestArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "nest").await?;
+
+	Ok(())
+}
+
+/// Applies the nest operation to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &NestArgs) -> NailResult<DataFrame> {
+	let options = NestOptions {
+		pack: parse_pack_specs(&args.pack)?,
+		keep: args.keep,
+		by: args.by.clone(),
+		collect: args.collect.clone(),
+		order_by: args.order_by.clone(),
+	};
+	args.common.log_if_verbose(&format!("Nesting with {:?}", options));
+
+	nest(df, options).await
+}
@@ -1,7 +1,9 @@
 use std::path::Path;
 
 use crate::error::{NailError, NailResult};
-use crate::utils::io::read_data;
+use crate::utils::io::{read_data, read_data_with_options};
+use crate::utils::column::select_columns_by_pattern;
+use crate::utils::output::explain_plan;
 use crate::cli::CommonArgs;
 use clap::Args;
 use datafusion::prelude::*;
@@ -135,35 +137,20 @@ pub async fn execute(args: OptimizeArgs) -> NailResult<()> {
     };
 
     // Read the input Parquet file
-    let df = read_data(&args.common.input).await?;
+    let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
     
     let count = df.clone().count().await?;
     args.common.log_if_verbose(&format!("Input file contains {} rows", count));
 
     // Sort data if requested
     let sorted_df = if let Some(sort_cols) = &args.sort_by {
-        let columns: Vec<&str> = sort_cols.split(',').map(|s| s.trim()).collect();
-        
-        // Validate columns exist
-        let schema = df.schema();
-        for col in &columns {
-            if schema.field_with_name(None, col).is_err() {
-                let available_cols: Vec<String> = schema.fields()
-                    .iter()
-                    .map(|f| f.name().to_string())
-                    .collect();
-                return Err(NailError::ColumnNotFound(
-                    format!("Column '{}' not found. Available columns: {}", 
-                            col, available_cols.join(", "))
-                ));
-            }
-        }
+        let columns = select_columns_by_pattern(df.schema().clone().into(), sort_cols)?;
         
         args.common.log_if_verbose(&format!("Sorting data by columns: {:?}", columns));
         
         // Create sort expressions
         let sort_exprs: Vec<datafusion::logical_expr::SortExpr> = columns.iter()
-            .map(|column_name| col(*column_name).sort(true, true))
+            .map(|column_name| col(column_name.as_str()).sort(true, true))
             .collect();
         
         df.sort(sort_exprs)?
@@ -171,6 +158,10 @@ pub async fn execute(args: OptimizeArgs) -> NailResult<()> {
         df
     };
 
+    if args.common.explain_requested() {
+        return explain_plan(&sorted_df, args.common.explain_analyze).await;
+    }
+
     // Determine output path
     let output_path = args.common.output.clone().unwrap_or_else(|| {
         let stem = args.common.input.file_stem()
@@ -369,10 +360,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 6,
@@ -406,10 +394,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Gzip,
             compression_level: 3,
@@ -449,10 +434,7 @@ mod tests {
                 common: CommonArgs {
                     input: input_path,
                     output: Some(output_path.clone()),
-                    format: None,
-                    random: None,
-                    verbose: false,
-                    jobs: None,
+                    ..CommonArgs::default()
                 },
                 compression: compression.clone(),
                 compression_level: 5,
@@ -478,10 +460,8 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
                 verbose: true, // Test verbose mode
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Zstd,
             compression_level: 4,
@@ -509,11 +489,7 @@ mod tests {
         let args = OptimizeArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 10, // Invalid level
@@ -536,11 +512,7 @@ mod tests {
         let args = OptimizeArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -563,11 +535,7 @@ mod tests {
         let args = OptimizeArgs {
             common: CommonArgs {
                 input: input_path,
-                output: None,
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -590,11 +558,7 @@ mod tests {
         let args = OptimizeArgs {
             common: CommonArgs {
                 input: input_path.clone(),
-                output: None, // No output path specified
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -622,10 +586,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -650,10 +611,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -678,10 +636,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -711,10 +666,7 @@ mod tests {
             common: CommonArgs {
                 input: input_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Gzip,
             compression_level: 3,
@@ -760,10 +712,7 @@ mod tests {
             common: CommonArgs {
                 input: file_path,
                 output: Some(output_path.clone()),
-                format: None,
-                random: None,
-                verbose: false,
-                jobs: None,
+                ..CommonArgs::default()
             },
             compression: CompressionType::Snappy,
             compression_level: 5,
@@ -0,0 +1,249 @@
+use clap::{Args, Parser};
+use datafusion::prelude::DataFrame;
+use std::path::PathBuf;
+use crate::cli::{BadRowPolicy, Cli, CommonArgs};
+use crate::commands::Commands;
+use crate::error::{NailError, NailResult};
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+
+/// Stand-in input path used when parsing a step with the regular command parser.
+const STEP_INPUT: &str = "<pipeline>";
+
+/// Commands that can be chained in a pipeline.
+pub const PIPELINE_COMMANDS: &[&str] = &[
+	"cast", "create", "dates", "dedup", "drop", "explode", "fill", "filter", "flatten", "head", "id",
+	"json-extract", "nest", "rename", "sample", "select", "shuffle", "sort", "strings",
+];
+
+#[derive(Args, Clone)]
+pub struct PipeArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(help = "Steps separated by '|', e.g. 'filter -c \"age>30\" | fill --method mean | dedup --row-wise'")]
+	pub pipeline: String,
+}
+
+pub async fn execute(args: PipeArgs) -> NailResult<()> {
+	let steps = split_pipeline(&args.pipeline)?;
+	run_pipeline(&args.common, &steps, "pipe").await
+}
+
+/// Reads the input once, applies every step to the same DataFrame plan and writes only the final result.
+pub async fn run_pipeline(common: &CommonArgs, steps: &[String], operation_name: &str) -> NailResult<()> {
+	let parsed = steps.iter()
+		.map(|step| parse_step(step, common))
+		.collect::<NailResult<Vec<_>>>()?;
+
+	common.log_if_verbose(&format!("Reading data from: {}", common.input.display()));
+	let mut df = read_data_with_options(&common.input, &common.read_options()).await?;
+
+	for (idx, (step, command)) in steps.iter().zip(parsed).enumerate() {
+		common.log_if_verbose(&format!("Step {}/{}: {}", idx + 1, steps.len(), step.trim()));
+		df = apply_step(df, command).await?;
+	}
+
+	let output_handler = OutputHandler::new(common);
+	output_handler.handle_output(&df, operation_name).await?;
+
+	Ok(())
+}
+
+/// Splits a pipeline on `|` characters that are not inside quotes.
+pub fn split_pipeline(pipeline: &str) -> NailResult<Vec<String>> {
+	let mut steps = Vec::new();
+	let mut current = String::new();
+	let mut quote: Option<char> = None;
+
+	for c in pipeline.chars() {
+		match (quote, c) {
+			(Some(q), c) if c == q => {
+				quote = None;
+				current.push(c);
+			},
+			(None, '"') | (None, '\'') => {
+				quote = Some(c);
+				current.push(c);
+			},
+			(None, '|') => steps.push(std::mem::take(&mut current)),
+			_ => current.push(c),
+		}
+	}
+	if quote.is_some() {
+		return Err(NailError::InvalidArgument("Unterminated quote in pipeline".to_string()));
+	}
+	steps.push(current);
+
+	if steps.iter().any(|s| s.trim().is_empty()) {
+		return Err(NailError::InvalidArgument("Pipeline contains an empty step".to_string()));
+	}
+	Ok(steps)
+}
+
+/// Parses one step with the regular command-line parser. Reading and writing belong
+/// to the pipeline, so steps take no input file and may not set output options.
+pub fn parse_step(step: &str, pipeline_common: &CommonArgs) -> NailResult<Commands> {
+	let tokens = shlex::split(step)
+		.ok_or_else(|| NailError::InvalidArgument(format!("Cannot parse pipeline step: {}", step.trim())))?;
+	let (name, rest) = tokens.split_first()
+		.ok_or_else(|| NailError::InvalidArgument("Pipeline contains an empty step".to_string()))?;
+
+	if !PIPELINE_COMMANDS.contains(&name.as_str()) {
+		return Err(NailError::InvalidArgument(format!(
+			"'{}' cannot be used in a pipeline. Supported steps: {}",
+			name, PIPELINE_COMMANDS.join(", ")
+		)));
+	}
+
+	let argv = ["nail", name.as_str(), STEP_INPUT].into_iter()
+		.map(String::from)
+		.chain(rest.iter().cloned());
+	let mut command = Cli::try_parse_from(argv)
+		.map_err(|e| NailError::InvalidArgument(format!(
+			"Invalid pipeline step '{}': {}",
+			step.trim(),
+			e.to_string().lines().next().unwrap_or_default().trim_start_matches("error: ")
+		)))?
+		.command;
+
+	let common = step_common(&mut command)
+		.ok_or_else(|| NailError::InvalidArgument(format!("'{}' cannot be used in a pipeline", name)))?;
+	let pipeline_options = pipeline_options_set(common);
+	if !pipeline_options.is_empty() {
+		return Err(NailError::InvalidArgument(format!(
+			"Step '{}' sets {}; input, session and output options belong to the whole pipeline. \
+			Pass them to the pipeline instead, or use a filter step in place of --where",
+			step.trim(), pipeline_options.join(", ")
+		)));
+	}
+	*common = CommonArgs {
+		input: PathBuf::from(STEP_INPUT),
+		random: common.random.or(pipeline_common.random),
+		verbose: common.verbose || pipeline_common.verbose,
+		..pipeline_common.clone()
+	};
+
+	Ok(command)
+}
+
+/// Common options a step sets that only make sense once for the whole pipeline.
+/// Only --random and --verbose may be given per step.
+fn pipeline_options_set(common: &CommonArgs) -> Vec<&'static str> {
+	[
+		("-o/--output", common.output.is_some()),
+		("-f/--format", common.format.is_some()),
+		("-j/--jobs", common.read.jobs.is_some()),
+		("--on-bad-rows", common.read.on_bad_rows != BadRowPolicy::Fail),
+		("--rejects-file", common.read.rejects_file.is_some()),
+		("--encoding", common.read.encoding.is_some()),
+		("--output-encoding", common.output_encoding.is_some()),
+		("--delimiter", common.read.delimiter.is_some()),
+		("--quote", common.read.quote.is_some()),
+		("--no-header", common.read.no_header),
+		("--schema", common.read.schema.is_some()),
+		("--memory-limit", common.read.memory_limit.is_some()),
+		("--temp-dir", common.read.temp_dir.is_some()),
+		("--batch-size", common.read.batch_size.is_some()),
+		("--collect-stats", common.read.collect_stats),
+		("--no-pruning", common.read.no_pruning),
+		("--explain", common.explain),
+		("--explain-analyze", common.explain_analyze),
+		("--where", common.where_clause.is_some()),
+	]
+	.into_iter()
+	.filter(|(_, set)| *set)
+	.map(|(name, _)| name)
+	.collect()
+}
+
+fn step_common(command: &mut Commands) -> Option<&mut CommonArgs> {
+	match command {
+		Commands::Cast(args) => Some(&mut args.common),
+		Commands::Dates(args) => Some(&mut args.common),
+		Commands::Explode(args) => Some(&mut args.common),
+		Commands::Flatten(args) => Some(&mut args.common),
+		Commands::Nest(args) => Some(&mut args.common),
+		Commands::JsonExtract(args) => Some(&mut args.common),
+		Commands::Strings(args) => Some(&mut args.common),
+		Commands::Create(args) => Some(&mut args.common),
+		Commands::Dedup(args) => Some(&mut args.common),
+		Commands::Drop(args) => Some(&mut args.common),
+		Commands::Fill(args) => Some(&mut args.common),
+		Commands::Filter(args) => Some(&mut args.common),
+		Commands::Head(args) => Some(&mut args.common),
+		Commands::Id(args) => Some(&mut args.common),
+		Commands::Rename(args) => Some(&mut args.common),
+		Commands::Sample(args) => Some(&mut args.common),
+		Commands::Select(args) => Some(&mut args.common),
+		Commands::Shuffle(args) => Some(&mut args.common),
+		Commands::Sort(args) => Some(&mut args.common),
+		_ => None,
+	}
+}
+
+pub async fn apply_step(df: DataFrame, command: Commands) -> NailResult<DataFrame> {
+	match command {
+		Commands::Cast(args) => crate::commands::cast::transform(df, &args).await,
+		Commands::Dates(args) => crate::commands::dates::transform(df, &args).await,
+		Commands::Explode(args) => crate::commands::explode::transform(df, &args).await,
+		Commands::Flatten(args) => crate::commands::flatten::transform(df, &args).await,
+		Commands::Nest(args) => crate::commands::nest::transform(df, &args).await,
+		Commands::JsonExtract(args) => crate::commands::json_extract::transform(df, &args).await,
+		Commands::Strings(args) => crate::commands::strings::transform(df, &args).await,
+		Commands::Create(args) => crate::commands::create::transform(df, &args).await,
+		Commands::Dedup(args) => crate::commands::dedup::transform(df, &args).await,
+		Commands::Drop(args) => crate::commands::drop::transform(df, &args).await,
+		Commands::Fill(args) => crate::commands::fill::transform(df, &args).await,
+		Commands::Filter(args) => crate::commands::filter::transform(df, &args).await,
+		Commands::Head(args) => crate::commands::head::transform(df, &args).await,
+		Commands::Id(args) => crate::commands::id::transform(df, &args).await,
+		Commands::Rename(args) => crate::commands::rename::transform(df, &args).await,
+		Commands::Sample(args) => crate::commands::sample::transform(df, &args).await,
+		Commands::Select(args) => crate::commands::select::transform(df, &args).await,
+		Commands::Shuffle(args) => crate::commands::shuffle::transform(df, &args).await,
+		Commands::Sort(args) => crate::commands::sort::transform(df, &args).await,
+		_ => Err(NailError::InvalidArgument("Command cannot be used in a pipeline".to_string())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_split_pipeline_respects_quotes() {
+		let steps = split_pipeline("filter -c \"name!=a|b\" | fill --method mean|dedup --row-wise").unwrap();
+		assert_eq!(steps.len(), 3);
+		assert_eq!(steps[0].trim(), "filter -c \"name!=a|b\"");
+		assert_eq!(steps[2], "dedup --row-wise");
+
+		assert!(split_pipeline("filter -c 'x>1").is_err());
+		assert!(split_pipeline("head | | dedup --row-wise").is_err());
+	}
+
+	#[test]
+	fn test_parse_step() {
+		let common = CommonArgs { input: PathBuf::from("data.csv"), verbose: true, read: crate::cli::ReadArgs { jobs: Some(2), ..Default::default() }, ..CommonArgs::default() };
+
+		let command = parse_step("head -n 3", &common).unwrap();
+		match command {
+			Commands::Head(args) => {
+				assert_eq!(args.number, 3);
+				assert_eq!(args.common.input, PathBuf::from(STEP_INPUT));
+				assert!(args.common.verbose);
+				assert_eq!(args.common.read.jobs, Some(2));
+			},
+			_ => panic!("expected head step"),
+		}
+
+		let error_message = |step: &str| parse_step(step, &common).err().map(|e| e.to_string()).unwrap_or_default();
+		assert!(error_message("stats").contains("cannot be used in a pipeline"));
+		assert!(error_message("head -o out.csv").contains("output options"));
+		assert!(error_message("head --where 'age > 30'").contains("--where"));
+		assert!(error_message("sort -c age --memory-limit 1G --on-bad-rows skip").contains("--on-bad-rows, --memory-limit"));
+		assert!(error_message("select -c name --explain").contains("--explain"));
+		assert!(parse_step("sample -n 2 --random 7 -v", &common).is_ok());
+		assert!(parse_step("head --bogus", &common).is_err());
+	}
+}
@@ -3,8 +3,9 @@ use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::format::display_dataframe;
+use crate::utils::output::explain_plan;
 use crate::cli::CommonArgs;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent},
@@ -40,9 +41,12 @@ pub struct PreviewArgs {
 }
 
 pub async fn execute(args: PreviewArgs) -> NailResult<()> {
+    if args.interactive {
+        args.common.reject_explain("preview --interactive")?;
+    }
     args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
     
-    let df = read_data(&args.common.input).await?;
+    let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
     let total_rows = df.clone().count().await?;
     
     // If interactive mode is requested, handle it separately
@@ -52,6 +56,9 @@ pub async fn execute(args: PreviewArgs) -> NailResult<()> {
     
     // Non-interactive mode (original behavior)
     if total_rows <= args.number {
+        if args.common.explain_requested() {
+            return explain_plan(&df, args.common.explain_analyze).await;
+        }
         display_dataframe(&df, args.common.output.as_deref(), args.common.format.as_ref()).await?;
         return Ok(());
     }
@@ -68,7 +75,7 @@ pub async fn execute(args: PreviewArgs) -> NailResult<()> {
     
     args.common.log_if_verbose(&format!("Randomly sampling {} rows from {} total rows", args.number, total_rows));
     
-    let ctx = crate::utils::create_context_with_jobs(args.common.jobs).await?;
+    let ctx = crate::utils::create_context(&args.common.session_options()).await?;
     let table_name = "temp_table";
     ctx.register_table(table_name, df.clone().into_view())?;
     
@@ -93,6 +100,9 @@ pub async fn execute(args: PreviewArgs) -> NailResult<()> {
     args.common.log_if_verbose(&format!("Executing SQL: {}", sql));
     
     let result = ctx.sql(&sql).await?;
+    if args.common.explain_requested() {
+        return explain_plan(&result, args.common.explain_analyze).await;
+    }
     
     display_dataframe(&result, args.common.output.as_deref(), args.common.format.as_ref()).await?;
     
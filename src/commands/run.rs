@@ -0,0 +1,93 @@
+use clap::Args;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use crate::cli::CommonArgs;
+use crate::commands::pipe::run_pipeline;
+use crate::error::{NailError, NailResult};
+
+/// Stand-in input path when --input is not given and the recipe's input is used.
+const RECIPE_INPUT: &str = "<recipe>";
+
+#[derive(Args, Clone)]
+// The recipe is the positional argument, so the shared input becomes an optional --input override
+#[command(mut_arg("input", |arg| arg
+	.short('i')
+	.long("input")
+	.required(false)
+	.default_value(RECIPE_INPUT)
+	.hide_default_value(true)
+	.help("Input file (overrides the recipe)")))]
+pub struct RunArgs {
+	#[arg(help = "Recipe file (YAML) with input, output and steps")]
+	pub recipe: PathBuf,
+
+	#[command(flatten)]
+	pub common: CommonArgs,
+}
+
+/// A pipeline stored on disk. Relative paths are resolved against the recipe's directory.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Recipe {
+	pub input: Option<PathBuf>,
+	pub output: Option<PathBuf>,
+	pub steps: Vec<String>,
+}
+
+impl Recipe {
+	pub fn load(path: &Path) -> NailResult<Self> {
+		let content = std::fs::read_to_string(path)?;
+		let mut recipe: Recipe = serde_yaml::from_str(&content)
+			.map_err(|e| NailError::InvalidArgument(format!("Invalid recipe {}: {}", path.display(), e)))?;
+
+		if recipe.steps.is_empty() {
+			return Err(NailError::InvalidArgument(format!("Recipe {} has no steps", path.display())));
+		}
+
+		let base = path.parent().unwrap_or(Path::new(""));
+		recipe.input = recipe.input.map(|p| base.join(p));
+		recipe.output = recipe.output.map(|p| base.join(p));
+		Ok(recipe)
+	}
+}
+
+pub async fn execute(args: RunArgs) -> NailResult<()> {
+	let recipe = Recipe::load(&args.recipe)?;
+
+	let mut common = args.common;
+	if common.input.as_os_str() == RECIPE_INPUT {
+		common.input = recipe.input
+			.ok_or_else(|| NailError::InvalidArgument(
+				"No input file. Set 'input' in the recipe or pass --input".to_string()
+			))?;
+	}
+	common.output = common.output.or(recipe.output);
+
+	common.log_if_verbose(&format!("Running recipe {} with {} steps", args.recipe.display(), recipe.steps.len()));
+	run_pipeline(&common, &recipe.steps, "run").await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_load_recipe_resolves_paths() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("clean.yaml");
+		std::fs::write(&path, "input: raw.csv\noutput: out/clean.parquet\nsteps:\n  - filter -c \"age>30\"\n  - dedup --row-wise\n").unwrap();
+
+		let recipe = Recipe::load(&path).unwrap();
+		assert_eq!(recipe.input, Some(dir.path().join("raw.csv")));
+		assert_eq!(recipe.output, Some(dir.path().join("out/clean.parquet")));
+		assert_eq!(recipe.steps, vec!["filter -c \"age>30\"", "dedup --row-wise"]);
+	}
+
+	#[test]
+	fn test_load_recipe_rejects_unknown_keys() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("bad.yaml");
+		std::fs::write(&path, "input: raw.csv\nstep:\n  - head\n").unwrap();
+		assert!(Recipe::load(&path).is_err());
+	}
+}
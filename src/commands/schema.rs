@@ -1,7 +1,8 @@
 use clap::Args;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
-use crate::utils::output::OutputHandler;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::{explain_plan, OutputHandler};
+use crate::utils::schema_file::SchemaField;
 use crate::cli::CommonArgs;
 use datafusion::prelude::*;
 
@@ -15,15 +16,15 @@ pub struct SchemaArgs {
 pub async fn execute(args: SchemaArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading schema from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	if args.common.explain_requested() {
+		return explain_plan(&df, args.common.explain_analyze).await;
+	}
+	
 	let schema = df.schema();
 	
 	let schema_info: Vec<SchemaField> = schema.fields().iter()
-		.map(|field| SchemaField {
-			name: field.name().clone(),
-			data_type: format!("{:?}", field.data_type()),
-			nullable: field.is_nullable(),
-		})
+		.map(|field| SchemaField::from_field(field))
 		.collect();
 	
 	args.common.log_if_verbose(&format!("Schema contains {} fields", schema_info.len()));
@@ -62,12 +63,4 @@ pub async fn execute(args: SchemaArgs) -> NailResult<()> {
 	output_handler.handle_output(&result_df, "schema").await?;
 	
 	Ok(())
-}
-
-
-#[derive(serde::Serialize)]
-struct SchemaField {
-	name: String,
-	data_type: String,
-	nullable: bool,
 }
\ No newline at end of file
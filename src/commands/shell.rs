@@ -0,0 +1,373 @@
+use clap::{Args, Parser};
+use datafusion::prelude::*;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use crate::cli::{Cli, CommonArgs};
+use crate::commands::Commands;
+use crate::commands::pipe::{apply_step, parse_step, split_pipeline, PIPELINE_COMMANDS};
+use crate::commands::sql::{is_valid_table_name, DEFAULT_TABLE_NAME};
+use crate::error::{NailError, NailResult};
+use crate::utils::format::display_dataframe;
+use crate::utils::io::{read_data_with_options, write_data};
+
+/// Name under which the result of an unassigned command is kept.
+const LAST_RESULT: &str = "_";
+
+/// Rows printed for results that are not assigned to a name.
+const DISPLAY_ROWS: usize = 20;
+
+const SHELL_COMMANDS: &[&str] = &["count", "exit", "help", "quit", "save", "schema", "sql", "stats", "tables", "use"];
+
+const HELP: &str = "\
+Commands run against the current table ('use NAME' to switch):
+  head -n 5, filter -c \"age>30\", sort -c age, ...   any pipeline command, steps may be chained with '|'
+  stats [-c COLUMNS] [-t exhaustive]              descriptive statistics
+  SELECT ... / sql QUERY                          SQL over all named tables
+  NAME = COMMAND                                  keep the result under NAME instead of printing it
+  tables | use NAME | schema [NAME] | count [NAME]
+  save [NAME] PATH                                write a table (format from the extension)
+  help | exit
+The last unassigned result is available as '_'.";
+
+#[derive(Args, Clone)]
+pub struct ShellArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+}
+
+pub async fn execute(args: ShellArgs) -> NailResult<()> {
+	args.common.reject_explain("shell")?;
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let df = df.cache().await?;
+
+	let mut shell = Shell::new(args.common.clone()).await?;
+	shell.store(DEFAULT_TABLE_NAME, df)?;
+	shell.current = DEFAULT_TABLE_NAME.to_string();
+
+	let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()
+		.map_err(|e| NailError::InvalidArgument(format!("Cannot start shell: {}", e)))?;
+	editor.set_helper(Some(ShellHelper::default()));
+	let history = history_path();
+	if let Some(path) = &history {
+		let _ = editor.load_history(path);
+	}
+
+	eprintln!("Loaded {} as '{}'. Type 'help' for commands.", args.common.input.display(), DEFAULT_TABLE_NAME);
+
+	loop {
+		if let Some(helper) = editor.helper_mut() {
+			helper.words = shell.completion_words();
+		}
+
+		let line = match editor.readline(&format!("nail:{}> ", shell.current)) {
+			Ok(line) => line,
+			Err(ReadlineError::Interrupted) => continue,
+			Err(ReadlineError::Eof) => break,
+			Err(e) => return Err(NailError::InvalidArgument(format!("Cannot read input: {}", e))),
+		};
+
+		let line = line.trim();
+		if line.is_empty() {
+			continue;
+		}
+		let _ = editor.add_history_entry(line);
+
+		match shell.run_line(line).await {
+			Ok(ShellFlow::Continue) => {},
+			Ok(ShellFlow::Exit) => break,
+			Err(e) => eprintln!("Error: {}", e),
+		}
+	}
+
+	if let Some(path) = &history {
+		let _ = editor.save_history(path);
+	}
+	Ok(())
+}
+
+fn history_path() -> Option<PathBuf> {
+	std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".nail_history"))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ShellFlow {
+	Continue,
+	Exit,
+}
+
+/// Named in-memory tables and the SQL context they are registered in.
+pub struct Shell {
+	common: CommonArgs,
+	ctx: SessionContext,
+	tables: BTreeMap<String, DataFrame>,
+	pub current: String,
+}
+
+impl Shell {
+	pub async fn new(common: CommonArgs) -> NailResult<Self> {
+		let ctx = crate::utils::create_context(&common.session_options()).await?;
+		Ok(Self { common, ctx, tables: BTreeMap::new(), current: DEFAULT_TABLE_NAME.to_string() })
+	}
+
+	pub fn table(&self, name: &str) -> NailResult<&DataFrame> {
+		self.tables.get(name).ok_or_else(|| NailError::InvalidArgument(format!(
+			"No table named '{}'. Available: {}",
+			name, self.tables.keys().cloned().collect::<Vec<_>>().join(", ")
+		)))
+	}
+
+	pub fn store(&mut self, name: &str, df: DataFrame) -> NailResult<()> {
+		if name != LAST_RESULT && !is_valid_table_name(name) {
+			return Err(NailError::InvalidArgument(format!(
+				"Invalid table name '{}'. Use letters, digits and underscores, starting with a letter or underscore",
+				name
+			)));
+		}
+		self.ctx.deregister_table(name)?;
+		self.ctx.register_table(name, df.clone().into_view())?;
+		self.tables.insert(name.to_string(), df);
+		Ok(())
+	}
+
+	fn completion_words(&self) -> Vec<String> {
+		let mut words: Vec<String> = self.tables.keys().cloned().collect();
+		if let Ok(df) = self.table(&self.current) {
+			words.extend(df.schema().fields().iter().map(|f| f.name().clone()));
+		}
+		words
+	}
+
+	/// Runs one line of input: a shell command, SQL, or pipeline steps with an optional `NAME =` target.
+	pub async fn run_line(&mut self, line: &str) -> NailResult<ShellFlow> {
+		let (target, command) = split_assignment(line);
+		let (keyword, rest) = command.split_once(char::is_whitespace)
+			.map(|(k, r)| (k, r.trim()))
+			.unwrap_or((command, ""));
+
+		if target.is_some() && SHELL_COMMANDS.contains(&keyword) && !matches!(keyword, "sql" | "stats") {
+			return Err(NailError::InvalidArgument(format!("'{}' does not produce a table", keyword)));
+		}
+
+		let result = match keyword {
+			"exit" | "quit" => return Ok(ShellFlow::Exit),
+			"help" => {
+				println!("{}", HELP);
+				return Ok(ShellFlow::Continue);
+			},
+			"tables" => {
+				for (name, df) in &self.tables {
+					let marker = if *name == self.current { "*" } else { " " };
+					println!("{} {} ({} rows, {} columns)", marker, name, df.clone().count().await?, df.schema().fields().len());
+				}
+				return Ok(ShellFlow::Continue);
+			},
+			"use" => {
+				self.table(rest)?;
+				self.current = rest.to_string();
+				return Ok(ShellFlow::Continue);
+			},
+			"schema" => {
+				let df = self.table(self.name_or_current(rest))?;
+				for field in df.schema().fields() {
+					println!("{}: {}{}", field.name(), field.data_type(), if field.is_nullable() { "" } else { " (not null)" });
+				}
+				return Ok(ShellFlow::Continue);
+			},
+			"count" => {
+				let df = self.table(self.name_or_current(rest))?;
+				println!("{}", df.clone().count().await?);
+				return Ok(ShellFlow::Continue);
+			},
+			"save" => {
+				self.save(rest).await?;
+				return Ok(ShellFlow::Continue);
+			},
+			"sql" => self.ctx.sql(rest.trim_end_matches(';')).await?,
+			_ if is_sql(keyword) => self.ctx.sql(command.trim_end_matches(';')).await?,
+			"stats" => {
+				let df = self.table(&self.current)?.clone();
+				match parse_shell_command(command, &self.common)? {
+					Commands::Stats(args) => crate::commands::stats::transform(df, &args).await?,
+					_ => unreachable!("stats parses to the stats command"),
+				}
+			},
+			_ if PIPELINE_COMMANDS.contains(&keyword) => {
+				let mut df = self.table(&self.current)?.clone();
+				for step in split_pipeline(command)? {
+					df = apply_step(df, parse_step(&step, &self.common)?).await?;
+				}
+				df
+			},
+			_ => {
+				return Err(NailError::InvalidArgument(format!(
+					"Unknown command '{}'. Type 'help' for available commands",
+					keyword
+				)));
+			},
+		};
+
+		match target {
+			Some(name) => {
+				let df = result.cache().await?;
+				self.common.log_if_verbose(&format!("Stored {} rows as '{}'", df.clone().count().await?, name));
+				self.store(name, df)?;
+			},
+			None => {
+				let total_rows = result.clone().count().await?;
+				display_dataframe(&result.clone().limit(0, Some(DISPLAY_ROWS))?, None, None).await?;
+				if total_rows > DISPLAY_ROWS {
+					println!("({} of {} rows shown; assign with 'name = ...' to keep the result)", DISPLAY_ROWS, total_rows);
+				}
+				self.store(LAST_RESULT, result)?;
+			},
+		}
+
+		Ok(ShellFlow::Continue)
+	}
+
+	fn name_or_current<'a>(&'a self, name: &'a str) -> &'a str {
+		if name.is_empty() { &self.current } else { name }
+	}
+
+	async fn save(&self, rest: &str) -> NailResult<()> {
+		let tokens = shlex::split(rest)
+			.ok_or_else(|| NailError::InvalidArgument(format!("Cannot parse: save {}", rest)))?;
+		let (name, path) = match tokens.as_slice() {
+			[path] => (self.current.as_str(), path),
+			[name, path] => (name.as_str(), path),
+			_ => return Err(NailError::InvalidArgument("Usage: save [NAME] PATH".to_string())),
+		};
+
+		let df = self.table(name)?;
+		write_data(df, &PathBuf::from(path), None).await?;
+		println!("Saved '{}' to {}", name, path);
+		Ok(())
+	}
+}
+
+/// Splits `name = command` into its target and command. Lines without a target return `None`.
+fn split_assignment(line: &str) -> (Option<&str>, &str) {
+	if let Some((name, command)) = line.split_once('=') {
+		let name = name.trim();
+		if is_valid_table_name(name) || name == LAST_RESULT {
+			return (Some(name), command.trim());
+		}
+	}
+	(None, line)
+}
+
+fn is_sql(keyword: &str) -> bool {
+	matches!(keyword.to_lowercase().as_str(), "select" | "with")
+}
+
+/// Parses a command that is not a pipeline step (such as `stats`) with the regular command-line parser.
+fn parse_shell_command(command: &str, common: &CommonArgs) -> NailResult<Commands> {
+	let tokens = shlex::split(command)
+		.ok_or_else(|| NailError::InvalidArgument(format!("Cannot parse: {}", command)))?;
+	let (name, rest) = tokens.split_first()
+		.ok_or_else(|| NailError::InvalidArgument("Empty command".to_string()))?;
+
+	let argv = ["nail", name.as_str(), "<shell>"].into_iter()
+		.map(String::from)
+		.chain(rest.iter().cloned());
+	let mut command = Cli::try_parse_from(argv)
+		.map_err(|e| NailError::InvalidArgument(
+			e.to_string().lines().next().unwrap_or_default().trim_start_matches("error: ").to_string()
+		))?
+		.command;
+
+	if let Commands::Stats(args) = &mut command {
+		args.common = CommonArgs { verbose: args.common.verbose || common.verbose, ..common.clone() };
+	}
+	Ok(command)
+}
+
+/// Completes command names at the start of a line and table or column names elsewhere.
+#[derive(Default)]
+pub struct ShellHelper {
+	words: Vec<String>,
+}
+
+impl Completer for ShellHelper {
+	type Candidate = Pair;
+
+	fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+		let before = &line[..pos];
+		let start = before.rfind(|c: char| c.is_whitespace() || matches!(c, ',' | '=' | '"' | '\'' | '(' | '|'))
+			.map(|i| i + 1)
+			.unwrap_or(0);
+		let prefix = &before[start..];
+
+		let (_, command) = split_assignment(before);
+		let at_command = !command.trim_start().contains(char::is_whitespace) || before[..start].trim_end().ends_with('|');
+
+		let candidates: Vec<String> = if at_command {
+			PIPELINE_COMMANDS.iter().chain(SHELL_COMMANDS).map(|s| s.to_string()).collect()
+		} else {
+			self.words.clone()
+		};
+
+		let mut matches: Vec<Pair> = candidates.into_iter()
+			.filter(|c| c.starts_with(prefix))
+			.map(|c| Pair { display: c.clone(), replacement: c })
+			.collect();
+		matches.sort_by(|a, b| a.display.cmp(&b.display));
+		matches.dedup_by(|a, b| a.display == b.display);
+		Ok((start, matches))
+	}
+}
+
+impl Hinter for ShellHelper {
+	type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::Int64Array;
+	use datafusion::arrow::datatypes::{DataType, Field, Schema};
+	use datafusion::arrow::record_batch::RecordBatch;
+	use std::sync::Arc;
+
+	#[test]
+	fn test_split_assignment() {
+		assert_eq!(split_assignment("adults = filter -c \"age>30\""), (Some("adults"), "filter -c \"age>30\""));
+		assert_eq!(split_assignment("filter -c \"age=30\""), (None, "filter -c \"age=30\""));
+		assert_eq!(split_assignment("SELECT a = 1 FROM t"), (None, "SELECT a = 1 FROM t"));
+	}
+
+	#[tokio::test]
+	async fn test_run_line_stores_results() {
+		let schema = Arc::new(Schema::new(vec![Field::new("age", DataType::Int64, false)]));
+		let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![25, 35, 45]))]).unwrap();
+		let df = SessionContext::new().read_batch(batch).unwrap();
+
+		let mut shell = Shell::new(CommonArgs::default()).await.unwrap();
+		shell.store("t", df).unwrap();
+
+		shell.run_line("older = filter -c \"age>30\"").await.unwrap();
+		assert_eq!(shell.table("older").unwrap().clone().count().await.unwrap(), 2);
+
+		shell.run_line("use older").await.unwrap();
+		shell.run_line("top = SELECT max(age) AS m FROM t").await.unwrap();
+		assert_eq!(shell.table("top").unwrap().clone().count().await.unwrap(), 1);
+
+		assert!(shell.run_line("bogus").await.is_err());
+		assert_eq!(shell.run_line("exit").await.unwrap(), ShellFlow::Exit);
+	}
+}
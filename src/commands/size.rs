@@ -1,8 +1,9 @@
 use clap::Args;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
-use crate::utils::output::OutputHandler;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::{explain_plan, OutputHandler};
 use crate::cli::CommonArgs;
+use datafusion::functions_aggregate::expr_fn::count;
 use datafusion::prelude::*;
 
 #[derive(Args, Clone)]
@@ -23,7 +24,12 @@ pub struct SizeArgs {
 pub async fn execute(args: SizeArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Analyzing size of: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	if args.common.explain_requested() {
+		let count_df = df.aggregate(vec![], vec![count(lit(1)).alias("row_count")])?;
+		return explain_plan(&count_df, args.common.explain_analyze).await;
+	}
+	
 	let schema = df.schema();
 	
 	let row_count = df.clone().count().await?;
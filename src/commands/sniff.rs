@@ -0,0 +1,497 @@
+use clap::Args;
+use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::prelude::*;
+use encoding_rs::Encoding;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use crate::error::{NailError, NailResult};
+use crate::utils::bad_rows::value_matches_type;
+use crate::utils::encoding::{detect_encoding, resolve_encoding, AUTO_ENCODING};
+use crate::utils::output::OutputHandler;
+use crate::utils::schema_file::write_schema_file;
+use crate::utils::{detect_file_format, FileFormat};
+use crate::cli::CommonArgs;
+
+const SAMPLE_BYTES: u64 = 8 * 1024 * 1024;
+const DELIMITER_CANDIDATES: &[u8] = b",;\t|:";
+const QUOTE_CANDIDATES: &[u8] = b"\"'";
+/// Share of sampled values a type must cover to be reported instead of Utf8.
+const TYPE_THRESHOLD: f64 = 0.95;
+const MAX_EXAMPLES: usize = 3;
+
+#[derive(Args, Clone)]
+pub struct SniffArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(long, help = "Number of data rows to sample", default_value = "1000")]
+	pub sample_rows: usize,
+
+	#[arg(long, help = "Print only the equivalent nail read options")]
+	pub emit_options: bool,
+
+	#[arg(long, help = "Write the inferred schema to a JSON file usable with --schema")]
+	pub write_schema: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SniffedDialect {
+	pub encoding: &'static Encoding,
+	pub has_bom: bool,
+	pub line_ending: &'static str,
+	pub delimiter: u8,
+	pub quote: u8,
+	pub has_header: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SniffedColumn {
+	pub name: String,
+	pub data_type: DataType,
+	pub confidence: f64,
+	pub nulls: usize,
+	pub examples: Vec<String>,
+}
+
+pub async fn execute(args: SniffArgs) -> NailResult<()> {
+	args.common.reject_explain("sniff")?;
+	args.common.log_if_verbose(&format!("Sniffing: {}", args.common.input.display()));
+
+	let format = detect_file_format(&args.common.input)?;
+	if !matches!(format, FileFormat::Csv | FileFormat::Tsv) {
+		return Err(NailError::UnsupportedFormat(
+			"sniff only inspects delimited text files (.csv, .tsv)".to_string()
+		));
+	}
+
+	let mut bytes = Vec::new();
+	let file = std::fs::File::open(&args.common.input)?;
+	file.take(SAMPLE_BYTES + 1).read_to_end(&mut bytes)?;
+	let truncated = bytes.len() as u64 > SAMPLE_BYTES;
+
+	let (text, encoding, has_bom) = decode_sample(&bytes, truncated, args.common.read.encoding.as_deref())?;
+	let (dialect, columns, sampled_rows) = sniff_text(&text, encoding, has_bom, args.sample_rows, &format);
+
+	args.common.log_if_verbose(&format!("Sampled {} rows and {} columns", sampled_rows, columns.len()));
+
+	if let Some(schema_path) = &args.write_schema {
+		// The schema is read strictly, so a type is only written when every sampled value fits it
+		for column in columns.iter().filter(|c| c.data_type != DataType::Utf8 && c.confidence < 1.0) {
+			eprintln!(
+				"Warning: column '{}' is written as Utf8 in {}: {:.1}% of sampled values are not {}",
+				column.name, schema_path.display(), (1.0 - column.confidence) * 100.0, column.data_type
+			);
+		}
+		let fields: Vec<Field> = columns.iter()
+			.map(|c| Field::new(&c.name, schema_type(c), true))
+			.collect();
+		write_schema_file(schema_path, &Schema::new(fields))?;
+		args.common.log_if_verbose(&format!("Schema written to: {}", schema_path.display()));
+	}
+
+	if args.emit_options {
+		println!("{}", read_options_string(&dialect, &format, args.write_schema.as_ref()));
+		return Ok(());
+	}
+
+	if args.common.output.is_none() && matches!(args.common.format, None | Some(crate::cli::OutputFormat::Text)) {
+		println!("Encoding:     {}{}", dialect.encoding.name(), if dialect.has_bom { " (BOM)" } else { "" });
+		println!("Line endings: {}", dialect.line_ending);
+		println!("Delimiter:    {}", describe_byte(dialect.delimiter));
+		println!("Quote:        {}", describe_byte(dialect.quote));
+		println!("Header:       {}", if dialect.has_header { "yes" } else { "no" });
+		println!("Sampled rows: {}", sampled_rows);
+		println!("Read options: {}", read_options_string(&dialect, &format, args.write_schema.as_ref()));
+		println!();
+	}
+
+	let result_df = columns_dataframe(&columns)?;
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "sniff").await?;
+
+	Ok(())
+}
+
+/// Type written to a schema file for `column`: its sniffed type if every sampled value fits, else Utf8.
+fn schema_type(column: &SniffedColumn) -> DataType {
+	if column.confidence >= 1.0 {
+		column.data_type.clone()
+	} else {
+		DataType::Utf8
+	}
+}
+
+/// Decodes the raw sample, dropping a trailing partial line when the sample was cut short.
+fn decode_sample(bytes: &[u8], truncated: bool, label: Option<&str>) -> NailResult<(String, &'static Encoding, bool)> {
+	let bytes = if truncated {
+		match bytes.iter().rposition(|b| *b == b'\n') {
+			Some(pos) => &bytes[..=pos],
+			None => bytes,
+		}
+	} else {
+		bytes
+	};
+
+	let has_bom = Encoding::for_bom(bytes).is_some();
+	let encoding = match label {
+		Some(label) if !label.trim().eq_ignore_ascii_case(AUTO_ENCODING) => resolve_encoding(label)?,
+		_ => detect_encoding(bytes),
+	};
+	let (text, used, _) = encoding.decode(bytes);
+
+	Ok((text.into_owned(), used, has_bom))
+}
+
+pub fn sniff_text(
+	text: &str,
+	encoding: &'static Encoding,
+	has_bom: bool,
+	sample_rows: usize,
+	format: &FileFormat,
+) -> (SniffedDialect, Vec<SniffedColumn>, usize) {
+	let line_ending = detect_line_ending(text);
+	let quote = detect_quote(text);
+	let default_delimiter = if matches!(format, FileFormat::Tsv) { b'\t' } else { b',' };
+	let delimiter = detect_delimiter(text, quote, sample_rows).unwrap_or(default_delimiter);
+
+	let rows = parse_rows(text, delimiter, quote, sample_rows + 1);
+	let has_header = detect_header(&rows);
+
+	let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+	let (names, data): (Vec<String>, &[Vec<String>]) = if has_header && !rows.is_empty() {
+		let names = (0..width)
+			.map(|i| rows[0].get(i).cloned().unwrap_or_else(|| format!("column_{}", i + 1)))
+			.collect();
+		(names, &rows[1..])
+	} else {
+		((1..=width).map(|i| format!("column_{}", i)).collect(), &rows[..rows.len().min(sample_rows)])
+	};
+
+	let columns = names.into_iter()
+		.enumerate()
+		.map(|(idx, name)| {
+			let values: Vec<&str> = data.iter()
+				.map(|row| row.get(idx).map(|s| s.as_str()).unwrap_or(""))
+				.collect();
+			infer_column(name, &values)
+		})
+		.collect();
+
+	let dialect = SniffedDialect { encoding, has_bom, line_ending, delimiter, quote, has_header };
+	(dialect, columns, data.len())
+}
+
+fn detect_line_ending(text: &str) -> &'static str {
+	let crlf = text.matches("\r\n").count();
+	let lf = text.matches('\n').count() - crlf;
+	let cr = text.matches('\r').count() - crlf;
+
+	match (crlf > 0, lf > 0, cr > 0) {
+		(false, false, false) => "none",
+		(true, false, false) => "CRLF",
+		(false, true, false) => "LF",
+		(false, false, true) => "CR",
+		_ => "mixed",
+	}
+}
+
+/// Picks the quote character that most often opens or closes a field.
+fn detect_quote(text: &str) -> u8 {
+	let bytes = text.as_bytes();
+	let is_boundary = |b: Option<&u8>| match b {
+		None => true,
+		Some(b) => DELIMITER_CANDIDATES.contains(b) || *b == b'\n' || *b == b'\r',
+	};
+
+	QUOTE_CANDIDATES.iter()
+		.map(|&quote| {
+			let count = bytes.iter().enumerate()
+				.filter(|(i, b)| {
+					**b == quote && (is_boundary(i.checked_sub(1).and_then(|p| bytes.get(p))) || is_boundary(bytes.get(i + 1)))
+				})
+				.count();
+			(quote, count)
+		})
+		.max_by_key(|(quote, count)| (*count, *quote == b'"'))
+		.map(|(quote, _)| quote)
+		.unwrap_or(b'"')
+}
+
+/// Picks the delimiter that yields the most consistent field count (greater than one).
+fn detect_delimiter(text: &str, quote: u8, sample_rows: usize) -> Option<u8> {
+	let mut best: Option<(u8, f64, usize)> = None;
+
+	for &delimiter in DELIMITER_CANDIDATES {
+		let rows = parse_rows(text, delimiter, quote, sample_rows + 1);
+		if rows.is_empty() {
+			continue;
+		}
+
+		let mut counts = std::collections::HashMap::new();
+		for row in &rows {
+			*counts.entry(row.len()).or_insert(0usize) += 1;
+		}
+		let (width, freq) = counts.into_iter()
+			.max_by_key(|(width, freq)| (*freq, *width))
+			.unwrap();
+		if width < 2 {
+			continue;
+		}
+
+		let consistency = freq as f64 / rows.len() as f64;
+		let better = match best {
+			None => true,
+			Some((_, best_consistency, best_width)) => {
+				consistency > best_consistency + 1e-9
+					|| ((consistency - best_consistency).abs() < 1e-9 && width > best_width)
+			},
+		};
+		if better {
+			best = Some((delimiter, consistency, width));
+		}
+	}
+
+	best.map(|(delimiter, _, _)| delimiter)
+}
+
+fn parse_rows(text: &str, delimiter: u8, quote: u8, limit: usize) -> Vec<Vec<String>> {
+	let mut reader = csv::ReaderBuilder::new()
+		.has_headers(false)
+		.flexible(true)
+		.delimiter(delimiter)
+		.quote(quote)
+		.from_reader(text.as_bytes());
+
+	reader.records()
+		.filter_map(|r| r.ok())
+		.take(limit)
+		.map(|record| record.iter().map(|f| f.to_string()).collect())
+		.collect()
+}
+
+/// A header is likely when the first row does not fit the types of the rows below it,
+/// or, for all-text data, when its values are unique and never repeated in the body.
+fn detect_header(rows: &[Vec<String>]) -> bool {
+	if rows.len() < 2 {
+		return true;
+	}
+
+	let first = &rows[0];
+	let body = &rows[1..];
+	let mut typed_votes = (0usize, 0usize);
+	let mut text_header = true;
+
+	for (idx, head) in first.iter().enumerate() {
+		let values: Vec<&str> = body.iter()
+			.map(|row| row.get(idx).map(|s| s.as_str()).unwrap_or(""))
+			.collect();
+		let column = infer_column(String::new(), &values);
+
+		if column.data_type != DataType::Utf8 && column.confidence >= TYPE_THRESHOLD {
+			if !head.is_empty() && !value_matches_type(head, &column.data_type) {
+				typed_votes.0 += 1;
+			} else {
+				typed_votes.1 += 1;
+			}
+		} else if head.is_empty() || values.contains(&head.as_str()) {
+			text_header = false;
+		}
+	}
+
+	if typed_votes.0 + typed_votes.1 > 0 {
+		return typed_votes.0 > typed_votes.1;
+	}
+
+	let mut seen = std::collections::HashSet::new();
+	text_header && first.iter().all(|h| seen.insert(h))
+}
+
+fn infer_column(name: String, values: &[&str]) -> SniffedColumn {
+	let non_null: Vec<&str> = values.iter().copied().filter(|v| !v.is_empty()).collect();
+	let nulls = values.len() - non_null.len();
+
+	let mut examples: Vec<String> = Vec::new();
+	for value in &non_null {
+		if examples.len() >= MAX_EXAMPLES {
+			break;
+		}
+		if !examples.iter().any(|e| e == value) {
+			examples.push(value.to_string());
+		}
+	}
+
+	if non_null.is_empty() {
+		return SniffedColumn { name, data_type: DataType::Utf8, confidence: 0.0, nulls, examples };
+	}
+
+	// Narrowest type first, so integers are not reported as floats
+	let candidates = [
+		DataType::Boolean,
+		DataType::Int64,
+		DataType::Float64,
+		DataType::Date32,
+		DataType::Timestamp(TimeUnit::Nanosecond, None),
+	];
+	let total = non_null.len() as f64;
+	let shares: Vec<(DataType, f64)> = candidates.into_iter()
+		.map(|dt| {
+			let matched = non_null.iter().filter(|v| value_matches_type(v, &dt)).count();
+			(dt, matched as f64 / total)
+		})
+		.collect();
+
+	let chosen = shares.iter()
+		.find(|(_, share)| *share >= 1.0)
+		.or_else(|| shares.iter()
+			.filter(|(_, share)| *share >= TYPE_THRESHOLD)
+			.fold(None, |best: Option<&(DataType, f64)>, candidate| match best {
+				// Ties keep the narrower type seen first
+				Some(b) if b.1 >= candidate.1 => Some(b),
+				_ => Some(candidate),
+			}));
+
+	match chosen {
+		Some((data_type, share)) => SniffedColumn {
+			name,
+			data_type: data_type.clone(),
+			confidence: *share,
+			nulls,
+			examples,
+		},
+		None => {
+			let best_other = shares.iter().map(|(_, share)| *share).fold(0.0, f64::max);
+			SniffedColumn { name, data_type: DataType::Utf8, confidence: 1.0 - best_other, nulls, examples }
+		},
+	}
+}
+
+fn describe_byte(byte: u8) -> String {
+	match byte {
+		b'\t' => "'\\t' (tab)".to_string(),
+		b' ' => "' ' (space)".to_string(),
+		_ => format!("'{}'", byte as char),
+	}
+}
+
+fn shell_quote_byte(byte: u8) -> String {
+	match byte {
+		b'\t' => "'\\t'".to_string(),
+		b'\'' => "\"'\"".to_string(),
+		_ => format!("'{}'", byte as char),
+	}
+}
+
+/// Builds the nail flags that read the file with the detected dialect.
+pub fn read_options_string(dialect: &SniffedDialect, format: &FileFormat, schema_path: Option<&PathBuf>) -> String {
+	let mut options = Vec::new();
+
+	let default_delimiter = if matches!(format, FileFormat::Tsv) { b'\t' } else { b',' };
+	if dialect.delimiter != default_delimiter {
+		options.push(format!("--delimiter {}", shell_quote_byte(dialect.delimiter)));
+	}
+	if dialect.quote != b'"' {
+		options.push(format!("--quote {}", shell_quote_byte(dialect.quote)));
+	}
+	if !dialect.has_header {
+		options.push("--no-header".to_string());
+	}
+	if dialect.encoding != encoding_rs::UTF_8 || dialect.has_bom {
+		options.push(format!("--encoding {}", dialect.encoding.name().to_lowercase()));
+	}
+	if let Some(path) = schema_path {
+		options.push(format!("--schema '{}'", path.display()));
+	}
+
+	options.join(" ")
+}
+
+fn columns_dataframe(columns: &[SniffedColumn]) -> NailResult<DataFrame> {
+	let schema = Arc::new(Schema::new(vec![
+		Field::new("column", DataType::Utf8, false),
+		Field::new("inferred_type", DataType::Utf8, false),
+		Field::new("confidence", DataType::Float64, false),
+		Field::new("nulls", DataType::Int64, false),
+		Field::new("examples", DataType::Utf8, false),
+	]));
+
+	let arrays: Vec<ArrayRef> = vec![
+		Arc::new(StringArray::from(columns.iter().map(|c| c.name.clone()).collect::<Vec<_>>())),
+		Arc::new(StringArray::from(columns.iter().map(|c| format!("{:?}", c.data_type)).collect::<Vec<_>>())),
+		Arc::new(Float64Array::from(columns.iter().map(|c| (c.confidence * 1000.0).round() / 1000.0).collect::<Vec<_>>())),
+		Arc::new(Int64Array::from(columns.iter().map(|c| c.nulls as i64).collect::<Vec<_>>())),
+		Arc::new(StringArray::from(columns.iter().map(|c| c.examples.join(" | ")).collect::<Vec<_>>())),
+	];
+
+	let batch = RecordBatch::try_new(schema, arrays)?;
+	let ctx = SessionContext::new();
+	Ok(ctx.read_batch(batch)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use encoding_rs::UTF_8;
+
+	fn sniff(text: &str) -> (SniffedDialect, Vec<SniffedColumn>, usize) {
+		sniff_text(text, UTF_8, false, 1000, &FileFormat::Csv)
+	}
+
+	#[test]
+	fn test_sniff_semicolon_with_header() {
+		let (dialect, columns, rows) = sniff("id;name;price\r\n1;Alice;9.5\r\n2;Bob;10\r\n3;\"Doe; Jane\";\r\n");
+		assert_eq!(dialect.delimiter, b';');
+		assert_eq!(dialect.quote, b'"');
+		assert_eq!(dialect.line_ending, "CRLF");
+		assert!(dialect.has_header);
+		assert_eq!(rows, 3);
+
+		assert_eq!(columns[0].name, "id");
+		assert_eq!(columns[0].data_type, DataType::Int64);
+		assert_eq!(columns[1].data_type, DataType::Utf8);
+		assert_eq!(columns[1].examples, vec!["Alice", "Bob", "Doe; Jane"]);
+		assert_eq!(columns[2].data_type, DataType::Float64);
+		assert_eq!(columns[2].nulls, 1);
+	}
+
+	#[test]
+	fn test_sniff_headerless_pipe() {
+		let (dialect, columns, rows) = sniff("1|2024-01-05|true\n2|2024-02-11|false\n3|2024-03-02|true\n");
+		assert_eq!(dialect.delimiter, b'|');
+		assert!(!dialect.has_header);
+		assert_eq!(rows, 3);
+		assert_eq!(columns[0].name, "column_1");
+		assert_eq!(columns[1].data_type, DataType::Date32);
+		assert_eq!(columns[2].data_type, DataType::Boolean);
+	}
+
+	#[test]
+	fn test_sniff_mostly_numeric_confidence() {
+		let mut text = String::from("value\n");
+		for i in 0..99 {
+			text.push_str(&format!("{}\n", i));
+		}
+		text.push_str("n/a\n");
+		let (_, columns, _) = sniff(&text);
+		assert_eq!(columns[0].data_type, DataType::Int64);
+		assert!((columns[0].confidence - 0.99).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_read_options_string() {
+		let dialect = SniffedDialect {
+			encoding: encoding_rs::WINDOWS_1252,
+			has_bom: false,
+			line_ending: "LF",
+			delimiter: b';',
+			quote: b'"',
+			has_header: false,
+		};
+		assert_eq!(
+			read_options_string(&dialect, &FileFormat::Csv, None),
+			"--delimiter ';' --no-header --encoding windows-1252"
+		);
+	}
+}
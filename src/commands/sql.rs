@@ -0,0 +1,188 @@
+use clap::Args;
+use std::path::PathBuf;
+use crate::error::{NailError, NailResult};
+use crate::cli::{CommonArgs, OutputFormat, ReadArgs};
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::utils::create_context;
+
+/// Table name used when a single input is given without an explicit name.
+pub const DEFAULT_TABLE_NAME: &str = "t";
+
+#[derive(Args, Clone)]
+pub struct SqlArgs {
+	#[arg(help = "SQL query to run (use --file for longer queries)")]
+	pub query: Option<String>,
+
+	#[arg(short, long, help = "Input file registered as table 't'")]
+	pub input: Option<PathBuf>,
+
+	#[arg(short, long = "table", value_name = "NAME=PATH", help = "Register a file as a table (repeatable). A bare PATH is registered as 't'")]
+	pub tables: Vec<String>,
+
+	#[arg(long, help = "Read the SQL query from a file")]
+	pub file: Option<PathBuf>,
+
+	#[arg(short, long, help = "Output file (if not specified, prints to console)")]
+	pub output: Option<PathBuf>,
+
+	#[arg(short, long, help = "Output format (auto-detect by default)", value_enum)]
+	pub format: Option<OutputFormat>,
+
+	#[arg(short, long, help = "Enable verbose output")]
+	pub verbose: bool,
+
+	#[command(flatten)]
+	pub read: ReadArgs,
+
+	#[arg(long, help = "Print the logical and physical plan instead of running the query", conflicts_with = "explain_analyze")]
+	pub explain: bool,
+
+	#[arg(long, help = "Run the query and print the physical plan with per-operator metrics instead of the result")]
+	pub explain_analyze: bool,
+}
+
+impl SqlArgs {
+	/// Shared arguments used for reading every table and writing the result.
+	pub fn common(&self) -> CommonArgs {
+		CommonArgs {
+			output: self.output.clone(),
+			format: self.format.clone(),
+			verbose: self.verbose,
+			read: self.read.clone(),
+			explain: self.explain,
+			explain_analyze: self.explain_analyze,
+			..CommonArgs::default()
+		}
+	}
+}
+
+pub async fn execute(args: SqlArgs) -> NailResult<()> {
+	let common = args.common();
+	let query = resolve_query(&args)?;
+	let tables = resolve_tables(&args)?;
+
+	let ctx = create_context(&common.session_options()).await?;
+	let read_options = common.read_options();
+
+	for (name, path) in &tables {
+		common.log_if_verbose(&format!("Registering table '{}' from: {}", name, path.display()));
+		let df = read_data_with_options(path, &read_options).await?;
+		ctx.register_table(name.as_str(), df.into_view())?;
+	}
+
+	common.log_if_verbose(&format!("Executing query: {}", query));
+	let result_df = ctx.sql(&query).await?;
+
+	let output_handler = OutputHandler::new(&common);
+	output_handler.handle_output(&result_df, "sql").await?;
+
+	Ok(())
+}
+
+fn resolve_query(args: &SqlArgs) -> NailResult<String> {
+	let query = match (&args.query, &args.file) {
+		(Some(_), Some(_)) => {
+			return Err(NailError::InvalidArgument(
+				"Specify the query either inline or with --file, not both".to_string()
+			));
+		},
+		(Some(query), None) => query.clone(),
+		(None, Some(path)) => std::fs::read_to_string(path)?,
+		(None, None) => {
+			return Err(NailError::InvalidArgument(
+				"No query given. Pass a SQL string or --file query.sql".to_string()
+			));
+		},
+	};
+
+	let query = query.trim().trim_end_matches(';').trim().to_string();
+	if query.is_empty() {
+		return Err(NailError::InvalidArgument("SQL query is empty".to_string()));
+	}
+	Ok(query)
+}
+
+/// Collects `--input` and `--table` entries into unique (name, path) pairs.
+pub fn resolve_tables(args: &SqlArgs) -> NailResult<Vec<(String, PathBuf)>> {
+	let mut tables: Vec<(String, PathBuf)> = Vec::new();
+
+	if let Some(input) = &args.input {
+		tables.push((DEFAULT_TABLE_NAME.to_string(), input.clone()));
+	}
+
+	for spec in &args.tables {
+		let (name, path) = match spec.split_once('=') {
+			Some((name, path)) => (name.trim().to_string(), PathBuf::from(path.trim())),
+			None => (DEFAULT_TABLE_NAME.to_string(), PathBuf::from(spec.trim())),
+		};
+
+		if !is_valid_table_name(&name) {
+			return Err(NailError::InvalidArgument(format!(
+				"Invalid table name '{}'. Use letters, digits and underscores, starting with a letter or underscore",
+				name
+			)));
+		}
+		if tables.iter().any(|(existing, _)| existing == &name) {
+			return Err(NailError::InvalidArgument(format!("Table '{}' is registered more than once", name)));
+		}
+		tables.push((name, path));
+	}
+
+	Ok(tables)
+}
+
+pub fn is_valid_table_name(name: &str) -> bool {
+	let mut chars = name.chars();
+	matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+		&& chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sql_args(query: Option<&str>, input: Option<&str>, tables: &[&str]) -> SqlArgs {
+		SqlArgs {
+			query: query.map(|q| q.to_string()),
+			input: input.map(PathBuf::from),
+			tables: tables.iter().map(|t| t.to_string()).collect(),
+			file: None,
+			output: None,
+			format: None,
+			verbose: false,
+			read: ReadArgs::default(),
+			explain: false,
+			explain_analyze: false,
+		}
+	}
+
+	#[test]
+	fn test_resolve_tables() {
+		let args = sql_args(None, Some("data.parquet"), &["users=users.csv", "orders = orders.parquet"]);
+		let tables = resolve_tables(&args).unwrap();
+		assert_eq!(tables, vec![
+			("t".to_string(), PathBuf::from("data.parquet")),
+			("users".to_string(), PathBuf::from("users.csv")),
+			("orders".to_string(), PathBuf::from("orders.parquet")),
+		]);
+	}
+
+	#[test]
+	fn test_resolve_tables_rejects_duplicates_and_bad_names() {
+		let duplicate = sql_args(None, Some("a.parquet"), &["b.parquet"]);
+		assert!(resolve_tables(&duplicate).unwrap_err().to_string().contains("more than once"));
+
+		let bad_name = sql_args(None, None, &["1st=a.parquet"]);
+		assert!(resolve_tables(&bad_name).unwrap_err().to_string().contains("Invalid table name"));
+	}
+
+	#[test]
+	fn test_resolve_query() {
+		let args = sql_args(Some("  SELECT 1;\n"), None, &[]);
+		assert_eq!(resolve_query(&args).unwrap(), "SELECT 1");
+
+		let missing = sql_args(None, None, &[]);
+		assert!(resolve_query(&missing).is_err());
+	}
+}
@@ -1,9 +1,10 @@
 use clap::Args;
+use datafusion::prelude::DataFrame;
 use crate::error::NailResult;
-use crate::utils::io::read_data;
+use crate::utils::io::read_data_with_options;
 use crate::utils::output::OutputHandler;
-use crate::utils::stats::{calculate_basic_stats, calculate_exhaustive_stats, calculate_hypothesis_tests, select_columns_by_pattern};
 use crate::cli::CommonArgs;
+use crate::ops::{self, stats, StatsOptions};
 
 #[derive(Args, Clone)]
 pub struct StatsArgs {
@@ -24,34 +25,40 @@ pub enum StatsType {
 	Hypothesis,
 }
 
+impl From<StatsType> for ops::StatsType {
+	fn from(stats_type: StatsType) -> Self {
+		match stats_type {
+			StatsType::Basic => ops::StatsType::Basic,
+			StatsType::Exhaustive => ops::StatsType::Exhaustive,
+			StatsType::Hypothesis => ops::StatsType::Hypothesis,
+		}
+	}
+}
+
 pub async fn execute(args: StatsArgs) -> NailResult<()> {
 	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
 	
-	let df = read_data(&args.common.input).await?;
-	let schema = df.schema();
-	
-	let target_columns = if let Some(col_spec) = &args.columns {
-		select_columns_by_pattern(schema.clone().into(), col_spec)?
-	} else {
-		schema.fields().iter().map(|f| f.name().clone()).collect()
-	};
-	
-	args.common.log_if_verbose(&format!("Computing {:?} statistics for {} columns", args.stats_type, target_columns.len()));
-	
-	let stats_df = match args.stats_type {
-		StatsType::Basic => calculate_basic_stats(&df, &target_columns).await?,
-		StatsType::Exhaustive => calculate_exhaustive_stats(&df, &target_columns).await?,
-		StatsType::Hypothesis => calculate_hypothesis_tests(&df, &target_columns).await?,
-	};
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let stats_df = transform(df.clone(), &args).await?;
 	
 	let output_handler = OutputHandler::new(&args.common);
 	output_handler.handle_output(&stats_df, "stats").await?;
 	
 	// Print overall row count for basic stats when outputting to console
-	if args.common.output.is_none() && args.common.format.is_none() {
+	if args.common.output.is_none() && args.common.format.is_none() && !args.common.explain_requested() {
 		let total_rows = df.clone().count().await?;
 		println!("count | {}", total_rows);
 	}
 	
 	Ok(())
-}
\ No newline at end of file
+}
+
+/// Computes the statistics table for an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &StatsArgs) -> NailResult<DataFrame> {
+	args.common.log_if_verbose(&format!("Computing {:?} statistics", args.stats_type));
+	
+	stats(df, StatsOptions {
+		columns: args.columns.clone(),
+		stats_type: args.stats_type.clone().into(),
+	}).await
+}
@@ -0,0 +1,151 @@
+use clap::{ArgAction, Args};
+use datafusion::prelude::*;
+use crate::error::NailResult;
+use crate::utils::io::read_data_with_options;
+use crate::utils::output::OutputHandler;
+use crate::cli::CommonArgs;
+use crate::ops::{self, strings, Padding, StringsOptions};
+
+#[derive(Args, Clone)]
+pub struct StringsArgs {
+	#[command(flatten)]
+	pub common: CommonArgs,
+
+	#[arg(short, long, help = "Columns to transform (default: all string columns)")]
+	pub columns: Option<String>,
+
+	#[arg(long, help = "Remove leading and trailing whitespace")]
+	pub trim: bool,
+
+	#[arg(long, value_enum, help = "Change letter case")]
+	pub case: Option<CaseStyle>,
+
+	#[arg(long, value_enum, value_name = "FORM", help = "Apply a Unicode normalization form")]
+	pub normalize: Option<UnicodeForm>,
+
+	#[arg(long, help = "Remove accents and other diacritics (é -> e)")]
+	pub strip_accents: bool,
+
+	#[arg(long, help = "Collapse runs of whitespace, including non-breaking spaces, into single spaces and trim")]
+	pub normalize_whitespace: bool,
+
+	#[arg(long, num_args = 2, value_names = ["PATTERN", "REPLACEMENT"], action = ArgAction::Append,
+		help = "Replace regex matches; the replacement may use $1 or ${name}. Repeat to chain replacements")]
+	pub replace: Vec<String>,
+
+	#[arg(long, value_name = "WIDTH", help = "Pad values to at least WIDTH characters")]
+	pub pad: Option<usize>,
+
+	#[arg(long, value_name = "CHAR", default_value = " ", requires = "pad", help = "Padding character")]
+	pub pad_char: char,
+
+	#[arg(long, value_enum, default_value = "left", requires = "pad", help = "Side to pad on")]
+	pub pad_side: PadSide,
+
+	#[arg(long, value_name = "REGEX", help = "Extract capture groups into new columns <column>_<group name or number>")]
+	pub extract: Option<String>,
+
+	#[arg(long, value_name = "SEPARATOR", help = "Split values on SEPARATOR into a list column (or N columns with --split-into)")]
+	pub split: Option<String>,
+
+	#[arg(long, value_name = "N", requires = "split", help = "Split into N columns <column>_1..<column>_N; the last keeps the remainder")]
+	pub split_into: Option<usize>,
+
+	#[arg(long, help = "Write results to new columns <column><SUFFIX> instead of replacing the columns")]
+	pub suffix: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub enum CaseStyle {
+	Lower,
+	Upper,
+	/// Upper-case the first letter of every word, lower-case the rest.
+	Title,
+}
+
+impl From<CaseStyle> for ops::CaseStyle {
+	fn from(case_style: CaseStyle) -> Self {
+		match case_style {
+			CaseStyle::Lower => ops::CaseStyle::Lower,
+			CaseStyle::Upper => ops::CaseStyle::Upper,
+			CaseStyle::Title => ops::CaseStyle::Title,
+		}
+	}
+}
+
+/// Unicode normalization forms.
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub enum UnicodeForm {
+	Nfc,
+	Nfd,
+	Nfkc,
+	Nfkd,
+}
+
+impl From<UnicodeForm> for ops::UnicodeForm {
+	fn from(unicode_form: UnicodeForm) -> Self {
+		match unicode_form {
+			UnicodeForm::Nfc => ops::UnicodeForm::Nfc,
+			UnicodeForm::Nfd => ops::UnicodeForm::Nfd,
+			UnicodeForm::Nfkc => ops::UnicodeForm::Nfkc,
+			UnicodeForm::Nfkd => ops::UnicodeForm::Nfkd,
+		}
+	}
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq)]
+pub enum PadSide {
+	#[default]
+	Left,
+	Right,
+	Both,
+}
+
+impl From<PadSide> for ops::PadSide {
+	fn from(pad_side: PadSide) -> Self {
+		match pad_side {
+			PadSide::Left => ops::PadSide::Left,
+			PadSide::Right => ops::PadSide::Right,
+			PadSide::Both => ops::PadSide::Both,
+		}
+	}
+}
+
+pub async fn execute(args: StringsArgs) -> NailResult<()> {
+	args.common.log_if_verbose(&format!("Reading data from: {}", args.common.input.display()));
+
+	let df = read_data_with_options(&args.common.input, &args.common.read_options()).await?;
+	let result_df = transform(df, &args).await?;
+
+	let output_handler = OutputHandler::new(&args.common);
+	output_handler.handle_output(&result_df, "strings").await?;
+
+	Ok(())
+}
+
+/// Applies the string operations to an in-memory DataFrame.
+pub async fn transform(df: DataFrame, args: &StringsArgs) -> NailResult<DataFrame> {
+	let options = StringsOptions {
+		columns: args.columns.clone(),
+		normalize: args.normalize.clone().map(Into::into),
+		strip_accents: args.strip_accents,
+		normalize_whitespace: args.normalize_whitespace,
+		trim: args.trim,
+		replace: args.replace.chunks(2)
+			.map(|pair| (pair[0].clone(), pair[1].clone()))
+			.collect(),
+		case: args.case.clone().map(Into::into),
+		pad: args.pad.map(|width| Padding {
+			width,
+			fill: args.pad_char,
+			side: args.pad_side.clone().into(),
+		}),
+		extract: args.extract.clone(),
+		split: args.split.clone(),
+		split_into: args.split_into,
+		suffix: args.suffix.clone(),
+	};
+	args.common.log_if_verbose(&format!("Applying string operations: {:?}", options));
+
+	strings(df, options).await
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use serde::Serialize;
 use std::fmt;
 
 pub type NailResult<T> = Result<T, NailError>;
@@ -50,6 +51,10 @@ impl fmt::Display for NailError {
 					}
 				} else if error_str.contains("Schema") {
 					write!(f, "Schema error: {}", simplify_schema_error(&error_str))
+				} else if error_str.contains("UTF-8 error") {
+					write!(f, "Encoding error: {}. Use --encoding to read non-UTF-8 input", simplify_datafusion_error(&error_str))
+				} else if error_str.contains("Resources exhausted") {
+					write!(f, "Memory limit exceeded: {}. Raise --memory-limit to leave room for spilling", simplify_datafusion_error(&error_str))
 				} else if error_str.contains("Column") && error_str.contains("not found") {
 					write!(f, "Column error: {}", simplify_column_error(&error_str))
 				} else {
@@ -94,6 +99,197 @@ impl fmt::Display for NailError {
 	}
 }
 
+/// Broad error classes, each with its own process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+	/// Failures while processing data (query execution, statistics, conversions)
+	Processing,
+	/// Invalid command line arguments or option values
+	Usage,
+	/// Input file does not exist
+	NotFound,
+	/// Unsupported, corrupted or undecodable input
+	Format,
+	/// Missing columns or schema mismatches
+	Schema,
+	/// Other I/O failures such as permission errors
+	Io,
+	/// Memory or other resource limits exceeded
+	Resources,
+}
+
+impl ErrorCategory {
+	pub fn exit_code(self) -> i32 {
+		match self {
+			ErrorCategory::Processing => 1,
+			ErrorCategory::Usage => 2,
+			ErrorCategory::NotFound => 3,
+			ErrorCategory::Format => 4,
+			ErrorCategory::Schema => 5,
+			ErrorCategory::Io => 6,
+			ErrorCategory::Resources => 7,
+		}
+	}
+}
+
+/// Machine-readable description of an error, printed by `--error-format json`.
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+	pub code: &'static str,
+	pub category: ErrorCategory,
+	pub exit_code: i32,
+	pub message: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub column: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub file: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub argument: Option<String>,
+	/// Unmodified text of the underlying library error, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<String>,
+	pub hints: Vec<String>,
+}
+
+impl NailError {
+	/// Stable identifier of the error variant; never changes between releases.
+	pub fn code(&self) -> &'static str {
+		match self {
+			NailError::Io(_) => "io",
+			NailError::DataFusion(_) => "datafusion",
+			NailError::Arrow(_) => "arrow",
+			NailError::Parquet(_) => "parquet",
+			NailError::Regex(_) => "regex",
+			NailError::SerdeJson(_) => "json",
+			NailError::InvalidArgument(_) => "invalid_argument",
+			NailError::FileNotFound(_) => "file_not_found",
+			NailError::UnsupportedFormat(_) => "unsupported_format",
+			NailError::ColumnNotFound(_) => "column_not_found",
+			NailError::Statistics(_) => "statistics",
+		}
+	}
+	
+	pub fn category(&self) -> ErrorCategory {
+		match self {
+			NailError::Io(e) => match e.kind() {
+				std::io::ErrorKind::NotFound => ErrorCategory::NotFound,
+				std::io::ErrorKind::InvalidData => ErrorCategory::Format,
+				_ => ErrorCategory::Io,
+			},
+			NailError::DataFusion(e) => {
+				let error_str = e.to_string();
+				if is_missing_object(&error_str) {
+					ErrorCategory::NotFound
+				} else if error_str.contains("Resources exhausted") {
+					ErrorCategory::Resources
+				} else if error_str.contains("UTF-8 error") {
+					ErrorCategory::Format
+				} else if error_str.contains("Schema") || (error_str.contains("Column") && error_str.contains("not found")) {
+					ErrorCategory::Schema
+				} else {
+					ErrorCategory::Processing
+				}
+			},
+			NailError::Parquet(_) | NailError::UnsupportedFormat(_) => ErrorCategory::Format,
+			NailError::Regex(_) | NailError::InvalidArgument(_) => ErrorCategory::Usage,
+			NailError::FileNotFound(_) => ErrorCategory::NotFound,
+			NailError::ColumnNotFound(_) => ErrorCategory::Schema,
+			NailError::Arrow(_) | NailError::SerdeJson(_) | NailError::Statistics(_) => ErrorCategory::Processing,
+		}
+	}
+	
+	pub fn exit_code(&self) -> i32 {
+		self.category().exit_code()
+	}
+	
+	pub fn report(&self) -> ErrorReport {
+		let message = self.to_string();
+		let category = self.category();
+		
+		let source = match self {
+			NailError::Io(e) => Some(e.to_string()),
+			NailError::DataFusion(e) => Some(e.to_string()),
+			NailError::Arrow(e) => Some(e.to_string()),
+			NailError::Parquet(e) => Some(e.to_string()),
+			NailError::Regex(e) => Some(e.to_string()),
+			NailError::SerdeJson(e) => Some(e.to_string()),
+			_ => None,
+		};
+		
+		let file = match self {
+			NailError::FileNotFound(path) => Some(path.clone()),
+			NailError::Io(e) if category == ErrorCategory::NotFound => Some(extract_file_path(e)).filter(|p| p != "unknown file"),
+			NailError::DataFusion(e) if category == ErrorCategory::NotFound => extract_path_from_datafusion_error(&e.to_string()),
+			_ => None,
+		};
+		
+		let column = match self {
+			NailError::ColumnNotFound(msg) => extract_quoted(msg),
+			NailError::DataFusion(e) if category == ErrorCategory::Schema => {
+				let error_str = e.to_string();
+				match error_str.split("No field named ").nth(1) {
+					Some(rest) => rest.split_whitespace().next().map(|name| name.trim_end_matches('.').to_string()),
+					None => extract_quoted(&error_str),
+				}
+			},
+			_ => None,
+		};
+		
+		let argument = match self {
+			NailError::InvalidArgument(msg) => extract_flag(msg),
+			_ => None,
+		};
+		
+		let mut hints = Vec::new();
+		match category {
+			ErrorCategory::NotFound => hints.push("Check that the input path exists and is readable".to_string()),
+			ErrorCategory::Schema => hints.push("Run 'nail headers <file>' to list the available columns".to_string()),
+			ErrorCategory::Resources => hints.push("Raise --memory-limit to leave room for spilling".to_string()),
+			ErrorCategory::Format if message.starts_with("Encoding error") => hints.push("Use --encoding to read non-UTF-8 input".to_string()),
+			ErrorCategory::Format if matches!(self, NailError::UnsupportedFormat(_)) => hints.push("Supported extensions: .parquet, .csv, .tsv, .json, .xlsx".to_string()),
+			ErrorCategory::Usage => hints.push("Run 'nail <command> --help' for the accepted options".to_string()),
+			ErrorCategory::Format | ErrorCategory::Processing | ErrorCategory::Io => {},
+		}
+		if let NailError::ColumnNotFound(msg) = self {
+			if let Some(start) = msg.find("Did you mean") {
+				let end = msg[start..].find('?').map(|i| start + i + 1).unwrap_or(msg.len());
+				hints.push(msg[start..end].to_string());
+			}
+			if let Some(available) = msg.split("Available columns: ").nth(1) {
+				hints.push(format!("Available columns: {}", available));
+			}
+		}
+		
+		ErrorReport {
+			code: self.code(),
+			category,
+			exit_code: category.exit_code(),
+			message,
+			column,
+			file,
+			argument,
+			source,
+			hints,
+		}
+	}
+}
+
+/// First name quoted with '...' or "..." in an error message.
+fn extract_quoted(msg: &str) -> Option<String> {
+	let start = msg.find(['\'', '"'])?;
+	let quote = msg[start..].chars().next()?;
+	let rest = &msg[start + 1..];
+	rest.find(quote).map(|end| rest[..end].to_string())
+}
+
+/// First `--option` mentioned in an error message.
+fn extract_flag(msg: &str) -> Option<String> {
+	msg.split(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '(' | ')' | ',' | '='))
+		.find(|token| token.starts_with("--") && token.len() > 2)
+		.map(|token| token.trim_end_matches(['.', ':']).to_string())
+}
+
 fn extract_file_path(e: &std::io::Error) -> String {
 	e.to_string()
 		.split_whitespace()
@@ -102,6 +298,11 @@ fn extract_file_path(e: &std::io::Error) -> String {
 		.to_string()
 }
 
+fn is_missing_object(error_str: &str) -> bool {
+	(error_str.contains("ObjectStore") && error_str.contains("NotFound"))
+		|| (error_str.contains("Object at location") && error_str.contains("not found"))
+}
+
 fn extract_path_from_datafusion_error(error_str: &str) -> Option<String> {
 	if let Some(start) = error_str.find("path: \"") {
 		let start = start + 7; // Skip 'path: "'
@@ -109,6 +310,12 @@ fn extract_path_from_datafusion_error(error_str: &str) -> Option<String> {
 			return Some(error_str[start..start + end].to_string());
 		}
 	}
+	if let Some(start) = error_str.find("Object at location ") {
+		let start = start + 19; // Skip 'Object at location '
+		if let Some(end) = error_str[start..].find(" not found") {
+			return Some(error_str[start..start + end].to_string());
+		}
+	}
 	None
 }
 
@@ -132,4 +339,37 @@ fn simplify_datafusion_error(error_str: &str) -> String {
 		.replace("Plan(\"", "")
 		.replace("\")", "")
 		.to_string()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_categories_and_exit_codes() {
+		let missing = NailError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, "No such file: /tmp/x.csv"));
+		assert_eq!(missing.code(), "io");
+		assert_eq!(missing.exit_code(), 3);
+		assert_eq!(NailError::InvalidArgument("bad".to_string()).exit_code(), 2);
+		assert_eq!(NailError::UnsupportedFormat("x.xyz".to_string()).exit_code(), 4);
+		assert_eq!(NailError::ColumnNotFound("Column 'a' not found".to_string()).exit_code(), 5);
+		assert_eq!(NailError::Statistics("x".to_string()).exit_code(), 1);
+	}
+
+	#[test]
+	fn test_report_fields() {
+		let report = NailError::ColumnNotFound("Column 'pricee' not found. Did you mean 'price'? Available columns: [\"price\"]".to_string()).report();
+		assert_eq!(report.code, "column_not_found");
+		assert_eq!(report.column.as_deref(), Some("pricee"));
+		assert!(report.hints.contains(&"Did you mean 'price'?".to_string()));
+		assert!(report.hints.iter().any(|h| h.contains("Available columns")));
+
+		let report = NailError::InvalidArgument("A fill value is required (--value)".to_string()).report();
+		assert_eq!(report.argument.as_deref(), Some("--value"));
+
+		let json = serde_json::to_value(NailError::FileNotFound("data.csv".to_string()).report()).unwrap();
+		assert_eq!(json["file"], "data.csv");
+		assert_eq!(json["category"], "not_found");
+		assert_eq!(json["exit_code"], 3);
+		assert!(json.get("column").is_none());
+	}
+}
@@ -1,6 +1,7 @@
 pub mod cli;
 pub mod commands;
 pub mod error;
+pub mod ops;
 pub mod utils;
 
 pub use error::{NailError, NailResult};
\ No newline at end of file
@@ -1,22 +1,48 @@
 mod cli;
 mod commands;
 mod error;
+mod ops;
 mod utils;
 
-use cli::Cli;
-use error::NailResult;
+use cli::{Cli, ErrorFormat};
+use error::{NailError, NailResult};
 pub use crate::commands::select::{select_columns_by_pattern, parse_row_specification};
 
 #[tokio::main]
 async fn main() {
-	if let Err(e) = run().await {
-		eprintln!("Error: {}", e);
-		std::process::exit(1);
+	let cli = match Cli::parse_with_width() {
+		Ok(cli) => cli,
+		Err((e, error_format)) => {
+			report_error(&e, error_format);
+			std::process::exit(e.exit_code());
+		},
+	};
+	
+	// Resolved after config layering, so `error-format` in a config file or NAIL_ERROR_FORMAT applies
+	let error_format = cli.error_format;
+	if let Err(e) = run(cli).await {
+		report_error(&e, error_format);
+		std::process::exit(e.exit_code());
+	}
+}
+
+fn report_error(e: &NailError, format: ErrorFormat) {
+	match format {
+		ErrorFormat::Text => eprintln!("Error: {}", e),
+		ErrorFormat::Json => match serde_json::to_string(&e.report()) {
+			Ok(json) => eprintln!("{}", json),
+			Err(_) => eprintln!("Error: {}", e),
+		},
 	}
 }
 
-async fn run() -> NailResult<()> {
-	let cli = Cli::parse_with_width();
+async fn run(cli: Cli) -> NailResult<()> {
+	let runtime_options = match &cli.command {
+		commands::Commands::Convert(args) => args.read.runtime_options(),
+		commands::Commands::Sql(args) => args.read.runtime_options(),
+		command => command.common_args().map(|c| c.runtime_options()).unwrap_or_default(),
+	};
+	utils::configure_runtime(&runtime_options)?;
 	
 	match cli.command {
 		commands::Commands::Head(args) => commands::head::execute(args).await,
@@ -26,6 +52,7 @@ async fn run() -> NailResult<()> {
 		commands::Commands::Schema(args) => commands::schema::execute(args).await,
 		commands::Commands::Count(args) => commands::count::execute(args).await,
 		commands::Commands::Size(args) => commands::size::execute(args).await,
+		commands::Commands::Sniff(args) => commands::sniff::execute(args).await,
 		commands::Commands::Metadata(args) => commands::metadata::execute(args).await,
 		commands::Commands::Stats(args) => commands::stats::execute(args).await,
 		commands::Commands::Correlations(args) => commands::correlations::execute(args).await,
@@ -39,15 +66,29 @@ async fn run() -> NailResult<()> {
 		commands::Commands::Rename(args) => commands::rename::execute(args).await,
 		commands::Commands::Create(args) => commands::create::execute(args).await,
 		commands::Commands::Id(args) => commands::id::execute(args).await,
+		commands::Commands::Shell(args) => commands::shell::execute(args).await,
 		commands::Commands::Shuffle(args) => commands::shuffle::execute(args).await,
 		commands::Commands::Sample(args) => commands::sample::execute(args).await,
 		commands::Commands::Dedup(args) => commands::dedup::execute(args).await,
 		commands::Commands::Binning(args) => commands::binning::execute(args).await,
+		commands::Commands::Cast(args) => commands::cast::execute(args).await,
+		commands::Commands::Dates(args) => commands::dates::execute(args).await,
+		commands::Commands::Explode(args) => commands::explode::execute(args).await,
+		commands::Commands::Flatten(args) => commands::flatten::execute(args).await,
+		commands::Commands::Nest(args) => commands::nest::execute(args).await,
+		commands::Commands::JsonExtract(args) => commands::json_extract::execute(args).await,
+		commands::Commands::Strings(args) => commands::strings::execute(args).await,
 		commands::Commands::Pivot(args) => commands::pivot::execute(args).await,
 		commands::Commands::Merge(args) => commands::merge::execute(args).await,
 		commands::Commands::Append(args) => commands::append::execute(args).await,
 		commands::Commands::Sort(args) => commands::sort::execute(args).await,
 		commands::Commands::Split(args) => commands::split::execute(args).await,
+		commands::Commands::Pipe(args) => commands::pipe::execute(args).await,
+		commands::Commands::Run(args) => commands::run::execute(args).await,
+		commands::Commands::Sql(args) => commands::sql::execute(args).await,
+		commands::Commands::Completions(args) => commands::completions::execute(args).await,
+		commands::Commands::Manpage(args) => commands::manpage::execute(args).await,
+		commands::Commands::Config(args) => commands::config::execute(args).await,
 		commands::Commands::Convert(args) => commands::convert::execute(args).await,
 		commands::Commands::Optimize(args) => commands::optimize::execute(args).await,
 		commands::Commands::Update(args) => commands::update::execute(args).await,
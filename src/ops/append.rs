@@ -0,0 +1,206 @@
+use datafusion::prelude::*;
+use datafusion::common::DFSchemaRef;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::resolve_column_name;
+
+#[derive(Debug, Clone, Default)]
+pub struct AppendOptions {
+	/// Align columns by name, filling missing ones with nulls, instead of requiring identical schemas.
+	pub ignore_schema: bool,
+}
+
+/// Appends the rows of `other` below `base`, keeping the schema of `base`.
+pub async fn append(base: DataFrame, other: DataFrame, options: AppendOptions) -> NailResult<DataFrame> {
+	let base_schema: DFSchemaRef = base.schema().clone().into();
+	let other_schema: DFSchemaRef = other.schema().clone().into();
+	
+	let aligned_df = if options.ignore_schema {
+		align_schemas(other, &base_schema)?
+	} else if schemas_compatible(&base_schema, &other_schema) {
+		other
+	} else {
+		return Err(NailError::InvalidArgument(
+			"Schema mismatch. Use --ignore-schema to force append.".to_string()
+		));
+	};
+	
+	Ok(base.union(aligned_df)?)
+}
+
+/// Returns true when both schemas have the same column names and types in the same order.
+pub fn schemas_compatible(schema1: &datafusion::common::DFSchemaRef, schema2: &datafusion::common::DFSchemaRef) -> bool {
+	if schema1.fields().len() != schema2.fields().len() {
+		return false;
+	}
+	
+	for (field1, field2) in schema1.fields().iter().zip(schema2.fields().iter()) {
+		if field1.name() != field2.name() || field1.data_type() != field2.data_type() {
+			return false;
+		}
+	}
+	
+	true
+}
+
+fn align_schemas(df: DataFrame, target_schema: &datafusion::common::DFSchemaRef) -> NailResult<DataFrame> {
+	let current_schema: DFSchemaRef = df.schema().clone().into();
+	let mut select_exprs = Vec::new();
+	
+	for target_field in target_schema.fields() {
+		let target_name = target_field.name();
+		
+		if let Ok(resolved_name) = resolve_column_name(&current_schema, target_name) {
+			select_exprs.push(Expr::Column(datafusion::common::Column::new(None::<String>, &resolved_name)));
+		} else {
+			// For missing columns, use typed NULL values instead of default values
+			let null_expr = match target_field.data_type() {
+				datafusion::arrow::datatypes::DataType::Int64 => {
+					lit(datafusion::scalar::ScalarValue::Int64(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Float64 => {
+					lit(datafusion::scalar::ScalarValue::Float64(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Int32 => {
+					lit(datafusion::scalar::ScalarValue::Int32(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Float32 => {
+					lit(datafusion::scalar::ScalarValue::Float32(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Utf8 => {
+					lit(datafusion::scalar::ScalarValue::Utf8(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Boolean => {
+					lit(datafusion::scalar::ScalarValue::Boolean(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Date32 => {
+					lit(datafusion::scalar::ScalarValue::Date32(None)).alias(target_name)
+				},
+				datafusion::arrow::datatypes::DataType::Date64 => {
+					lit(datafusion::scalar::ScalarValue::Date64(None)).alias(target_name)
+				},
+				_ => {
+					// For unknown types, try to create a null of the correct type
+					lit(datafusion::scalar::ScalarValue::try_from(target_field.data_type()).unwrap_or(datafusion::scalar::ScalarValue::Null)).alias(target_name)
+				},
+			};
+			select_exprs.push(null_expr);
+		}
+	}
+	
+	Ok(df.select(select_exprs)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+	use datafusion::arrow::array::{Int64Array, StringArray};
+	use datafusion::arrow::record_batch::RecordBatch;
+	use std::sync::Arc;
+	
+	async fn create_test_dataframe(
+		values: Vec<i64>,
+		names: Vec<&str>
+	) -> NailResult<DataFrame> {
+		let ctx = SessionContext::new();
+		
+		let schema = Arc::new(ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("name", DataType::Utf8, false),
+		]));
+		
+		let id_array = Arc::new(Int64Array::from(values));
+		let name_array = Arc::new(StringArray::from(names));
+		
+		let batch = RecordBatch::try_new(
+			schema.clone(),
+			vec![id_array, name_array],
+		)?;
+		
+		let df = ctx.read_batch(batch)?;
+		Ok(df)
+	}
+	
+
+	#[test]
+	fn test_schemas_compatible() {
+		use datafusion::common::DFSchema;
+		use datafusion::arrow::datatypes::{DataType, Field};
+		
+		// Create compatible schemas by converting from Arrow schemas
+		let arrow_schema1 = ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("name", DataType::Utf8, false),
+		]);
+		let schema1 = Arc::new(DFSchema::try_from(arrow_schema1).unwrap());
+		
+		let arrow_schema2 = ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("name", DataType::Utf8, false),
+		]);
+		let schema2 = Arc::new(DFSchema::try_from(arrow_schema2).unwrap());
+		
+		assert!(schemas_compatible(&schema1, &schema2));
+		
+		// Test incompatible schemas - different number of fields
+		let arrow_schema3 = ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+		]);
+		let schema3 = Arc::new(DFSchema::try_from(arrow_schema3).unwrap());
+		
+		assert!(!schemas_compatible(&schema1, &schema3));
+		
+		// Test incompatible schemas - different field names
+		let arrow_schema4 = ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("description", DataType::Utf8, false),
+		]);
+		let schema4 = Arc::new(DFSchema::try_from(arrow_schema4).unwrap());
+		
+		assert!(!schemas_compatible(&schema1, &schema4));
+		
+		// Test incompatible schemas - different data types
+		let arrow_schema5 = ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("name", DataType::Float64, false),
+		]);
+		let schema5 = Arc::new(DFSchema::try_from(arrow_schema5).unwrap());
+		
+		assert!(!schemas_compatible(&schema1, &schema5));
+	}
+	
+	#[tokio::test]
+	async fn test_align_schemas() {
+		// Create a DataFrame with missing columns
+		let df = create_test_dataframe(vec![1, 2, 3], vec!["a", "b", "c"]).await.unwrap();
+		
+		// Create target schema with additional column
+		use datafusion::common::DFSchema;
+		use datafusion::arrow::datatypes::{DataType, Field};
+		
+		let arrow_target_schema = ArrowSchema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("name", DataType::Utf8, false),
+			Field::new("score", DataType::Float64, false),
+		]);
+		let target_schema = Arc::new(DFSchema::try_from(arrow_target_schema).unwrap());
+		
+		let aligned_df = align_schemas(df, &target_schema).unwrap();
+		let aligned_schema = aligned_df.schema();
+		
+		// Check that aligned schema has all target fields
+		assert_eq!(aligned_schema.fields().len(), 3);
+		assert!(aligned_schema.field_with_name(None, "id").is_ok());
+		assert!(aligned_schema.field_with_name(None, "name").is_ok());
+		assert!(aligned_schema.field_with_name(None, "score").is_ok());
+		
+		// Check that default values are added for missing column
+		let batches = aligned_df.collect().await.unwrap();
+		assert_eq!(batches[0].num_rows(), 3);
+		
+		let score_array = batches[0].column(2).as_any().downcast_ref::<datafusion::arrow::array::Float64Array>().unwrap();
+		assert_eq!(score_array.value(0), 0.0);
+		assert_eq!(score_array.value(1), 0.0);
+		assert_eq!(score_array.value(2), 0.0);
+	}
+}
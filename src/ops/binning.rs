@@ -0,0 +1,379 @@
+use datafusion::arrow::array::{Array, Float64Array, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::functions_aggregate::expr_fn::{count, max, min};
+use datafusion::logical_expr::conditional_expressions::CaseBuilder;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::{column_not_found, select_columns_by_pattern};
+
+#[derive(Debug, Clone, Default)]
+pub enum BinningMethod {
+	#[default]
+	EqualWidth,
+	EqualFrequency,
+	Custom,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bins {
+	/// Number of bins, with edges derived from the data.
+	Count(usize),
+	/// Explicit edges in ascending order.
+	Edges(Vec<f64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct BinningOptions {
+	/// Numeric columns to bin.
+	pub columns: String,
+	pub bins: Bins,
+	pub method: BinningMethod,
+	/// One label per bin; bins are labelled with their range when `None`.
+	pub labels: Option<Vec<String>>,
+	/// Appended to each binned column's name to name its bin column.
+	pub suffix: String,
+	pub drop_original: bool,
+	/// Widen the first equal-width bin slightly so it includes the minimum.
+	pub include_lowest: bool,
+}
+
+impl Default for BinningOptions {
+	fn default() -> Self {
+		Self {
+			columns: String::new(),
+			bins: Bins::Count(10),
+			method: BinningMethod::default(),
+			labels: None,
+			suffix: "_binned".to_string(),
+			drop_original: false,
+			include_lowest: false,
+		}
+	}
+}
+
+/// Adds a bin label column for each selected numeric column.
+pub async fn binning(df: DataFrame, options: BinningOptions) -> NailResult<DataFrame> {
+	let columns = select_columns_by_pattern(df.schema().clone().into(), &options.columns)?;
+
+	// Validate columns exist and are numeric
+	let schema = df.schema();
+	for column in &columns {
+		match schema.field_with_name(None, column) {
+			Ok(field) => {
+				if !field.data_type().is_numeric() {
+					return Err(NailError::InvalidArgument(
+						format!("Column '{}' is not numeric (type: {:?})", column, field.data_type())
+					));
+				}
+			},
+			Err(_) => {
+				let available_cols: Vec<String> = schema.fields().iter().map(|f| f.name().to_string()).collect();
+				return Err(column_not_found(column, &available_cols));
+			}
+		}
+	}
+
+	if let Some(labels) = &options.labels {
+		let expected_labels = match &options.bins {
+			Bins::Count(n) => *n,
+			Bins::Edges(edges) => edges.len() - 1,
+		};
+		if labels.len() != expected_labels {
+			return Err(NailError::InvalidArgument(
+				format!("Number of labels ({}) must match number of bins ({})", labels.len(), expected_labels)
+			));
+		}
+	}
+
+	let mut result_df = df;
+	for column_name in &columns {
+		let edges = bin_edges(&result_df, column_name, &options).await?;
+
+		let value = ident(column_name);
+		let mut case_expr: Option<CaseBuilder> = None;
+		for i in 0..edges.len() - 1 {
+			let lower = edges[i];
+			let upper = edges[i + 1];
+
+			let label = match &options.labels {
+				Some(labels) => labels[i].clone(),
+				None => format!("[{:.2}, {:.2})", lower, upper),
+			};
+
+			let condition = if i == edges.len() - 2 {
+				// Last bin includes upper bound
+				value.clone().gt_eq(lit(lower)).and(value.clone().lt_eq(lit(upper)))
+			} else {
+				value.clone().gt_eq(lit(lower)).and(value.clone().lt(lit(upper)))
+			};
+
+			case_expr = Some(match case_expr {
+				None => when(condition, lit(label)),
+				Some(mut prev) => prev.when(condition, lit(label)),
+			});
+		}
+
+		let binned = match case_expr {
+			Some(mut case_expr) => case_expr.otherwise(lit("NULL"))?,
+			None => lit("NULL"),
+		};
+		result_df = result_df.with_column(&format!("{}{}", column_name, options.suffix), binned)?;
+	}
+
+	if options.drop_original {
+		let keep: Vec<Expr> = result_df.schema().fields().iter()
+			.filter(|f| !columns.contains(f.name()))
+			.map(|f| ident(f.name()))
+			.collect();
+		result_df = result_df.select(keep)?;
+	}
+
+	Ok(result_df)
+}
+
+/// Resolves the bin edges `binning` uses for `column_name`.
+pub async fn bin_edges(df: &DataFrame, column_name: &str, options: &BinningOptions) -> NailResult<Vec<f64>> {
+	match (&options.bins, &options.method) {
+		(Bins::Edges(edges), _) => Ok(edges.clone()),
+		(Bins::Count(_), BinningMethod::Custom) => Err(NailError::InvalidArgument(
+			"Custom binning method requires bin edges to be specified".to_string()
+		)),
+		(Bins::Count(n), BinningMethod::EqualWidth) => {
+			let (min_val, max_val) = column_range(df, column_name).await?;
+			Ok(calculate_equal_width_edges(min_val, max_val, *n, options.include_lowest))
+		},
+		(Bins::Count(n), BinningMethod::EqualFrequency) => {
+			calculate_equal_frequency_edges(df, column_name, *n).await
+		},
+	}
+}
+
+/// Parses a bin count such as `5` or comma-separated edges such as `0,10,50,100`.
+pub fn parse_bins(bins_str: &str) -> NailResult<Bins> {
+	// Check if it's a single number (number of bins)
+	if let Ok(n) = bins_str.parse::<usize>() {
+		if n == 0 {
+			return Err(NailError::InvalidArgument(
+				"Number of bins must be greater than 0".to_string()
+			));
+		}
+		return Ok(Bins::Count(n));
+	}
+
+	// Otherwise, try to parse as comma-separated edges
+	let edges: Result<Vec<f64>, _> = bins_str
+		.split(',')
+		.map(|s| s.trim().parse::<f64>())
+		.collect();
+
+	match edges {
+		Ok(mut edge_vec) => {
+			if edge_vec.len() < 2 {
+				return Err(NailError::InvalidArgument(
+					"At least 2 bin edges must be specified".to_string()
+				));
+			}
+			// Sort edges to ensure they're in ascending order
+			edge_vec.sort_by(|a, b| a.total_cmp(b));
+			Ok(Bins::Edges(edge_vec))
+		},
+		Err(_) => Err(NailError::InvalidArgument(
+			format!("Invalid bins specification: '{}'. Use a number (e.g., '5') or comma-separated edges (e.g., '0,10,50,100')", bins_str)
+		))
+	}
+}
+
+async fn column_range(df: &DataFrame, column_name: &str) -> NailResult<(f64, f64)> {
+	let stats_batch = df.clone()
+		.aggregate(
+			vec![],
+			vec![
+				min(ident(column_name)).alias("min_val"),
+				max(ident(column_name)).alias("max_val"),
+				count(ident(column_name)).alias("count"),
+			]
+		)?
+		.collect()
+		.await?;
+	if stats_batch.is_empty() || stats_batch[0].num_rows() == 0 {
+		return Err(NailError::InvalidArgument(
+			format!("No data found in column '{}'", column_name)
+		));
+	}
+
+	let min_val = extract_float_value(&stats_batch[0], 0, 0)?;
+	let max_val = extract_float_value(&stats_batch[0], 1, 0)?;
+	Ok((min_val, max_val))
+}
+
+fn calculate_equal_width_edges(min_val: f64, max_val: f64, n_bins: usize, include_lowest: bool) -> Vec<f64> {
+	let mut edges = Vec::with_capacity(n_bins + 1);
+	let width = (max_val - min_val) / n_bins as f64;
+
+	for i in 0..=n_bins {
+		edges.push(min_val + (i as f64) * width);
+	}
+
+	// Adjust for floating point precision
+	if include_lowest && !edges.is_empty() {
+		edges[0] -= f64::EPSILON * edges[0].abs();
+	}
+
+	edges
+}
+
+async fn calculate_equal_frequency_edges(df: &DataFrame, column_name: &str, n_bins: usize) -> NailResult<Vec<f64>> {
+	// Get sorted values to calculate quantiles
+	let batches = df.clone()
+		.select(vec![cast(ident(column_name), DataType::Float64).alias("value")])?
+		.filter(col("value").is_not_null())?
+		.sort(vec![col("value").sort(true, true)])?
+		.collect()
+		.await?;
+
+	let mut all_values = Vec::new();
+	for batch in batches {
+		let float_array = batch.column(0).as_any().downcast_ref::<Float64Array>()
+			.ok_or_else(|| NailError::InvalidArgument(
+				format!("Column '{}' must be numeric for equal-frequency binning", column_name)
+			))?;
+		all_values.extend(float_array.values().iter().copied());
+	}
+
+	if all_values.is_empty() {
+		return Err(NailError::InvalidArgument(
+			format!("No valid values found in column '{}'", column_name)
+		));
+	}
+
+	// Calculate quantile positions
+	let mut edges = Vec::with_capacity(n_bins + 1);
+	edges.push(all_values[0]); // First value (min)
+
+	for i in 1..n_bins {
+		let quantile_pos = (i as f64 / n_bins as f64) * (all_values.len() - 1) as f64;
+		let index = quantile_pos.floor() as usize;
+		let fraction = quantile_pos - quantile_pos.floor();
+
+		let value = if index + 1 < all_values.len() {
+			all_values[index] + fraction * (all_values[index + 1] - all_values[index])
+		} else {
+			all_values[index]
+		};
+
+		edges.push(value);
+	}
+
+	edges.push(all_values[all_values.len() - 1]); // Last value (max)
+
+	// Remove duplicates and ensure edges are increasing
+	edges.dedup_by(|a, b| (*a - *b).abs() < f64::EPSILON);
+
+	Ok(edges)
+}
+
+fn extract_float_value(batch: &RecordBatch, col_idx: usize, row_idx: usize) -> NailResult<f64> {
+	let array = batch.column(col_idx);
+
+	if let Some(float_array) = array.as_any().downcast_ref::<Float64Array>() {
+		if float_array.is_null(row_idx) {
+			return Err(NailError::InvalidArgument("Null value encountered".to_string()));
+		}
+		Ok(float_array.value(row_idx))
+	} else if let Some(int_array) = array.as_any().downcast_ref::<Int64Array>() {
+		if int_array.is_null(row_idx) {
+			return Err(NailError::InvalidArgument("Null value encountered".to_string()));
+		}
+		Ok(int_array.value(row_idx) as f64)
+	} else {
+		Err(NailError::InvalidArgument(
+			format!("Unexpected data type for numeric value: {:?}", array.data_type())
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::StringArray;
+	use datafusion::arrow::datatypes::{Field, Schema};
+	use std::sync::Arc;
+
+	#[test]
+	fn test_parse_bins() {
+		// Test single number (number of bins)
+		assert_eq!(parse_bins("5").unwrap(), Bins::Count(5));
+
+		// Test comma-separated edges
+		assert_eq!(parse_bins("0,10,20,30").unwrap(), Bins::Edges(vec![0.0, 10.0, 20.0, 30.0]));
+
+		// Test invalid input
+		assert!(parse_bins("0").is_err());
+		assert!(parse_bins("abc").is_err());
+		assert!(parse_bins("10,20").is_ok()); // At least 2 edges is valid
+		assert!(parse_bins("10,").is_err());
+
+		// Test edge sorting
+		assert_eq!(parse_bins("30,10,0,20").unwrap(), Bins::Edges(vec![0.0, 10.0, 20.0, 30.0]));
+	}
+
+	#[test]
+	fn test_calculate_equal_width_edges() {
+		// Test basic equal width binning
+		let edges = calculate_equal_width_edges(0.0, 100.0, 5, false);
+		assert_eq!(edges, vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+
+		// Test with include_lowest
+		let edges = calculate_equal_width_edges(0.0, 100.0, 5, true);
+		assert!(edges[0] <= 0.0); // Should be less than or equal to 0
+
+		// Test with negative range
+		let edges = calculate_equal_width_edges(-50.0, 50.0, 4, false);
+		assert_eq!(edges[0], -50.0);
+		assert_eq!(edges[4], 50.0);
+		assert_eq!(edges[2], 0.0); // Middle should be 0
+	}
+
+	#[test]
+	fn test_extract_float_value() {
+		// Create test batch with different numeric types
+		let schema = Arc::new(Schema::new(vec![
+			Field::new("float_col", DataType::Float64, false),
+			Field::new("int_col", DataType::Int64, false),
+		]));
+
+		let batch = RecordBatch::try_new(
+			schema,
+			vec![
+				Arc::new(Float64Array::from(vec![1.5, 2.5, 3.5])),
+				Arc::new(Int64Array::from(vec![10, 20, 30])),
+			],
+		).unwrap();
+
+		assert_eq!(extract_float_value(&batch, 0, 0).unwrap(), 1.5);
+		assert_eq!(extract_float_value(&batch, 0, 2).unwrap(), 3.5);
+
+		// Test int extraction (should convert to float)
+		assert_eq!(extract_float_value(&batch, 1, 0).unwrap(), 10.0);
+		assert_eq!(extract_float_value(&batch, 1, 2).unwrap(), 30.0);
+	}
+
+	#[tokio::test]
+	async fn test_binning_equal_frequency_on_integers() {
+		let df = SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1), (2), (3), (4)) AS t(n)"
+		).await.unwrap();
+		let options = BinningOptions {
+			columns: "n".to_string(),
+			bins: Bins::Count(2),
+			method: BinningMethod::EqualFrequency,
+			labels: Some(vec!["low".to_string(), "high".to_string()]),
+			drop_original: true,
+			..Default::default()
+		};
+		let batches = binning(df, options).await.unwrap().collect().await.unwrap();
+		let labels = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+		assert_eq!(labels.iter().flatten().collect::<Vec<_>>(), vec!["low", "low", "high", "high"]);
+	}
+}
@@ -0,0 +1,466 @@
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::compute::{cast_with_options, CastOptions as ArrowCastOptions};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::FormatOptions;
+use datafusion::prelude::*;
+use regex::Regex;
+use std::sync::{Arc, LazyLock};
+use crate::error::{NailError, NailResult};
+use crate::utils::collect_in_session;
+use crate::utils::io::dataframe_from_batches;
+use crate::utils::column::select_columns_by_pattern;
+
+/// What to do with values that cannot be converted to the target type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum CastErrorPolicy {
+	/// Stop with an error listing the failing values.
+	#[default]
+	Fail,
+	/// Replace the failing values with nulls.
+	Null,
+	/// Leave a column with failing values as strings.
+	KeepAsString,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CastOptions {
+	/// `(column selector, target type)` pairs such as `("price", "float64")`.
+	pub columns: Vec<(String, String)>,
+	pub on_error: CastErrorPolicy,
+	/// String numbers use `,` as the decimal separator and `.` for thousands (`1.234,56`).
+	pub decimal_comma: bool,
+}
+
+/// Conversion failures in one column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastReport {
+	pub column: String,
+	pub target: DataType,
+	pub failed: usize,
+	/// A few of the values that failed, for error messages.
+	pub examples: Vec<String>,
+	/// The column was left as strings because of `CastErrorPolicy::KeepAsString`.
+	pub kept_as_string: bool,
+}
+
+const MAX_EXAMPLES: usize = 3;
+
+/// Currency codes before or after the amount (`EUR 5`, `5 USD`) and currency symbols.
+static CURRENCY: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"^[A-Z]{3}\s+|\s+[A-Z]{3}$|\p{Sc}").unwrap()
+});
+
+/// Parses `column=type` pairs separated by commas, e.g. `price=float64,ts=timestamp[ms, UTC]`.
+pub fn parse_cast_specs(spec: &str) -> NailResult<Vec<(String, String)>> {
+	let mut pairs = Vec::new();
+	let mut depth = 0i32;
+	let mut quoted = false;
+	let mut start = 0;
+	for (index, c) in spec.char_indices().chain(std::iter::once((spec.len(), ','))) {
+		match c {
+			'"' => quoted = !quoted,
+			'(' | '[' if !quoted => depth += 1,
+			')' | ']' if !quoted => depth -= 1,
+			',' if !quoted && depth == 0 => {
+				let pair = spec[start..index].trim();
+				let (column, type_name) = pair.rsplit_once('=')
+					.filter(|(column, type_name)| !column.trim().is_empty() && !type_name.trim().is_empty())
+					.ok_or_else(|| NailError::InvalidArgument(format!(
+						"Invalid cast spec '{}': expected column=type", pair
+					)))?;
+				pairs.push((column.trim().to_string(), type_name.trim().to_string()));
+				start = index + 1;
+			},
+			_ => {},
+		}
+	}
+	Ok(pairs)
+}
+
+/// Casts the selected columns to new types and reports, per column, how many values
+/// could not be converted.
+pub async fn cast(df: DataFrame, options: CastOptions) -> NailResult<(DataFrame, Vec<CastReport>)> {
+	let mut targets: Vec<(String, DataType)> = Vec::new();
+	for (selector, type_name) in &options.columns {
+		let data_type = parse_type_name(type_name)?;
+		for column in select_columns_by_pattern(df.schema().clone().into(), selector)? {
+			targets.retain(|(name, _)| *name != column);
+			targets.push((column, data_type.clone()));
+		}
+	}
+
+	let input_schema = df.schema().as_arrow().clone();
+	let (ctx, batches) = collect_in_session(df).await?;
+
+	let mut reports: Vec<CastReport> = targets.iter()
+		.map(|(column, target)| CastReport {
+			column: column.clone(),
+			target: target.clone(),
+			failed: 0,
+			examples: Vec::new(),
+			kept_as_string: false,
+		})
+		.collect();
+
+	// Convert every batch first so failures are counted over the whole column
+	let mut converted: Vec<Vec<ArrayRef>> = Vec::with_capacity(batches.len());
+	for batch in &batches {
+		let mut columns = batch.columns().to_vec();
+		for (report, (column, target)) in reports.iter_mut().zip(&targets) {
+			let index = input_schema.index_of(column)?;
+			let source = batch.column(index);
+			let result = cast_array(source, target, options.decimal_comma)?;
+			record_failures(report, source, &result)?;
+			columns[index] = result;
+		}
+		converted.push(columns);
+	}
+
+	let failed: Vec<&CastReport> = reports.iter().filter(|r| r.failed > 0).collect();
+	if options.on_error == CastErrorPolicy::Fail && !failed.is_empty() {
+		let details: Vec<String> = failed.iter()
+			.map(|r| format!(
+				"{} value(s) in '{}' cannot be converted to {} (e.g. {})",
+				r.failed, r.column, r.target,
+				r.examples.iter().map(|e| format!("'{}'", e)).collect::<Vec<_>>().join(", ")
+			))
+			.collect();
+		return Err(NailError::InvalidArgument(format!(
+			"{}. Use --on-error null to replace them with nulls or --on-error keep-as-string to leave those columns unchanged",
+			details.join("; ")
+		)));
+	}
+
+	if options.on_error == CastErrorPolicy::KeepAsString {
+		for report in reports.iter_mut().filter(|r| r.failed > 0) {
+			let index = input_schema.index_of(&report.column)?;
+			for (batch, columns) in batches.iter().zip(converted.iter_mut()) {
+				columns[index] = cast_with_options(batch.column(index), &DataType::Utf8, &ArrowCastOptions::default())?;
+			}
+			report.kept_as_string = true;
+		}
+	}
+
+	let fields: Vec<Field> = input_schema.fields().iter()
+		.map(|field| {
+			let target = reports.iter().find(|r| r.column == *field.name()).map(|r| {
+				if r.kept_as_string { DataType::Utf8 } else { r.target.clone() }
+			});
+			match target {
+				Some(data_type) => Field::new(field.name(), data_type, true),
+				None => field.as_ref().clone(),
+			}
+		})
+		.collect();
+	let schema = Arc::new(Schema::new(fields));
+
+	let batches = converted.into_iter()
+		.map(|columns| RecordBatch::try_new(schema.clone(), columns))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok((dataframe_from_batches(&ctx, schema, batches)?, reports))
+}
+
+/// Counts values that were present before the cast and null after it.
+fn record_failures(report: &mut CastReport, source: &ArrayRef, result: &ArrayRef) -> NailResult<()> {
+	let failures: Vec<usize> = (0..source.len())
+		.filter(|&i| source.is_valid(i) && result.is_null(i))
+		.collect();
+	if failures.is_empty() {
+		return Ok(());
+	}
+
+	report.failed += failures.len();
+	if report.examples.len() < MAX_EXAMPLES {
+		let text = cast_with_options(source, &DataType::Utf8, &ArrowCastOptions::default())?;
+		let text = text.as_any().downcast_ref::<StringArray>()
+			.ok_or_else(|| NailError::InvalidArgument("Cannot display the values that failed to convert".to_string()))?;
+		for i in failures {
+			let example = text.value(i).to_string();
+			if report.examples.len() == MAX_EXAMPLES {
+				break;
+			}
+			if !report.examples.contains(&example) {
+				report.examples.push(example);
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Casts one array; values that cannot be converted become nulls.
+fn cast_array(array: &ArrayRef, target: &DataType, decimal_comma: bool) -> NailResult<ArrayRef> {
+	let lenient = ArrowCastOptions { safe: true, format_options: FormatOptions::default() };
+
+	let strings = match array.data_type() {
+		DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View if target.is_numeric() => {
+			cast_with_options(array, &DataType::Utf8, &lenient)?
+		},
+		_ => return Ok(cast_with_options(array, target, &lenient)?),
+	};
+	let strings = strings.as_any().downcast_ref::<StringArray>()
+		.ok_or_else(|| NailError::InvalidArgument("Expected a string column".to_string()))?;
+
+	let numbers: Vec<Option<(String, bool)>> = strings.iter()
+		.map(|value| value.and_then(|v| normalize_number(v, decimal_comma)))
+		.collect();
+
+	let parsed: ArrayRef = if target.is_integer() {
+		Arc::new(numbers.iter().map(|n| n.as_ref().and_then(|(text, percent)| parse_integer(text, *percent))).collect::<Int64Array>())
+	} else if target.is_floating() {
+		Arc::new(numbers.iter().map(|n| n.as_ref().and_then(|(text, percent)| parse_float(text, *percent))).collect::<Float64Array>())
+	} else {
+		// Decimals parse the canonical text so no precision is lost through floats
+		Arc::new(numbers.iter().map(|n| n.as_ref().and_then(|(text, percent)| {
+			if *percent { parse_float(text, true).map(|v| v.to_string()) } else { Some(text.clone()) }
+		})).collect::<StringArray>())
+	};
+	Ok(cast_with_options(&parsed, target, &lenient)?)
+}
+
+fn parse_integer(text: &str, percent: bool) -> Option<i64> {
+	if !percent {
+		if let Ok(value) = text.parse::<i64>() {
+			return Some(value);
+		}
+	}
+	let value = parse_float(text, percent)?;
+	// Whole numbers written as `12.0` are fine; `12.5` is not an integer
+	if value.fract() == 0.0 && value.abs() < 9.2e18 {
+		Some(value as i64)
+	} else {
+		None
+	}
+}
+
+fn parse_float(text: &str, percent: bool) -> Option<f64> {
+	let value: f64 = text.parse().ok()?;
+	Some(if percent { value / 100.0 } else { value })
+}
+
+/// Canonical `-1234.5` form of a number written with thousands separators, a decimal
+/// comma, currency symbols or codes, a percent sign or accounting parentheses.
+/// Returns the number text and whether it was a percentage.
+pub fn normalize_number(text: &str, decimal_comma: bool) -> Option<(String, bool)> {
+	let mut text = text.trim();
+	if text.is_empty() {
+		return None;
+	}
+
+	let mut negative = false;
+	if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+		negative = true;
+		text = inner.trim();
+	}
+
+	let stripped = CURRENCY.replace_all(text, "");
+	let mut text = stripped.trim();
+
+	let percent = text.ends_with('%');
+	text = text.trim_end_matches('%').trim();
+
+	if let Some(rest) = text.strip_prefix('-') {
+		negative = !negative;
+		text = rest;
+	} else if let Some(rest) = text.strip_suffix('-') {
+		negative = !negative;
+		text = rest;
+	} else if let Some(rest) = text.strip_prefix('+') {
+		text = rest;
+	}
+	// Currency symbols may sit between the sign and the digits (`-$5`)
+	let text = CURRENCY.replace_all(text.trim(), "");
+
+	let (thousands, decimal) = if decimal_comma { ('.', ',') } else { (',', '.') };
+	let (mantissa, exponent) = match text.find(['e', 'E']) {
+		Some(i) => (&text[..i], Some(&text[i + 1..])),
+		None => (&text[..], None),
+	};
+	let (integer, fraction) = match mantissa.split_once(decimal) {
+		Some((integer, fraction)) => (integer, Some(fraction)),
+		None => (mantissa, None),
+	};
+
+	let mut number = String::with_capacity(text.len() + 1);
+	if negative {
+		number.push('-');
+	}
+	number.push_str(&integer_digits(integer, thousands)?);
+	if let Some(fraction) = fraction {
+		if !fraction.chars().all(|c| c.is_ascii_digit()) {
+			return None;
+		}
+		number.push('.');
+		number.push_str(fraction);
+	}
+	if !number.chars().any(|c| c.is_ascii_digit()) {
+		return None;
+	}
+	if let Some(exponent) = exponent {
+		let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+		if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+			return None;
+		}
+		number.push('e');
+		number.push_str(exponent);
+	}
+	Some((number, percent))
+}
+
+/// Digits of the integer part. A thousands separator (or a space or apostrophe) is only
+/// accepted between complete groups of three digits, so `1,5` is not read as `15`.
+fn integer_digits(integer: &str, thousands: char) -> Option<String> {
+	let is_separator = |c: char| c == thousands || matches!(c, ' ' | '\u{a0}' | '\u{202f}' | '\'' | '\u{2019}');
+	let Some(separator) = integer.chars().find(|c| is_separator(*c)) else {
+		return integer.chars().all(|c| c.is_ascii_digit()).then(|| integer.to_string());
+	};
+
+	let groups: Vec<&str> = integer.split(separator).collect();
+	let valid = groups.iter().all(|g| g.chars().all(|c| c.is_ascii_digit()))
+		&& (1..=3).contains(&groups[0].len())
+		&& groups[1..].iter().all(|g| g.len() == 3);
+	valid.then(|| groups.concat())
+}
+
+/// Parses a type name such as `float64`, `utf8`, `timestamp[ms]` or `decimal(10,2)`.
+pub fn parse_type_name(name: &str) -> NailResult<DataType> {
+	let normalized = name.trim().to_lowercase().replace(' ', "");
+	let data_type = match normalized.as_str() {
+		"int8" | "i8" | "tinyint" => DataType::Int8,
+		"int16" | "i16" | "smallint" => DataType::Int16,
+		"int32" | "i32" | "integer" => DataType::Int32,
+		"int64" | "i64" | "int" | "bigint" | "long" => DataType::Int64,
+		"uint8" | "u8" => DataType::UInt8,
+		"uint16" | "u16" => DataType::UInt16,
+		"uint32" | "u32" => DataType::UInt32,
+		"uint64" | "u64" => DataType::UInt64,
+		"float32" | "f32" | "float" | "real" => DataType::Float32,
+		"float64" | "f64" | "double" => DataType::Float64,
+		"utf8" | "string" | "str" | "text" | "varchar" => DataType::Utf8,
+		"bool" | "boolean" => DataType::Boolean,
+		"date" | "date32" => DataType::Date32,
+		"date64" => DataType::Date64,
+		"timestamp" => DataType::Timestamp(TimeUnit::Microsecond, None),
+		_ => return parse_parameterized_type(&normalized, name),
+	};
+	Ok(data_type)
+}
+
+fn parse_parameterized_type(normalized: &str, name: &str) -> NailResult<DataType> {
+	let invalid = || NailError::InvalidArgument(format!(
+		"Unknown type '{}'. Use one of int8..int64, uint8..uint64, float32, float64, decimal(p,s), utf8, bool, date, timestamp[s|ms|us|ns(,tz)]",
+		name.trim()
+	));
+
+	if let Some(params) = normalized.strip_prefix("timestamp[").and_then(|p| p.strip_suffix(']')) {
+		let (unit, tz) = match params.split_once(',') {
+			Some((unit, tz)) => (unit, Some(name_case_tz(name, tz))),
+			None => (params, None),
+		};
+		let unit = match unit {
+			"s" => TimeUnit::Second,
+			"ms" => TimeUnit::Millisecond,
+			"us" | "µs" => TimeUnit::Microsecond,
+			"ns" => TimeUnit::Nanosecond,
+			_ => return Err(invalid()),
+		};
+		return Ok(DataType::Timestamp(unit, tz.map(Into::into)));
+	}
+
+	if let Some(params) = normalized.strip_prefix("decimal(").and_then(|p| p.strip_suffix(')')) {
+		let (precision, scale) = params.split_once(',').unwrap_or((params, "0"));
+		let precision: u8 = precision.parse().map_err(|_| invalid())?;
+		let scale: i8 = scale.parse().map_err(|_| invalid())?;
+		if precision == 0 || precision > 38 || scale.unsigned_abs() > precision {
+			return Err(invalid());
+		}
+		return Ok(DataType::Decimal128(precision, scale));
+	}
+
+	Err(invalid())
+}
+
+/// Time zone names keep their original case (`Europe/Paris`, `UTC`).
+fn name_case_tz(name: &str, lowered_tz: &str) -> String {
+	name.split_once(',')
+		.map(|(_, tz)| tz.trim().trim_end_matches(']').trim().to_string())
+		.unwrap_or_else(|| lowered_tz.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_cast_specs() {
+		assert_eq!(
+			parse_cast_specs("price=float64, ts=timestamp[ms, UTC],amount=decimal(10,2)").unwrap(),
+			vec![
+				("price".to_string(), "float64".to_string()),
+				("ts".to_string(), "timestamp[ms, UTC]".to_string()),
+				("amount".to_string(), "decimal(10,2)".to_string()),
+			]
+		);
+		assert!(parse_cast_specs("price").is_err());
+		assert!(parse_cast_specs("price=").is_err());
+	}
+
+	#[test]
+	fn test_parse_type_name() {
+		assert_eq!(parse_type_name("float64").unwrap(), DataType::Float64);
+		assert_eq!(parse_type_name("UTF8").unwrap(), DataType::Utf8);
+		assert_eq!(parse_type_name("timestamp[ms]").unwrap(), DataType::Timestamp(TimeUnit::Millisecond, None));
+		assert_eq!(parse_type_name("timestamp[us, Europe/Paris]").unwrap(), DataType::Timestamp(TimeUnit::Microsecond, Some("Europe/Paris".into())));
+		assert_eq!(parse_type_name("decimal(10, 2)").unwrap(), DataType::Decimal128(10, 2));
+		assert!(parse_type_name("timestamp[hours]").is_err());
+		assert!(parse_type_name("money").unwrap_err().to_string().contains("Unknown type 'money'"));
+	}
+
+	#[test]
+	fn test_normalize_number() {
+		let n = |text: &str| normalize_number(text, false);
+		assert_eq!(n("1,234.56"), Some(("1234.56".to_string(), false)));
+		assert_eq!(n("$ 1,000"), Some(("1000".to_string(), false)));
+		assert_eq!(n("-€12.5"), Some(("-12.5".to_string(), false)));
+		assert_eq!(n("(300)"), Some(("-300".to_string(), false)));
+		assert_eq!(n("12.5 %"), Some(("12.5".to_string(), true)));
+		assert_eq!(n("1.5e3"), Some(("1.5e3".to_string(), false)));
+		assert_eq!(n("EUR 99"), Some(("99".to_string(), false)));
+		assert_eq!(normalize_number("1.234,56 €", true), Some(("1234.56".to_string(), false)));
+		assert_eq!(normalize_number("1 234,5", true), Some(("1234.5".to_string(), false)));
+		assert_eq!(n("n/a"), None);
+		assert_eq!(n("1.2.3"), None);
+		assert_eq!(n("-"), None);
+
+		// A thousands separator outside complete groups of three is not a number
+		assert_eq!(normalize_number("1.5", true), None);
+		assert_eq!(normalize_number("2.25", true), None);
+		assert_eq!(n("1,5"), None);
+		assert_eq!(n("12,34,567"), None);
+		assert_eq!(n("1,234,567.5"), Some(("1234567.5".to_string(), false)));
+		assert_eq!(n("1,234.5,6"), None);
+		assert_eq!(n("1'234'567"), Some(("1234567".to_string(), false)));
+		assert_eq!(n(".5"), Some((".5".to_string(), false)));
+		assert_eq!(n("1e"), None);
+	}
+
+	#[test]
+	fn test_cast_array() {
+		let strings: ArrayRef = Arc::new(StringArray::from(vec![Some("1,200"), Some("12.0"), Some("12.5"), Some("oops"), None]));
+		let ints = cast_array(&strings, &DataType::Int64, false).unwrap();
+		let ints = ints.as_any().downcast_ref::<Int64Array>().unwrap();
+		assert_eq!(ints.iter().collect::<Vec<_>>(), vec![Some(1200), Some(12), None, None, None]);
+
+		let percents: ArrayRef = Arc::new(StringArray::from(vec!["50%", "1,5"]));
+		let floats = cast_array(&percents, &DataType::Float64, true).unwrap();
+		let floats = floats.as_any().downcast_ref::<Float64Array>().unwrap();
+		assert_eq!(floats.iter().collect::<Vec<_>>(), vec![Some(0.5), Some(1.5)]);
+
+		let mut report = CastReport { column: "x".to_string(), target: DataType::Int64, failed: 0, examples: Vec::new(), kept_as_string: false };
+		let result = cast_array(&strings, &DataType::Int64, false).unwrap();
+		record_failures(&mut report, &strings, &result).unwrap();
+		assert_eq!(report.failed, 2);
+		assert_eq!(report.examples, vec!["12.5".to_string(), "oops".to_string()]);
+	}
+}
@@ -0,0 +1,293 @@
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, StringArray};
+use datafusion::arrow::compute::cast as cast_array;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::JoinType;
+use datafusion::functions_aggregate::expr_fn::{corr, count, sum};
+use datafusion::functions_window::expr_fn::row_number;
+use datafusion::logical_expr::ExprFunctionExt;
+use datafusion::prelude::*;
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal, StudentsT};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+use crate::utils::session_context;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CorrelationType {
+	#[default]
+	Pearson,
+	Kendall,
+	Spearman,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorrelationsOptions {
+	/// Column selector; every numeric column when `None`.
+	pub columns: Option<String>,
+	pub correlation_type: CorrelationType,
+	/// One row per column with a `corr_with_<column>` value for every column, instead of one row per pair.
+	pub matrix: bool,
+	/// Add Fisher z, t and chi-squared p-values to each pair; ignored for the matrix.
+	pub include_tests: bool,
+	/// Decimal places correlations are rounded to.
+	pub digits: usize,
+}
+
+impl Default for CorrelationsOptions {
+	fn default() -> Self {
+		Self {
+			columns: None,
+			correlation_type: CorrelationType::default(),
+			matrix: false,
+			include_tests: false,
+			digits: 4,
+		}
+	}
+}
+
+/// Correlates every pair of selected numeric columns.
+pub async fn correlations(df: DataFrame, options: CorrelationsOptions) -> NailResult<DataFrame> {
+	let columns = numeric_columns(&df, options.columns.as_deref())?;
+
+	let mut values = HashMap::new();
+	for (i, col1) in columns.iter().enumerate() {
+		for col2 in columns.iter().skip(i + 1) {
+			let value = correlation(&df, col1, col2, &options.correlation_type).await?;
+			values.insert((col1.clone(), col2.clone()), value);
+			values.insert((col2.clone(), col1.clone()), value);
+		}
+	}
+
+	let batch = if options.matrix {
+		matrix_batch(&columns, &values, options.digits)?
+	} else {
+		let rows = if options.include_tests { Some(df.clone().count().await?) } else { None };
+		pairs_batch(&columns, &values, options.digits, rows)?
+	};
+
+	Ok(session_context(&df).read_batch(batch)?)
+}
+
+fn numeric_columns(df: &DataFrame, selector: Option<&str>) -> NailResult<Vec<String>> {
+	let schema = df.schema();
+	let numeric_columns: Vec<String> = match selector {
+		Some(spec) => {
+			let selected = select_columns_by_pattern(schema.clone().into(), spec)?;
+			let mut numeric_columns = Vec::new();
+			let mut non_numeric_info = Vec::new();
+			for name in selected {
+				let field = schema.field_with_name(None, &name)
+					.map_err(|_| NailError::ColumnNotFound(name.clone()))?;
+				if field.data_type().is_numeric() {
+					numeric_columns.push(name);
+				} else {
+					non_numeric_info.push(format!("'{}' ({:?})", name, field.data_type()));
+				}
+			}
+			if !non_numeric_info.is_empty() {
+				return Err(NailError::Statistics(
+					format!("Correlation requires numeric columns only. Non-numeric columns found: {}. Available numeric columns: {:?}",
+						non_numeric_info.join(", "), numeric_columns)
+				));
+			}
+			numeric_columns
+		},
+		None => schema.fields().iter()
+			.filter(|f| f.data_type().is_numeric())
+			.map(|f| f.name().clone())
+			.collect(),
+	};
+
+	if numeric_columns.len() < 2 {
+		return Err(NailError::Statistics(
+			format!("Need at least 2 numeric columns for correlation. Found {} numeric columns: {:?}",
+				numeric_columns.len(), numeric_columns)
+		));
+	}
+
+	Ok(numeric_columns)
+}
+
+/// Correlation of two columns; `None` when it is undefined, e.g. for a constant column.
+async fn correlation(df: &DataFrame, col1: &str, col2: &str, correlation_type: &CorrelationType) -> NailResult<Option<f64>> {
+	let x = cast(ident(col1), DataType::Float64);
+	let y = cast(ident(col2), DataType::Float64);
+
+	let result = match correlation_type {
+		CorrelationType::Pearson => df.clone()
+			.aggregate(vec![], vec![corr(x, y).alias("correlation")])?,
+		CorrelationType::Spearman => {
+			let rank = |column: &str| -> NailResult<Expr> {
+				Ok(row_number().order_by(vec![ident(column).sort(true, false)]).build()?)
+			};
+			df.clone()
+				.select(vec![rank(col1)?.alias("rank1"), rank(col2)?.alias("rank2")])?
+				.aggregate(vec![], vec![corr(
+					cast(col("rank1"), DataType::Float64),
+					cast(col("rank2"), DataType::Float64),
+				).alias("correlation")])?
+		},
+		CorrelationType::Kendall => {
+			// Kendall's tau-a: (concordant - discordant) pairs over all pairs of rows
+			let indexed = df.clone().select(vec![x.alias("x1"), y.alias("y1"), row_number().alias("rn1")])?;
+			let other = indexed.clone().select(vec![
+				col("x1").alias("x2"),
+				col("y1").alias("y2"),
+				col("rn1").alias("rn2"),
+			])?;
+			let product = (col("x1") - col("x2")) * (col("y1") - col("y2"));
+			let sign = when(product.clone().gt(lit(0.0)), lit(1.0))
+				.when(product.lt(lit(0.0)), lit(-1.0))
+				.otherwise(lit(0.0))?;
+			indexed
+				.join_on(other, JoinType::Inner, vec![col("rn1").lt(col("rn2"))])?
+				.aggregate(vec![], vec![sum(sign).alias("kendall_s"), count(lit(1)).alias("n_pairs")])?
+				.select(vec![(col("kendall_s") / cast(col("n_pairs"), DataType::Float64)).alias("correlation")])?
+		},
+	};
+
+	let batches = result.collect().await?;
+	let Some(batch) = batches.iter().find(|b| b.num_rows() > 0) else {
+		return Ok(None);
+	};
+	let values = cast_array(batch.column(0), &DataType::Float64)?;
+	let values = values.as_any().downcast_ref::<Float64Array>()
+		.ok_or_else(|| NailError::Statistics("Unexpected correlation type".to_string()))?;
+	Ok((values.is_valid(0) && values.value(0).is_finite()).then(|| values.value(0)))
+}
+
+fn round(value: f64, digits: usize) -> f64 {
+	let factor = 10_f64.powi(digits as i32);
+	(value * factor).round() / factor
+}
+
+fn matrix_batch(columns: &[String], values: &HashMap<(String, String), Option<f64>>, digits: usize) -> NailResult<RecordBatch> {
+	// Sort columns for deterministic order
+	let mut sorted_columns = columns.to_vec();
+	sorted_columns.sort();
+
+	let mut fields = vec![Field::new("variable", DataType::Utf8, false)];
+	let mut arrays: Vec<ArrayRef> = vec![Arc::new(StringArray::from(sorted_columns.clone()))];
+	for col2 in &sorted_columns {
+		let column_values: Vec<Option<f64>> = sorted_columns.iter()
+			.map(|col1| if col1 == col2 { Some(1.0) } else { values[&(col1.clone(), col2.clone())] })
+			.map(|value| value.map(|v| round(v, digits)))
+			.collect();
+		fields.push(Field::new(format!("corr_with_{}", col2.replace('.', "_")), DataType::Float64, true));
+		arrays.push(Arc::new(Float64Array::from(column_values)));
+	}
+
+	Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+/// One row per pair of columns; with `rows`, adds p-values for the null hypothesis of no correlation.
+fn pairs_batch(columns: &[String], values: &HashMap<(String, String), Option<f64>>, digits: usize, rows: Option<usize>) -> NailResult<RecordBatch> {
+	let mut col1_vec = Vec::new();
+	let mut col2_vec = Vec::new();
+	let mut corr_vec = Vec::new();
+	let mut p_fisher = Vec::new();
+	let mut p_t = Vec::new();
+	let mut p_chi2 = Vec::new();
+
+	for (i, col1) in columns.iter().enumerate() {
+		for col2 in columns.iter().skip(i + 1) {
+			let r = values[&(col1.clone(), col2.clone())];
+			col1_vec.push(col1.clone());
+			col2_vec.push(col2.clone());
+			corr_vec.push(r.map(|v| round(v, digits)));
+
+			if let Some(n) = rows {
+				let n = n as f64;
+				// Fisher z-test
+				p_fisher.push(r.and_then(|r| {
+					let z = 0.5 * ((1.0 + r) / (1.0 - r)).ln() * (n - 3.0).sqrt();
+					let normal = Normal::new(0.0, 1.0).ok()?;
+					Some(2.0 * (1.0 - normal.cdf(z.abs())))
+				}));
+				// t-test, and the equivalent chi-squared test on t²
+				let t = r.map(|r| r * ((n - 2.0) / (1.0 - r * r)).sqrt());
+				p_t.push(t.and_then(|t| {
+					let student = StudentsT::new(0.0, 1.0, n - 2.0).ok()?;
+					Some(2.0 * (1.0 - student.cdf(t.abs())))
+				}));
+				p_chi2.push(t.and_then(|t| {
+					let chi_squared = ChiSquared::new(1.0).ok()?;
+					Some(1.0 - chi_squared.cdf(t * t))
+				}));
+			}
+		}
+	}
+
+	let mut fields = vec![
+		Field::new("column1", DataType::Utf8, false),
+		Field::new("column2", DataType::Utf8, false),
+		Field::new("correlation", DataType::Float64, true),
+	];
+	let mut arrays: Vec<ArrayRef> = vec![
+		Arc::new(StringArray::from(col1_vec)),
+		Arc::new(StringArray::from(col2_vec)),
+		Arc::new(Float64Array::from(corr_vec)),
+	];
+	if rows.is_some() {
+		for (name, p_values) in [("p_fisher", p_fisher), ("p_t", p_t), ("p_chi2", p_chi2)] {
+			fields.push(Field::new(name, DataType::Float64, true));
+			arrays.push(Arc::new(Float64Array::from(p_values)));
+		}
+	}
+
+	Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 2.0, 5.0, 'a'), (2, 4.0, 3.0, 'b'), (3, 5.0, 4.0, 'c'), (4, 9.0, 1.0, 'd')) AS t(a, b, c, s)"
+		).await.unwrap()
+	}
+
+	async fn correlation_values(df: DataFrame) -> Vec<f64> {
+		let batches = df.collect().await.unwrap();
+		batches[0].column(2).as_any().downcast_ref::<Float64Array>().unwrap().values().to_vec()
+	}
+
+	#[tokio::test]
+	async fn test_rank_correlations() {
+		let options = CorrelationsOptions { correlation_type: CorrelationType::Spearman, ..Default::default() };
+		assert_eq!(correlation_values(correlations(sample_df().await, options).await.unwrap()).await, vec![1.0, -0.8, -0.8]);
+
+		let options = CorrelationsOptions { correlation_type: CorrelationType::Kendall, ..Default::default() };
+		assert_eq!(correlation_values(correlations(sample_df().await, options).await.unwrap()).await, vec![1.0, -0.6667, -0.6667]);
+	}
+
+	#[tokio::test]
+	async fn test_matrix_with_tests() {
+		let options = CorrelationsOptions {
+			columns: Some("a,b".to_string()),
+			matrix: true,
+			include_tests: true,
+			..Default::default()
+		};
+		let batches = correlations(sample_df().await, options).await.unwrap().collect().await.unwrap();
+		let schema = batches[0].schema();
+		let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+		assert_eq!(names, vec!["variable", "corr_with_a", "corr_with_b"]);
+		let diagonal = batches[0].column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+		assert_eq!(diagonal.value(0), 1.0);
+
+		let options = CorrelationsOptions { include_tests: true, ..Default::default() };
+		let batches = correlations(sample_df().await, options).await.unwrap().collect().await.unwrap();
+		assert_eq!(batches[0].num_columns(), 6);
+	}
+
+	#[tokio::test]
+	async fn test_rejects_non_numeric_columns() {
+		let options = CorrelationsOptions { columns: Some("a,s".to_string()), ..Default::default() };
+		let err = correlations(sample_df().await, options).await.unwrap_err();
+		assert!(err.to_string().contains("Non-numeric columns found"));
+	}
+}
@@ -0,0 +1,213 @@
+use datafusion::prelude::DataFrame;
+use regex::Regex;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::column_not_found;
+
+#[derive(Debug, Clone, Default)]
+pub struct CreateOptions {
+    /// `(name, SQL expression)` pairs for the new columns, created in order.
+    pub columns: Vec<(String, String)>,
+    /// SQL predicate applied before the columns are created.
+    pub row_filter: Option<String>,
+}
+
+/// Parses `name=expression` specs. One argument may hold several specs separated by
+/// commas; a comma only starts a new spec outside parentheses and quotes, and only when
+/// it is followed by `name=`, so `round(x, 2)` and `a = b` stay inside one expression.
+pub fn parse_column_specs(spec: &str) -> NailResult<Vec<(String, String)>> {
+    let spec_start = Regex::new(r#"^\s*(?:"(?:[^"]|"")+"|[A-Za-z_][A-Za-z0-9_]*)\s*=(?:[^=]|$)"#)?;
+
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for (index, c) in top_level_chars(spec) {
+        if c == ',' && spec_start.is_match(&spec[index + 1..]) {
+            pieces.push(&spec[start..index]);
+            start = index + 1;
+        }
+    }
+    pieces.push(&spec[start..]);
+
+    pieces.into_iter().map(split_column_spec).collect()
+}
+
+/// Splits one `name=expression` spec at its first top-level `=`.
+fn split_column_spec(spec: &str) -> NailResult<(String, String)> {
+    let invalid = || NailError::InvalidArgument(format!(
+        "Invalid column spec '{}': expected name=expression", spec.trim()
+    ));
+
+    let (index, _) = top_level_chars(spec).find(|&(_, c)| c == '=').ok_or_else(invalid)?;
+    let name = spec[..index].trim();
+    let expr = spec[index + 1..].trim();
+    let name = match name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+        Some(quoted) => quoted.replace("\"\"", "\""),
+        None => name.to_string(),
+    };
+    if name.is_empty() || expr.is_empty() {
+        return Err(invalid());
+    }
+    Ok((name, expr.to_string()))
+}
+
+/// Characters outside quotes and parentheses, with their byte offsets.
+fn top_level_chars(text: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    text.char_indices().filter(move |&(_, c)| {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth -= 1,
+            (None, _) => return depth == 0,
+        }
+        false
+    })
+}
+
+/// Offset of the `)` closing a parenthesis opened just before `text`.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return Some(index),
+            (None, ')') => depth -= 1,
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Rewrites `if(condition, then, else)` calls, which SQL lacks, into `CASE` expressions.
+pub fn rewrite_if_calls(expr: &str) -> NailResult<String> {
+    let call = Regex::new(r"(?i)\bif\s*\(")?;
+    let mut output = String::new();
+    let mut rest = expr;
+
+    while let Some(found) = find_outside_quotes(&call, rest) {
+        output.push_str(&rest[..found.start()]);
+        let body_start = found.end();
+        let Some(close) = closing_paren(&rest[body_start..]).map(|i| body_start + i) else {
+            return Err(NailError::InvalidArgument(format!("Unclosed 'if(' in expression: {}", expr)));
+        };
+
+        let body = &rest[body_start..close];
+        let mut args = Vec::new();
+        let mut arg_start = 0;
+        for (index, c) in top_level_chars(body) {
+            if c == ',' {
+                args.push(&body[arg_start..index]);
+                arg_start = index + 1;
+            }
+        }
+        args.push(&body[arg_start..]);
+        if args.len() != 3 {
+            return Err(NailError::InvalidArgument(format!(
+                "if() takes 3 arguments, if(condition, then, else), got {}: if({})", args.len(), body
+            )));
+        }
+
+        let args = args.iter()
+            .map(|arg| rewrite_if_calls(arg.trim()))
+            .collect::<NailResult<Vec<_>>>()?;
+        output.push_str(&format!("CASE WHEN {} THEN {} ELSE {} END", args[0], args[1], args[2]));
+        rest = &rest[close + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// First match of `pattern` that does not start inside a quoted string.
+fn find_outside_quotes<'a>(pattern: &Regex, text: &'a str) -> Option<regex::Match<'a>> {
+    pattern.find_iter(text).find(|found| {
+        let before = &text[..found.start()];
+        before.matches('\'').count().is_multiple_of(2) && before.matches('"').count().is_multiple_of(2)
+    })
+}
+
+/// Filters rows and appends columns computed from SQL expressions over the input columns.
+/// Each column can use the columns created before it.
+pub async fn create(df: DataFrame, options: CreateOptions) -> NailResult<DataFrame> {
+    let mut result_df = df;
+
+    if let Some(row_expr) = &options.row_filter {
+        let predicate = result_df.parse_sql_expr(row_expr)
+            .map_err(|e| NailError::InvalidArgument(format!("Invalid row filter expression: {}", e)))?;
+        result_df = result_df.filter(predicate)
+            .map_err(|e| NailError::InvalidArgument(format!("Invalid row filter expression: {}", e)))?;
+    }
+
+    for (name, expr_str) in &options.columns {
+        let existing_columns: Vec<String> = result_df.schema().fields().iter()
+            .map(|f| f.name().clone()).collect();
+        if existing_columns.contains(name) {
+            return Err(NailError::InvalidArgument(format!("Column '{}' already exists", name)));
+        }
+
+        let expr = result_df.parse_sql_expr(&rewrite_if_calls(expr_str)?)
+            .map_err(|e| expression_error(name, &e.to_string(), &existing_columns))?;
+        result_df = result_df.with_column(name, expr)
+            .map_err(|e| expression_error(name, &e.to_string(), &existing_columns))?;
+    }
+
+    Ok(result_df)
+}
+
+fn expression_error(name: &str, message: &str, available: &[String]) -> NailError {
+    let missing = Regex::new(r"No field named ([^ ]+?)\.?(?: Valid fields|$)").ok()
+        .and_then(|pattern| pattern.captures(message))
+        .map(|captures| captures[1].trim_matches('"').trim_start_matches("t.").trim_matches('"').to_string());
+
+    match missing.map(|column| column_not_found(&column, available)) {
+        Some(NailError::ColumnNotFound(detail)) => NailError::ColumnNotFound(format!("Invalid column expression: {}", detail)),
+        _ => NailError::InvalidArgument(format!("Invalid column expression for '{}': {}", name, message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_column_specs() {
+        assert_eq!(
+            parse_column_specs("doubled=value*2, id_plus_one = id+1").unwrap(),
+            vec![("doubled".to_string(), "value*2".to_string()), ("id_plus_one".to_string(), "id+1".to_string())]
+        );
+        assert_eq!(
+            parse_column_specs("r=round(value, 2)").unwrap(),
+            vec![("r".to_string(), "round(value, 2)".to_string())]
+        );
+        assert_eq!(
+            parse_column_specs("is_a=category = 'A', label='x=1, y'").unwrap(),
+            vec![("is_a".to_string(), "category = 'A'".to_string()), ("label".to_string(), "'x=1, y'".to_string())]
+        );
+        assert_eq!(
+            parse_column_specs("\"unit price\"=value/2").unwrap(),
+            vec![("unit price".to_string(), "value/2".to_string())]
+        );
+        assert!(parse_column_specs("value*2").is_err());
+        assert!(parse_column_specs("name=").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_if_calls() {
+        assert_eq!(
+            rewrite_if_calls("if(value > 300, 'high', 'low')").unwrap(),
+            "CASE WHEN value > 300 THEN 'high' ELSE 'low' END"
+        );
+        assert_eq!(
+            rewrite_if_calls("IF(a, if(b, 1, 2), coalesce(c, 3)) + 1").unwrap(),
+            "CASE WHEN a THEN CASE WHEN b THEN 1 ELSE 2 END ELSE coalesce(c, 3) END + 1"
+        );
+        assert_eq!(rewrite_if_calls("'if(x)' || diff(a)").unwrap(), "'if(x)' || diff(a)");
+        assert!(rewrite_if_calls("if(a, b)").unwrap_err().to_string().contains("takes 3 arguments"));
+    }
+}
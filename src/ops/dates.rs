@@ -0,0 +1,372 @@
+use datafusion::arrow::array::{Array, StringArray};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::Column;
+use datafusion::prelude::*;
+use std::str::FromStr;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+use crate::utils::datetime::{convert_date_format, detect_date_formats, has_time_fields, parse_date_expr};
+
+/// Number of non-null values inspected when detecting a column's date format.
+const DETECTION_SAMPLE: usize = 1000;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum DateTarget {
+	/// Date when the format has no time of day, timestamp otherwise.
+	#[default]
+	Auto,
+	Date,
+	Timestamp,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateUnit {
+	Second,
+	Minute,
+	Hour,
+	Day,
+	/// Monday of the ISO week.
+	Week,
+	Month,
+	Quarter,
+	Year,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DatePart {
+	Year,
+	Quarter,
+	Month,
+	Day,
+	Hour,
+	Minute,
+	Second,
+	/// ISO 8601 week number.
+	Week,
+	/// ISO weekday, 1 = Monday to 7 = Sunday.
+	Weekday,
+	DayOfYear,
+	IsWeekend,
+	/// Seconds since 1970-01-01 UTC.
+	Epoch,
+}
+
+impl DatePart {
+	fn name(&self) -> &'static str {
+		match self {
+			DatePart::Year => "year",
+			DatePart::Quarter => "quarter",
+			DatePart::Month => "month",
+			DatePart::Day => "day",
+			DatePart::Hour => "hour",
+			DatePart::Minute => "minute",
+			DatePart::Second => "second",
+			DatePart::Week => "week",
+			DatePart::Weekday => "weekday",
+			DatePart::DayOfYear => "day_of_year",
+			DatePart::IsWeekend => "is_weekend",
+			DatePart::Epoch => "epoch",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DatesOptions {
+	/// Column selector for the columns to parse or transform.
+	pub columns: String,
+	/// Format of string columns, user-friendly (`dd/mm/yyyy HH:mm`) or chrono (`%d/%m/%Y`);
+	/// detected from the values when `None`.
+	pub format: Option<String>,
+	pub to: DateTarget,
+	/// Time zone that naive timestamps are in.
+	pub from_tz: Option<String>,
+	/// Time zone to convert timestamps to; naive timestamps are taken as UTC unless `from_tz` is set.
+	pub tz: Option<String>,
+	pub truncate: Option<DateUnit>,
+	/// Parts extracted into new columns `<column>_<part>`, taken before truncation.
+	pub extract: Vec<DatePart>,
+	/// Write the parsed values to `<column><suffix>` instead of replacing the column.
+	pub suffix: Option<String>,
+}
+
+/// Format detected for a string column when no format was given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateFormatReport {
+	pub column: String,
+	/// chrono format the column was parsed with.
+	pub format: String,
+	/// The values also read as day-first; month-first was assumed.
+	pub ambiguous: bool,
+}
+
+/// Temporal type of a column while it is being transformed.
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+	Date,
+	Timestamp(Option<String>),
+}
+
+/// Parses string columns into dates or timestamps, converts time zones, truncates
+/// to a unit and extracts date parts into new columns. Returns one report per string
+/// column whose format was detected.
+pub async fn dates(df: DataFrame, options: DatesOptions) -> NailResult<(DataFrame, Vec<DateFormatReport>)> {
+	for tz in options.from_tz.iter().chain(options.tz.iter()) {
+		Tz::from_str(tz).map_err(|_| NailError::InvalidArgument(format!(
+			"Unknown time zone '{}'. Use an IANA name such as 'Europe/Paris' or an offset such as '+02:00'", tz
+		)))?;
+	}
+
+	let targets = select_columns_by_pattern(df.schema().clone().into(), &options.columns)?;
+	let mut columns = Vec::new();
+	let mut reports = Vec::new();
+	for target in &targets {
+		columns.push((target.clone(), transform_column(&df, target, &options, &mut reports).await?));
+	}
+
+	let existing: Vec<String> = df.schema().fields().iter().map(|f| f.name().clone()).collect();
+	let mut select_exprs = Vec::new();
+	for name in &existing {
+		let source = column(name);
+		let Some((_, (value, parts))) = columns.iter().find(|(target, _)| target == name) else {
+			select_exprs.push(source);
+			continue;
+		};
+
+		match &options.suffix {
+			Some(suffix) => {
+				select_exprs.push(source);
+				select_exprs.push(value.clone().alias(new_column(&format!("{}{}", name, suffix), &existing)?));
+			},
+			None => select_exprs.push(value.clone().alias(name)),
+		}
+		for (part, expr) in parts {
+			select_exprs.push(expr.clone().alias(new_column(&format!("{}_{}", name, part.name()), &existing)?));
+		}
+	}
+
+	Ok((df.select(select_exprs)?, reports))
+}
+
+fn column(name: &str) -> Expr {
+	Expr::Column(Column::new(None::<String>, name))
+}
+
+fn new_column(name: &str, existing: &[String]) -> NailResult<String> {
+	if existing.iter().any(|e| e == name) {
+		return Err(NailError::InvalidArgument(format!(
+			"Column '{}' already exists. Use --suffix or rename it first", name
+		)));
+	}
+	Ok(name.to_string())
+}
+
+/// The transformed value of one column and the expressions for its extracted parts.
+async fn transform_column(
+	df: &DataFrame,
+	name: &str,
+	options: &DatesOptions,
+	reports: &mut Vec<DateFormatReport>,
+) -> NailResult<(Expr, Vec<(DatePart, Expr)>)> {
+	let data_type = df.schema().field_with_unqualified_name(name)?.data_type().clone();
+	let (mut expr, mut kind) = match &data_type {
+		DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => parse_strings(df, name, options, reports).await?,
+		DataType::Date32 | DataType::Date64 => (column(name), Kind::Date),
+		DataType::Timestamp(_, tz) => (column(name), Kind::Timestamp(tz.as_ref().map(|tz| tz.to_string()))),
+		other => return Err(NailError::InvalidArgument(format!(
+			"Column '{}' is {}; dates works on string, date and timestamp columns", name, other
+		))),
+	};
+
+	match (&options.to, &kind) {
+		(DateTarget::Date, Kind::Timestamp(_)) => {
+			expr = cast(expr, DataType::Date32);
+			kind = Kind::Date;
+		},
+		(DateTarget::Timestamp, Kind::Date) => {
+			expr = cast(expr, timestamp_type(None));
+			kind = Kind::Timestamp(None);
+		},
+		_ => {},
+	}
+
+	if options.from_tz.is_some() || options.tz.is_some() {
+		let Kind::Timestamp(current) = &kind else {
+			return Err(NailError::InvalidArgument(format!(
+				"Column '{}' holds dates; time zones apply to timestamps (add --to timestamp)", name
+			)));
+		};
+		let mut zone = current.clone();
+		if zone.is_none() {
+			// Naive values are wall-clock times in --from-tz, or UTC
+			zone = Some(options.from_tz.clone().unwrap_or_else(|| "UTC".to_string()));
+			expr = cast(expr, timestamp_type(zone.clone()));
+		}
+		if let Some(tz) = &options.tz {
+			zone = Some(tz.clone());
+			expr = cast(expr, timestamp_type(zone.clone()));
+		}
+		kind = Kind::Timestamp(zone);
+	}
+
+	let parts = options.extract.iter()
+		.map(|part| (*part, extract_part(expr.clone(), *part, &kind)))
+		.collect();
+
+	if let Some(unit) = options.truncate {
+		let truncated = date_trunc(lit(format!("{:?}", unit).to_lowercase()), expr);
+		expr = match &kind {
+			Kind::Date => cast(truncated, DataType::Date32),
+			Kind::Timestamp(tz) => cast(truncated, timestamp_type(tz.clone())),
+		};
+	}
+
+	Ok((expr, parts))
+}
+
+fn timestamp_type(tz: Option<String>) -> DataType {
+	DataType::Timestamp(TimeUnit::Microsecond, tz.map(Into::into))
+}
+
+/// Parsing expression for a string column, with the format given or detected from its values.
+async fn parse_strings(df: &DataFrame, name: &str, options: &DatesOptions, reports: &mut Vec<DateFormatReport>) -> NailResult<(Expr, Kind)> {
+	let format = match &options.format {
+		Some(format) => convert_date_format(format)?,
+		None => {
+			let report = detect_column_format(df, name).await?;
+			let format = report.format.clone();
+			reports.push(report);
+			format
+		},
+	};
+	let as_date = match options.to {
+		DateTarget::Auto => !has_time_fields(&format),
+		DateTarget::Date => true,
+		DateTarget::Timestamp => false,
+	};
+	if as_date {
+		Ok((parse_date_expr(column(name), &format)?, Kind::Date))
+	} else {
+		Ok((to_timestamp_micros(vec![column(name), lit(format)]), Kind::Timestamp(None)))
+	}
+}
+
+async fn detect_column_format(df: &DataFrame, name: &str) -> NailResult<DateFormatReport> {
+	let sample = df.clone()
+		.select(vec![cast(column(name), DataType::Utf8).alias("value")])?
+		.filter(col("value").is_not_null().and(trim(vec![col("value")]).not_eq(lit(""))))?
+		.limit(0, Some(DETECTION_SAMPLE))?
+		.collect().await?;
+	let values: Vec<String> = sample.iter()
+		.filter_map(|batch| batch.column(0).as_any().downcast_ref::<StringArray>().cloned())
+		.flat_map(|array| (0..array.len()).map(move |i| array.value(i).trim().to_string()))
+		.collect();
+	let values: Vec<&str> = values.iter().map(String::as_str).collect();
+
+	let formats = detect_date_formats(&values);
+	match formats.as_slice() {
+		[] => {
+			let example = values.iter()
+				.find(|value| detect_date_formats(&[value]).is_empty())
+				.or(values.first());
+			Err(NailError::InvalidArgument(match example {
+				Some(value) => format!(
+					"Cannot detect the date format of column '{}' (e.g. '{}'). Pass it with --date-format, e.g. --date-format 'dd/mm/yyyy HH:mm'",
+					name, value
+				),
+				None => format!("Column '{}' has no values to detect a date format from; pass --date-format", name),
+			}))
+		},
+		[format, others @ ..] => {
+			let day_first = format.replace("%m/%d", "%d/%m").replace("%m-%d", "%d-%m");
+			Ok(DateFormatReport {
+				column: name.to_string(),
+				format: format.to_string(),
+				ambiguous: day_first != *format && others.contains(&day_first.as_str()),
+			})
+		},
+	}
+}
+
+fn extract_part(expr: Expr, part: DatePart, kind: &Kind) -> Expr {
+	let date_part_of = |name: &str, expr: Expr| date_part(lit(name), expr);
+	match part {
+		DatePart::Weekday => {
+			// date_part's dow counts from Sunday = 0
+			let dow = cast(date_part_of("dow", expr), DataType::Int32);
+			cast((dow + lit(6)) % lit(7) + lit(1), DataType::Int32)
+		},
+		DatePart::IsWeekend => {
+			let dow = cast(date_part_of("dow", expr), DataType::Int32);
+			dow.clone().eq(lit(0)).or(dow.eq(lit(6)))
+		},
+		DatePart::DayOfYear => cast(date_part_of("doy", expr), DataType::Int32),
+		DatePart::Epoch => {
+			let instant = if *kind == Kind::Date { cast(expr, timestamp_type(None)) } else { expr };
+			cast(date_part_of("epoch", instant), DataType::Int64)
+		},
+		other => cast(date_part_of(other.name(), expr), DataType::Int32),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::util::pretty::pretty_format_batches;
+
+	async fn run_with_reports(options: DatesOptions) -> (String, Vec<DateFormatReport>) {
+		let ctx = SessionContext::new();
+		let df = ctx.sql("SELECT * FROM (VALUES ('05/01/2024 23:30'), ('10/02/2024 08:00'), (NULL)) AS t(d)").await.unwrap();
+		let (df, reports) = dates(df, options).await.unwrap();
+		let batches = df.collect().await.unwrap();
+		(pretty_format_batches(&batches).unwrap().to_string(), reports)
+	}
+
+	async fn run(options: DatesOptions) -> String {
+		run_with_reports(options).await.0
+	}
+
+	#[tokio::test]
+	async fn test_parse_and_extract() {
+		let table = run(DatesOptions {
+			columns: "d".to_string(),
+			format: Some("dd/mm/yyyy HH:mm".to_string()),
+			truncate: Some(DateUnit::Day),
+			extract: vec![DatePart::Weekday, DatePart::IsWeekend, DatePart::Hour],
+			..Default::default()
+		}).await;
+		assert!(table.contains("| 2024-01-05T00:00:00 | 5         | false        | 23     |"), "{}", table);
+		assert!(table.contains("| 2024-02-10T00:00:00 | 6         | true         | 8      |"), "{}", table);
+	}
+
+	#[tokio::test]
+	async fn test_time_zones() {
+		let table = run(DatesOptions {
+			columns: "d".to_string(),
+			format: Some("%d/%m/%Y %H:%M".to_string()),
+			from_tz: Some("Europe/Paris".to_string()),
+			tz: Some("UTC".to_string()),
+			suffix: Some("_utc".to_string()),
+			..Default::default()
+		}).await;
+		assert!(table.contains("| 05/01/2024 23:30 | 2024-01-05T22:30:00Z |"), "{}", table);
+	}
+
+	#[tokio::test]
+	async fn test_detected_format_is_reported() {
+		let (table, reports) = run_with_reports(DatesOptions { columns: "d".to_string(), ..Default::default() }).await;
+		assert!(table.contains("2024-05-01T23:30:00"), "{}", table);
+		assert_eq!(reports, vec![DateFormatReport {
+			column: "d".to_string(),
+			format: "%m/%d/%Y %H:%M".to_string(),
+			ambiguous: true,
+		}]);
+
+		let (_, reports) = run_with_reports(DatesOptions {
+			columns: "d".to_string(),
+			format: Some("dd/mm/yyyy HH:mm".to_string()),
+			..Default::default()
+		}).await;
+		assert!(reports.is_empty());
+	}
+}
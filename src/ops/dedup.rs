@@ -0,0 +1,270 @@
+use datafusion::prelude::*;
+use std::collections::HashSet;
+use datafusion::functions_window::expr_fn::row_number;
+use datafusion::logical_expr::ExprFunctionExt;
+use crate::error::{NailError, NailResult};
+
+/// Position of each row in the input while duplicates are ranked.
+const ROW_ORDER_COLUMN: &str = "__row_num_for_dedup__";
+const GROUP_RANK_COLUMN: &str = "__dedup_rank__";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupMode {
+	#[default]
+	Rows,
+	Columns,
+}
+
+#[derive(Debug, Clone)]
+pub struct DedupOptions {
+	pub mode: DedupMode,
+	/// Columns compared for row-wise deduplication; all columns when `None`.
+	pub columns: Option<String>,
+	/// Which occurrence to keep: `first` or `last`.
+	pub keep: String,
+}
+
+impl Default for DedupOptions {
+	fn default() -> Self {
+		Self {
+			mode: DedupMode::default(),
+			columns: None,
+			keep: "first".to_string(),
+		}
+	}
+}
+
+/// Removes duplicate rows or duplicate columns.
+pub async fn dedup(df: DataFrame, options: DedupOptions) -> NailResult<DataFrame> {
+	match options.mode {
+		DedupMode::Rows => deduplicate_rows(df, options.columns.as_deref(), &options.keep),
+		DedupMode::Columns => deduplicate_columns(&df, &options.keep).await,
+	}
+}
+
+fn deduplicate_rows(df: DataFrame, columns: Option<&str>, keep: &str) -> NailResult<DataFrame> {
+	let schema = df.schema();
+	let dedup_cols = if let Some(col_spec) = columns {
+		// Convert DFSchemaRef to DFSchemaRef for pattern matching
+		crate::utils::column::select_columns_by_pattern(schema.clone().into(), col_spec)?
+	} else {
+		schema.fields().iter().map(|f| f.name().clone()).collect()
+	};
+	
+	if dedup_cols.is_empty() {
+		// Nothing to dedup on, return original
+		return Ok(df);
+	}
+	
+	let keep_first = match keep {
+		"first" => true,
+		"last" => false,
+		_ => return Err(NailError::InvalidArgument("keep must be 'first' or 'last'".to_string())),
+	};
+	
+	// Number the rows in input order, then keep the first or last row of each group
+	let original_columns: Vec<Expr> = schema.fields().iter()
+		.map(|f| ident(f.name()))
+		.collect();
+	let rank = row_number()
+		.partition_by(dedup_cols.iter().map(ident).collect())
+		.order_by(vec![ident(ROW_ORDER_COLUMN).sort(keep_first, false)])
+		.build()?;
+	
+	Ok(df.with_column(ROW_ORDER_COLUMN, row_number())?
+		.with_column(GROUP_RANK_COLUMN, rank)?
+		.filter(ident(GROUP_RANK_COLUMN).eq(lit(1u64)))?
+		.select(original_columns)?)
+}
+
+async fn deduplicate_columns(df: &DataFrame, keep: &str) -> NailResult<DataFrame> {
+	let schema = df.schema();
+	let field_names: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
+	
+	// First, check for duplicate column names
+	let mut seen_names = HashSet::new();
+	let mut name_duplicates = Vec::new();
+	
+	for field_name in &field_names {
+		if !seen_names.contains(field_name) {
+			seen_names.insert(field_name.clone());
+		} else {
+			name_duplicates.push(field_name.clone());
+		}
+	}
+	
+	// Error out if there are duplicate column names to prevent silent data loss
+	if !name_duplicates.is_empty() {
+		return Err(NailError::InvalidArgument(format!(
+			"Duplicate column names found: {:?}. This could result in data loss. \
+			Please use the 'rename' command to resolve column name conflicts before deduplicating.",
+			name_duplicates
+		)));
+	}
+	
+	// Now check for columns with identical content
+	let batches = df.clone().collect().await?;
+	if batches.is_empty() {
+		return Ok(df.clone());
+	}
+	
+	let mut unique_columns = Vec::new();
+	let mut processed_columns = HashSet::new();
+	
+	for (i, field_name) in field_names.iter().enumerate() {
+		if processed_columns.contains(field_name) {
+			continue;
+		}
+		
+		let mut duplicate_group = vec![field_name.clone()];
+		
+		// Find columns with identical content
+		for (j, other_field_name) in field_names.iter().enumerate() {
+			if i != j && !processed_columns.contains(other_field_name) {
+				if columns_have_identical_content(&batches, field_name, other_field_name)? {
+					duplicate_group.push(other_field_name.clone());
+				}
+			}
+		}
+		
+		// Choose which column to keep from the duplicate group
+		let column_to_keep = if keep == "first" {
+			duplicate_group.first().unwrap().clone()
+		} else if keep == "last" {
+			duplicate_group.last().unwrap().clone()
+		} else {
+			return Err(NailError::InvalidArgument(
+				"Keep must be either 'first' or 'last'".to_string()
+			));
+		};
+		
+		unique_columns.push(col(&column_to_keep));
+		
+		// Mark all columns in this group as processed
+		for col_name in duplicate_group {
+			processed_columns.insert(col_name);
+		}
+	}
+	
+	let result = df.clone().select(unique_columns)?;
+	Ok(result)
+}
+
+fn columns_have_identical_content(
+	batches: &[datafusion::arrow::record_batch::RecordBatch],
+	col1: &str,
+	col2: &str,
+) -> NailResult<bool> {
+	use datafusion::arrow::array::Array;
+	
+	for batch in batches {
+		let array1 = batch.column_by_name(col1)
+			.ok_or_else(|| NailError::InvalidArgument(format!("Column '{}' not found", col1)))?;
+		let array2 = batch.column_by_name(col2)
+			.ok_or_else(|| NailError::InvalidArgument(format!("Column '{}' not found", col2)))?;
+		
+		// Check if arrays have the same length
+		if array1.len() != array2.len() {
+			return Ok(false);
+		}
+		
+		// Check if arrays have the same data type
+		if array1.data_type() != array2.data_type() {
+			return Ok(false);
+		}
+		
+		// Compare all values
+		for i in 0..array1.len() {
+			let val1_is_null = array1.is_null(i);
+			let val2_is_null = array2.is_null(i);
+			
+			if val1_is_null != val2_is_null {
+				return Ok(false);
+			}
+			
+			if !val1_is_null {
+				// Both are non-null, compare values
+				match array1.data_type() {
+					datafusion::arrow::datatypes::DataType::Int64 => {
+						let arr1 = array1.as_any().downcast_ref::<datafusion::arrow::array::Int64Array>().unwrap();
+						let arr2 = array2.as_any().downcast_ref::<datafusion::arrow::array::Int64Array>().unwrap();
+						if arr1.value(i) != arr2.value(i) {
+							return Ok(false);
+						}
+					},
+					datafusion::arrow::datatypes::DataType::Float64 => {
+						let arr1 = array1.as_any().downcast_ref::<datafusion::arrow::array::Float64Array>().unwrap();
+						let arr2 = array2.as_any().downcast_ref::<datafusion::arrow::array::Float64Array>().unwrap();
+						if (arr1.value(i) - arr2.value(i)).abs() > f64::EPSILON {
+							return Ok(false);
+						}
+					},
+					datafusion::arrow::datatypes::DataType::Utf8 => {
+						let arr1 = array1.as_any().downcast_ref::<datafusion::arrow::array::StringArray>().unwrap();
+						let arr2 = array2.as_any().downcast_ref::<datafusion::arrow::array::StringArray>().unwrap();
+						if arr1.value(i) != arr2.value(i) {
+							return Ok(false);
+						}
+					},
+					_ => {
+						// For other types, use string comparison
+						let val1 = format!("{:?}", array1.slice(i, 1));
+						let val2 = format!("{:?}", array2.slice(i, 1));
+						if val1 != val2 {
+							return Ok(false);
+						}
+					}
+				}
+			}
+		}
+	}
+	
+	Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{Array, Int64Array};
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'a', 1), (2, 'b', 2), (3, 'a', 3), (4, 'b', 4), (5, 'c', 5)) AS t(id, kind, copy)"
+		).await.unwrap()
+	}
+
+	async fn ids(df: DataFrame) -> Vec<i64> {
+		let mut ids: Vec<i64> = df.collect().await.unwrap().iter()
+			.flat_map(|b| b.column(0).as_any().downcast_ref::<Int64Array>().unwrap().values().to_vec())
+			.collect();
+		ids.sort();
+		ids
+	}
+
+	#[tokio::test]
+	async fn test_dedup_rows_keep_first_and_last() {
+		let options = DedupOptions { columns: Some("kind".to_string()), ..Default::default() };
+		assert_eq!(ids(dedup(sample_df().await, options).await.unwrap()).await, vec![1, 2, 5]);
+
+		let options = DedupOptions {
+			columns: Some("kind".to_string()),
+			keep: "last".to_string(),
+			..Default::default()
+		};
+		assert_eq!(ids(dedup(sample_df().await, options).await.unwrap()).await, vec![3, 4, 5]);
+	}
+
+	#[tokio::test]
+	async fn test_dedup_columns_drops_identical_content() {
+		let options = DedupOptions { mode: DedupMode::Columns, ..Default::default() };
+		let result = dedup(sample_df().await, options).await.unwrap();
+		let names: Vec<String> = result.schema().fields().iter().map(|f| f.name().clone()).collect();
+		assert_eq!(names, vec!["id", "kind"]);
+	}
+
+	#[tokio::test]
+	async fn test_dedup_rejects_unknown_keep() {
+		let options = DedupOptions { keep: "middle".to_string(), ..Default::default() };
+		assert!(dedup(sample_df().await, options).await.is_err());
+	}
+}
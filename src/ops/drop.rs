@@ -0,0 +1,95 @@
+use datafusion::prelude::*;
+use regex::Regex;
+use std::sync::LazyLock;
+use crate::error::NailResult;
+use crate::utils::predicate::parse_predicate;
+use crate::ops::select::{filter_row_numbers, parse_row_specification, select_columns_by_pattern};
+
+/// Row numbers and ranges such as `1,3,5-10`.
+static ROW_NUMBERS: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"^\s*\d+(\s*-\s*\d+)?(\s*,\s*\d+(\s*-\s*\d+)?)*\s*$").unwrap()
+});
+
+#[derive(Debug, Clone, Default)]
+pub struct DropOptions {
+	/// Column names or patterns to remove, comma-separated.
+	pub columns: Option<String>,
+	/// Row numbers and ranges (`1,3,5-10`) or a row predicate (`age<18`) to remove.
+	pub rows: Option<String>,
+}
+
+/// Removes the selected columns and rows.
+pub async fn drop(df: DataFrame, options: DropOptions) -> NailResult<DataFrame> {
+	let mut result_df = df;
+	
+	if let Some(col_spec) = &options.columns {
+		let schema = result_df.schema();
+		let columns_to_drop = select_columns_by_pattern(schema.clone().into(), col_spec)?;
+		
+		let remaining_columns: Vec<Expr> = result_df.schema().fields().iter()
+			.filter(|f| !columns_to_drop.contains(f.name()))
+			.map(|f| Expr::Column(datafusion::common::Column::new(None::<String>, f.name())))
+			.collect();
+		
+		result_df = result_df.select(remaining_columns)?;
+	}
+	
+	if let Some(row_spec) = &options.rows {
+		// A predicate wins, so bare boolean columns (`active`, `not active`) are conditions;
+		// only strict row number syntax falls back to row indices
+		let schema = result_df.schema().clone().into();
+		match parse_predicate(row_spec, &schema) {
+			Ok(condition) => {
+				// Keep every row the condition is not true for, including rows where it is null
+				result_df = result_df.filter(condition.is_not_true())?;
+			},
+			Err(_) if ROW_NUMBERS.is_match(row_spec) => {
+				let row_indices = parse_row_specification(row_spec)?;
+				result_df = filter_row_numbers(result_df, &row_indices, false)?;
+			},
+			Err(e) => return Err(e),
+		}
+	}
+	
+	Ok(result_df)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use datafusion::arrow::util::pretty::pretty_format_batches;
+
+	async fn users() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'Ann', true), (2, 'Bob', false), (3, 'Cy', true), (4, 'Di', false)) AS t(id, name, active)"
+		).await.unwrap()
+	}
+
+	async fn remaining_ids(rows: &str) -> NailResult<Vec<String>> {
+		let df = drop(users().await, DropOptions { rows: Some(rows.to_string()), ..DropOptions::default() }).await?;
+		let rendered = pretty_format_batches(&df.select_columns(&["id"])?.collect().await?)?.to_string();
+		Ok(rendered.lines()
+			.filter_map(|line| line.trim_matches(|c| c == '|' || c == ' ').parse::<i64>().ok())
+			.map(|id| id.to_string())
+			.collect())
+	}
+
+	#[tokio::test]
+	async fn test_drop_rows_by_condition_or_row_numbers() {
+		assert_eq!(remaining_ids("name=Ann").await.unwrap(), ["2", "3", "4"]);
+		assert_eq!(remaining_ids("id>2").await.unwrap(), ["1", "2"]);
+		assert_eq!(remaining_ids("name in ('Bob', 'Cy')").await.unwrap(), ["1", "4"]);
+
+		// Bare boolean predicates are conditions, not row specifications
+		assert_eq!(remaining_ids("active").await.unwrap(), ["2", "4"]);
+		assert_eq!(remaining_ids("!active").await.unwrap(), ["1", "3"]);
+		assert_eq!(remaining_ids("not active").await.unwrap(), ["1", "3"]);
+
+		assert_eq!(remaining_ids("1,3").await.unwrap(), ["2", "4"]);
+		assert_eq!(remaining_ids("2-3").await.unwrap(), ["1", "4"]);
+		assert_eq!(remaining_ids("4").await.unwrap(), ["1", "2", "3"]);
+
+		assert!(remaining_ids("just_text").await.is_err());
+	}
+}
@@ -0,0 +1,194 @@
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, Int64Array, UInt64Array};
+use datafusion::arrow::compute::take;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::prelude::*;
+use std::sync::Arc;
+use crate::error::{NailError, NailResult};
+use crate::utils::collect_in_session;
+use crate::utils::io::dataframe_from_batches;
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub struct ExplodeOptions {
+	/// List columns to explode; several columns are exploded in parallel, element by element.
+	pub columns: String,
+	/// Name of a new column holding each element's 0-based position in its list.
+	pub position: Option<String>,
+	/// Drop rows whose lists are all null or empty instead of keeping them with nulls.
+	pub drop_empty: bool,
+}
+
+/// Produces one row per list element. With several list columns, the n-th elements
+/// share a row and shorter lists are padded with nulls.
+pub async fn explode(df: DataFrame, options: ExplodeOptions) -> NailResult<DataFrame> {
+	let input_schema = df.schema().as_arrow().clone();
+	let targets = select_columns_by_pattern(df.schema().clone().into(), &options.columns)?;
+	let mut list_indices = Vec::new();
+	for name in &targets {
+		let index = input_schema.index_of(name)?;
+		if list_item(input_schema.field(index).data_type()).is_none() {
+			return Err(NailError::InvalidArgument(format!(
+				"Column '{}' is {}, not a list column", name, input_schema.field(index).data_type()
+			)));
+		}
+		list_indices.push(index);
+	}
+	if let Some(position) = &options.position {
+		if input_schema.field_with_name(position).is_ok() {
+			return Err(NailError::InvalidArgument(format!("Column '{}' already exists", position)));
+		}
+	}
+
+	let schema = Arc::new(output_schema(&input_schema, &list_indices, options.position.as_deref()));
+	let (ctx, batches) = collect_in_session(df).await?;
+	let mut output = Vec::with_capacity(batches.len());
+	for batch in &batches {
+		output.push(explode_batch(batch, &list_indices, &options, schema.clone())?);
+	}
+
+	dataframe_from_batches(&ctx, schema, output)
+}
+
+fn list_item(data_type: &DataType) -> Option<&Field> {
+	match data_type {
+		DataType::List(item) | DataType::LargeList(item) | DataType::FixedSizeList(item, _) => Some(item),
+		_ => None,
+	}
+}
+
+/// The schema with list columns replaced by their element type and the position column
+/// placed after the first exploded column.
+fn output_schema(input: &Schema, list_indices: &[usize], position: Option<&str>) -> Schema {
+	let mut fields = Vec::new();
+	for (index, field) in input.fields().iter().enumerate() {
+		match list_item(field.data_type()).filter(|_| list_indices.contains(&index)) {
+			Some(item) => fields.push(Field::new(field.name(), item.data_type().clone(), true)),
+			None => fields.push(field.as_ref().clone()),
+		}
+		if Some(&index) == list_indices.first() {
+			if let Some(position) = position {
+				fields.push(Field::new(position, DataType::Int64, true));
+			}
+		}
+	}
+	Schema::new(fields)
+}
+
+/// `(start, length)` of every row's list in `values`; null lists have length 0.
+fn list_ranges(array: &ArrayRef) -> (ArrayRef, Vec<(usize, usize)>) {
+	let ranges = |offsets: Vec<usize>, array: &dyn Array| -> Vec<(usize, usize)> {
+		offsets.windows(2).enumerate()
+			.map(|(row, w)| if array.is_null(row) { (w[0], 0) } else { (w[0], w[1] - w[0]) })
+			.collect()
+	};
+	match array.data_type() {
+		DataType::List(_) => {
+			let list = array.as_list::<i32>();
+			let offsets = list.value_offsets().iter().map(|&o| o as usize).collect();
+			(list.values().clone(), ranges(offsets, list))
+		},
+		DataType::LargeList(_) => {
+			let list = array.as_list::<i64>();
+			let offsets = list.value_offsets().iter().map(|&o| o as usize).collect();
+			(list.values().clone(), ranges(offsets, list))
+		},
+		_ => {
+			let list = array.as_fixed_size_list();
+			let size = list.value_length() as usize;
+			let offsets = (0..=list.len()).map(|row| row * size).collect();
+			(list.values().clone(), ranges(offsets, list))
+		},
+	}
+}
+
+fn explode_batch(batch: &RecordBatch, list_indices: &[usize], options: &ExplodeOptions, schema: Arc<Schema>) -> NailResult<RecordBatch> {
+	let lists: Vec<(ArrayRef, Vec<(usize, usize)>)> = list_indices.iter()
+		.map(|&index| list_ranges(batch.column(index)))
+		.collect();
+
+	// Source row for every output row, and each element's position within its row
+	let mut rows: Vec<u64> = Vec::new();
+	let mut positions: Vec<Option<i64>> = Vec::new();
+	for row in 0..batch.num_rows() {
+		let count = lists.iter().map(|(_, ranges)| ranges[row].1).max().unwrap_or(0);
+		if count == 0 {
+			if !options.drop_empty {
+				rows.push(row as u64);
+				positions.push(None);
+			}
+			continue;
+		}
+		for position in 0..count {
+			rows.push(row as u64);
+			positions.push(Some(position as i64));
+		}
+	}
+
+	let row_indices = UInt64Array::from(rows.clone());
+	let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+	for (index, column) in batch.columns().iter().enumerate() {
+		match list_indices.iter().position(|&i| i == index) {
+			Some(list) => {
+				let (values, ranges) = &lists[list];
+				let element_indices: UInt64Array = rows.iter().zip(&positions)
+					.map(|(&row, position)| {
+						let (start, length) = ranges[row as usize];
+						position.filter(|&p| (p as usize) < length).map(|p| (start + p as usize) as u64)
+					})
+					.collect();
+				columns.push(take(values.as_ref(), &element_indices, None)?);
+			},
+			None => columns.push(take(column.as_ref(), &row_indices, None)?),
+		}
+		if options.position.is_some() && Some(&index) == list_indices.first() {
+			columns.push(Arc::new(Int64Array::from(positions.clone())));
+		}
+	}
+
+	Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::util::pretty::pretty_format_batches;
+
+	async fn run(options: ExplodeOptions) -> String {
+		let ctx = SessionContext::new();
+		let df = ctx.sql(
+			"SELECT 1 AS id, make_array('a', 'b', 'c') AS tags, make_array(10, 20) AS scores \
+			 UNION ALL SELECT 2, make_array('x'), make_array(30) \
+			 UNION ALL SELECT 3, NULL AS t, NULL AS s"
+		).await.unwrap().sort(vec![col("id").sort(true, false)]).unwrap();
+		let batches = explode(df, options).await.unwrap().collect().await.unwrap();
+		pretty_format_batches(&batches).unwrap().to_string()
+	}
+
+	#[tokio::test]
+	async fn test_explode_parallel_lists() {
+		let table = run(ExplodeOptions {
+			columns: "tags,scores".to_string(),
+			position: Some("pos".to_string()),
+			..Default::default()
+		}).await;
+		let rows: Vec<&str> = table.lines().filter(|line| line.starts_with("| ")).skip(1).collect();
+		assert_eq!(rows, vec![
+			"| 1  | a    | 0   | 10     |",
+			"| 1  | b    | 1   | 20     |",
+			"| 1  | c    | 2   |        |",
+			"| 2  | x    | 0   | 30     |",
+			"| 3  |      |     |        |",
+		], "{}", table);
+	}
+
+	#[tokio::test]
+	async fn test_explode_drop_empty() {
+		let table = run(ExplodeOptions {
+			columns: "tags".to_string(),
+			drop_empty: true,
+			..Default::default()
+		}).await;
+		assert_eq!(table.lines().filter(|line| line.starts_with("| ")).count(), 5, "{}", table);
+	}
+}
@@ -0,0 +1,549 @@
+use datafusion::prelude::*;
+use datafusion::arrow::array::{Float64Array, Int64Array, Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::functions_aggregate::expr_fn::{approx_percentile_cont, avg, count};
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Clone, Debug, Default)]
+pub enum FillMethod {
+	#[default]
+	Value,
+	Mean,
+	Median,
+	Mode,
+	Forward,
+	Backward,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FillOptions {
+	pub method: FillMethod,
+	/// Fill value, required for `FillMethod::Value`.
+	pub value: Option<String>,
+	/// Column pattern to fill; all columns when `None`.
+	pub columns: Option<String>,
+}
+
+/// Fills missing values in the selected columns.
+pub async fn fill(df: DataFrame, options: FillOptions) -> NailResult<DataFrame> {
+	let columns = if let Some(col_spec) = &options.columns {
+		let schema = df.schema();
+		select_columns_by_pattern(schema.clone().into(), col_spec)?
+	} else {
+		df.schema().fields().iter().map(|f| f.name().clone()).collect()
+	};
+	
+	fill_missing_values(df, &columns, &options.method, options.value.as_deref()).await
+}
+
+async fn fill_missing_values(
+	df: DataFrame,
+	columns: &[String],
+	method: &FillMethod,
+	value: Option<&str>,
+) -> NailResult<DataFrame> {
+	let schema = df.schema().clone();
+	let mut select_exprs = Vec::new();
+	
+	for field in schema.fields() {
+		let field_name = field.name();
+		
+		if columns.contains(field_name) {
+			let filled_expr = match method {
+				FillMethod::Value => {
+					let fill_val = value.ok_or_else(|| NailError::InvalidArgument(
+						"A fill value is required for the 'value' method (--value)".to_string()
+					))?;
+					match field.data_type() {
+						datafusion::arrow::datatypes::DataType::Int64 => {
+							let val: i64 = fill_val.parse()
+								.map_err(|_| NailError::InvalidArgument(format!("Invalid integer value: {}", fill_val)))?;
+							coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(val)])
+						},
+						datafusion::arrow::datatypes::DataType::Float64 => {
+							let val: f64 = fill_val.parse()
+								.map_err(|_| NailError::InvalidArgument(format!("Invalid float value: {}", fill_val)))?;
+							coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(val)])
+						},
+						datafusion::arrow::datatypes::DataType::Utf8 => {
+							coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(fill_val)])
+						},
+						_ => Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+					}
+				},
+				FillMethod::Mean => {
+					// Use DataFusion's built-in avg function instead of manual calculation
+					match field.data_type() {
+						datafusion::arrow::datatypes::DataType::Float64 | 
+						datafusion::arrow::datatypes::DataType::Int64 => {
+							coalesce(vec![
+								Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+								lit(0.0) // This will be replaced by actual mean calculation below
+							])
+						},
+						_ => {
+							return Err(NailError::Statistics(format!("Mean calculation not supported for column '{}' of type {:?}", field_name, field.data_type())));
+						},
+					}
+				},
+				FillMethod::Median => {
+					match field.data_type() {
+						datafusion::arrow::datatypes::DataType::Float64 | 
+						datafusion::arrow::datatypes::DataType::Int64 => {
+							coalesce(vec![
+								Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+								lit(0.0) // This will be replaced by actual median calculation below
+							])
+						},
+						_ => {
+							return Err(NailError::Statistics(format!("Median calculation not supported for column '{}' of type {:?}", field_name, field.data_type())));
+						},
+					}
+				},
+				FillMethod::Mode => {
+					// Mode is the most frequent value
+					coalesce(vec![
+						Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+						lit("") // This will be replaced by actual mode calculation below
+					])
+				},
+				FillMethod::Forward => {
+					// Forward fill - handled after the select_exprs construction
+					Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+				},
+				FillMethod::Backward => {
+					// Backward fill - handled after the select_exprs construction
+					Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+				},
+			};
+			
+			select_exprs.push(filled_expr.alias(field_name));
+		} else {
+			select_exprs.push(Expr::Column(datafusion::common::Column::new(None::<String>, field_name)));
+		}
+	}
+	
+	// For statistical methods, use DataFusion's built-in aggregation functions
+	if matches!(method, FillMethod::Mean | FillMethod::Median | FillMethod::Mode) {
+		let mut stats_values = std::collections::HashMap::<String, f64>::new();
+		let mut mode_values = std::collections::HashMap::<String, String>::new();
+		
+		// Calculate statistical values for each target column using SQL
+		for col_name in columns {
+			let field = schema.fields().iter()
+				.find(|f| f.name() == col_name)
+				.ok_or_else(|| NailError::Statistics(format!("Column '{}' not found", col_name)))?;
+			
+			match method {
+				FillMethod::Mean => {
+					match field.data_type() {
+						datafusion::arrow::datatypes::DataType::Float64 | 
+						datafusion::arrow::datatypes::DataType::Int64 => {
+							if let Some(mean) = column_stat(&df, avg(ident(col_name))).await? {
+								stats_values.insert(col_name.clone(), mean);
+							}
+						},
+						_ => return Err(NailError::Statistics(format!("Mean calculation not supported for column '{}' of type {:?}", col_name, field.data_type()))),
+					}
+				},
+				FillMethod::Median => {
+					match field.data_type() {
+						datafusion::arrow::datatypes::DataType::Float64 | 
+						datafusion::arrow::datatypes::DataType::Int64 => {
+							if let Some(median) = column_stat(&df, approx_percentile_cont(ident(col_name), lit(0.5), None)).await? {
+								stats_values.insert(col_name.clone(), median);
+							}
+						},
+						_ => return Err(NailError::Statistics(format!("Median calculation not supported for column '{}' of type {:?}", col_name, field.data_type()))),
+					}
+				},
+				FillMethod::Mode => {
+					// The mode is the most frequent non-null value
+					let batches = df.clone()
+						.filter(ident(col_name).is_not_null())?
+						.aggregate(vec![ident(col_name)], vec![count(lit(1)).alias("freq")])?
+						.sort(vec![col("freq").sort(false, false)])?
+						.limit(0, Some(1))?
+						.collect()
+						.await?;
+					if let Some(batch) = batches.first() {
+						if batch.num_rows() > 0 {
+							let mode_array = batch.column(0);
+							if !mode_array.is_null(0) {
+								match field.data_type() {
+									datafusion::arrow::datatypes::DataType::Utf8 => {
+										let str_array = mode_array.as_any().downcast_ref::<arrow::array::StringArray>().unwrap();
+										mode_values.insert(col_name.clone(), str_array.value(0).to_string());
+									},
+									datafusion::arrow::datatypes::DataType::Int64 => {
+										let int_array = mode_array.as_any().downcast_ref::<Int64Array>().unwrap();
+										mode_values.insert(col_name.clone(), int_array.value(0).to_string());
+									},
+									datafusion::arrow::datatypes::DataType::Float64 => {
+										let float_array = mode_array.as_any().downcast_ref::<Float64Array>().unwrap();
+										mode_values.insert(col_name.clone(), float_array.value(0).to_string());
+									},
+									_ => {},
+								}
+							}
+						}
+					}
+				},
+				_ => unreachable!(),
+			}
+		}
+		
+		// Now rebuild select expressions with actual calculated values
+		select_exprs.clear();
+		for field in schema.fields() {
+			let field_name = field.name();
+			
+			if columns.contains(field_name) {
+				let filled_expr = match method {
+					FillMethod::Mean => {
+						if let Some(&stat_val) = stats_values.get(field_name) {
+							match field.data_type() {
+								datafusion::arrow::datatypes::DataType::Float64 => {
+									coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(stat_val)])
+								},
+								datafusion::arrow::datatypes::DataType::Int64 => {
+									coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(stat_val)])
+								},
+								_ => Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+							}
+						} else {
+							Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+						}
+					},
+					FillMethod::Median => {
+						if let Some(&stat_val) = stats_values.get(field_name) {
+							match field.data_type() {
+								datafusion::arrow::datatypes::DataType::Float64 => {
+									coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(stat_val)])
+								},
+								datafusion::arrow::datatypes::DataType::Int64 => {
+									coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(stat_val)])
+								},
+								_ => Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+							}
+						} else {
+							Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+						}
+					},
+					FillMethod::Mode => {
+						if let Some(mode_val) = mode_values.get(field_name) {
+							match field.data_type() {
+								datafusion::arrow::datatypes::DataType::Utf8 => {
+									coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(mode_val.as_str())])
+								},
+								datafusion::arrow::datatypes::DataType::Float64 => {
+									if let Ok(num_val) = mode_val.parse::<f64>() {
+										coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(num_val)])
+									} else {
+										Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+									}
+								},
+								datafusion::arrow::datatypes::DataType::Int64 => {
+									if let Ok(num_val) = mode_val.parse::<i64>() {
+										coalesce(vec![Expr::Column(datafusion::common::Column::new(None::<String>, field_name)), lit(num_val)])
+									} else {
+										Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+									}
+								},
+								_ => Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+							}
+						} else {
+							Expr::Column(datafusion::common::Column::new(None::<String>, field_name))
+						}
+					},
+					_ => Expr::Column(datafusion::common::Column::new(None::<String>, field_name)),
+				};
+				select_exprs.push(filled_expr.alias(field_name));
+			} else {
+				select_exprs.push(Expr::Column(datafusion::common::Column::new(None::<String>, field_name)));
+			}
+		}
+	} else if matches!(method, FillMethod::Forward | FillMethod::Backward) {
+		// For forward/backward fill, we need to use window functions
+		return fill_forward_backward(df, columns, method).await;
+	}
+	
+	Ok(df.select(select_exprs)?)
+}
+
+/// Evaluates an aggregate over the whole frame as a float; `None` when it is null.
+async fn column_stat(df: &DataFrame, aggregate: Expr) -> NailResult<Option<f64>> {
+	let batches = df.clone()
+		.aggregate(vec![], vec![aggregate.alias("stat_value")])?
+		.select(vec![cast(col("stat_value"), DataType::Float64)])?
+		.collect()
+		.await?;
+	Ok(batches.iter()
+		.find(|batch| batch.num_rows() > 0)
+		.and_then(|batch| {
+			let values = batch.column(0).as_any().downcast_ref::<Float64Array>()?;
+			(!values.is_null(0)).then(|| values.value(0))
+		}))
+}
+
+async fn fill_forward_backward(df: DataFrame, columns: &[String], method: &FillMethod) -> NailResult<DataFrame> {
+	// Values carry across batch boundaries, so the frame is filled as one batch
+	let schema: datafusion::arrow::datatypes::SchemaRef = std::sync::Arc::new(df.schema().as_arrow().clone());
+	let (ctx, batches) = crate::utils::collect_in_session(df).await?;
+	let batch = datafusion::arrow::compute::concat_batches(&schema, &batches)?;
+	
+	let mut arrays = Vec::new();
+	for (field, array) in schema.fields().iter().zip(batch.columns()) {
+		if columns.contains(field.name()) {
+			match method {
+				FillMethod::Forward => arrays.push(forward_fill_array(array.clone())?),
+				FillMethod::Backward => arrays.push(backward_fill_array(array.clone())?),
+				_ => arrays.push(array.clone()),
+			}
+		} else {
+			arrays.push(array.clone());
+		}
+	}
+	let filled = datafusion::arrow::record_batch::RecordBatch::try_new(schema, arrays)?;
+	
+	Ok(ctx.read_batch(filled)?)
+}
+
+fn forward_fill_array(array: datafusion::arrow::array::ArrayRef) -> NailResult<datafusion::arrow::array::ArrayRef> {
+	use datafusion::arrow::array::{Array, StringArray, Float64Array, Int64Array};
+	use datafusion::arrow::datatypes::DataType;
+	
+	match array.data_type() {
+		DataType::Utf8 => {
+			let string_array = array.as_any().downcast_ref::<StringArray>().unwrap();
+			let mut builder = datafusion::arrow::array::StringBuilder::new();
+			let mut last_value: Option<String> = None;
+			
+			for i in 0..string_array.len() {
+				if string_array.is_null(i) {
+					if let Some(ref value) = last_value {
+						builder.append_value(value);
+					} else {
+						builder.append_null();
+					}
+				} else {
+					let value = string_array.value(i).to_string();
+					last_value = Some(value.clone());
+					builder.append_value(&value);
+				}
+			}
+			
+			Ok(std::sync::Arc::new(builder.finish()))
+		},
+		DataType::Float64 => {
+			let float_array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+			let mut builder = datafusion::arrow::array::Float64Builder::new();
+			let mut last_value: Option<f64> = None;
+			
+			for i in 0..float_array.len() {
+				if float_array.is_null(i) {
+					if let Some(value) = last_value {
+						builder.append_value(value);
+					} else {
+						builder.append_null();
+					}
+				} else {
+					let value = float_array.value(i);
+					last_value = Some(value);
+					builder.append_value(value);
+				}
+			}
+			
+			Ok(std::sync::Arc::new(builder.finish()))
+		},
+		DataType::Int64 => {
+			let int_array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+			let mut builder = datafusion::arrow::array::Int64Builder::new();
+			let mut last_value: Option<i64> = None;
+			
+			for i in 0..int_array.len() {
+				if int_array.is_null(i) {
+					if let Some(value) = last_value {
+						builder.append_value(value);
+					} else {
+						builder.append_null();
+					}
+				} else {
+					let value = int_array.value(i);
+					last_value = Some(value);
+					builder.append_value(value);
+				}
+			}
+			
+			Ok(std::sync::Arc::new(builder.finish()))
+		},
+		_ => {
+			// For other types, just return the original array
+			Ok(array.clone())
+		}
+	}
+}
+
+fn backward_fill_array(array: datafusion::arrow::array::ArrayRef) -> NailResult<datafusion::arrow::array::ArrayRef> {
+	use datafusion::arrow::array::{Array, StringArray, Float64Array, Int64Array};
+	use datafusion::arrow::datatypes::DataType;
+	
+	match array.data_type() {
+		DataType::Utf8 => {
+			let string_array = array.as_any().downcast_ref::<StringArray>().unwrap();
+			let mut builder = datafusion::arrow::array::StringBuilder::new();
+			let mut values = Vec::new();
+			
+			// First pass: collect all values
+			for i in 0..string_array.len() {
+				if string_array.is_null(i) {
+					values.push(None);
+				} else {
+					values.push(Some(string_array.value(i).to_string()));
+				}
+			}
+			
+			// Second pass: backward fill
+			let mut next_value: Option<String> = None;
+			for i in (0..values.len()).rev() {
+				if values[i].is_some() {
+					next_value = values[i].clone();
+				} else if let Some(ref value) = next_value {
+					values[i] = Some(value.clone());
+				}
+			}
+			
+			// Third pass: build result
+			for value in values {
+				if let Some(v) = value {
+					builder.append_value(&v);
+				} else {
+					builder.append_null();
+				}
+			}
+			
+			Ok(std::sync::Arc::new(builder.finish()))
+		},
+		DataType::Float64 => {
+			let float_array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+			let mut builder = datafusion::arrow::array::Float64Builder::new();
+			let mut values = Vec::new();
+			
+			// First pass: collect all values
+			for i in 0..float_array.len() {
+				if float_array.is_null(i) {
+					values.push(None);
+				} else {
+					values.push(Some(float_array.value(i)));
+				}
+			}
+			
+			// Second pass: backward fill
+			let mut next_value: Option<f64> = None;
+			for i in (0..values.len()).rev() {
+				if values[i].is_some() {
+					next_value = values[i];
+				} else if let Some(value) = next_value {
+					values[i] = Some(value);
+				}
+			}
+			
+			// Third pass: build result
+			for value in values {
+				if let Some(v) = value {
+					builder.append_value(v);
+				} else {
+					builder.append_null();
+				}
+			}
+			
+			Ok(std::sync::Arc::new(builder.finish()))
+		},
+		DataType::Int64 => {
+			let int_array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+			let mut builder = datafusion::arrow::array::Int64Builder::new();
+			let mut values = Vec::new();
+			
+			// First pass: collect all values
+			for i in 0..int_array.len() {
+				if int_array.is_null(i) {
+					values.push(None);
+				} else {
+					values.push(Some(int_array.value(i)));
+				}
+			}
+			
+			// Second pass: backward fill
+			let mut next_value: Option<i64> = None;
+			for i in (0..values.len()).rev() {
+				if values[i].is_some() {
+					next_value = values[i];
+				} else if let Some(value) = next_value {
+					values[i] = Some(value);
+				}
+			}
+			
+			// Third pass: build result
+			for value in values {
+				if let Some(v) = value {
+					builder.append_value(v);
+				} else {
+					builder.append_null();
+				}
+			}
+			
+			Ok(std::sync::Arc::new(builder.finish()))
+		},
+		_ => {
+			// For other types, just return the original array
+			Ok(array.clone())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::Float64Array;
+	use datafusion::arrow::datatypes::{DataType, Field, Schema};
+	use datafusion::arrow::record_batch::RecordBatch;
+	use std::sync::Arc;
+
+	fn values_df(values: Vec<Option<f64>>) -> DataFrame {
+		let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Float64, true)]));
+		let batch = RecordBatch::try_new(schema, vec![Arc::new(Float64Array::from(values))]).unwrap();
+		SessionContext::new().read_batch(batch).unwrap()
+	}
+
+	async fn collect_values(df: DataFrame) -> Vec<Option<f64>> {
+		let batches = df.collect().await.unwrap();
+		batches.iter()
+			.flat_map(|b| b.column(0).as_any().downcast_ref::<Float64Array>().unwrap().iter().collect::<Vec<_>>())
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn test_fill_mean() {
+		let df = values_df(vec![Some(1.0), None, Some(3.0)]);
+		let result = fill(df, FillOptions { method: FillMethod::Mean, ..Default::default() }).await.unwrap();
+		assert_eq!(collect_values(result).await, vec![Some(1.0), Some(2.0), Some(3.0)]);
+	}
+
+	#[tokio::test]
+	async fn test_fill_forward_empty_input() {
+		let df = values_df(vec![]);
+		let result = fill(df, FillOptions { method: FillMethod::Forward, ..Default::default() }).await.unwrap();
+		assert!(collect_values(result).await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_fill_value_requires_value() {
+		let df = values_df(vec![Some(1.0), None]);
+		assert!(fill(df.clone(), FillOptions::default()).await.is_err());
+
+		let options = FillOptions { value: Some("0".to_string()), ..Default::default() };
+		let result = fill(df, options).await.unwrap();
+		assert_eq!(collect_values(result).await, vec![Some(1.0), Some(0.0)]);
+	}
+}
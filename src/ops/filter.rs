@@ -0,0 +1,173 @@
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::predicate::{date_window, parse_predicate};
+
+#[derive(Clone, Debug)]
+pub enum RowFilter {
+	NoNan,
+	NumericOnly,
+	CharOnly,
+	NoZeros,
+}
+
+/// A time window on a date or timestamp column; `start` is inclusive, `end` exclusive.
+#[derive(Debug, Clone)]
+pub struct DateWindow {
+	pub column: String,
+	pub start: String,
+	pub end: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FilterOptions {
+	/// Row predicate such as `age>25,status=active` or `(age >= 65 or vip) and not name ~ '^test'`.
+	pub conditions: Option<String>,
+	pub between_dates: Option<DateWindow>,
+	pub rows: Option<RowFilter>,
+}
+
+/// Keeps the rows matching the column conditions, the date window and the row filter.
+pub async fn filter(df: DataFrame, options: FilterOptions) -> NailResult<DataFrame> {
+	let mut result_df = df;
+	
+	if let Some(conditions) = &options.conditions {
+		result_df = apply_column_filters(result_df, conditions)?;
+	}
+	
+	if let Some(window) = &options.between_dates {
+		let schema = result_df.schema().clone().into();
+		result_df = result_df.filter(date_window(&schema, &window.column, &window.start, &window.end)?)?;
+	}
+	
+	if let Some(row_filter) = &options.rows {
+		result_df = apply_row_filter(result_df, row_filter)?;
+	}
+	
+	Ok(result_df)
+}
+
+fn apply_column_filters(df: DataFrame, conditions: &str) -> NailResult<DataFrame> {
+	let schema = df.schema().clone().into();
+	let filter_expr = parse_predicate(conditions, &schema)?;
+	
+	Ok(df.filter(filter_expr)?)
+}
+
+fn apply_row_filter(df: DataFrame, filter: &RowFilter) -> NailResult<DataFrame> {
+	let schema = df.schema();
+	let filter_expr = match filter {
+		RowFilter::NoNan => {
+			let conditions: Vec<Expr> = schema.fields().iter()
+				.map(|f| Expr::Column(datafusion::common::Column::new(None::<String>, f.name())).is_not_null())
+				.collect();
+			conditions.into_iter().reduce(|acc, expr| acc.and(expr)).unwrap()
+		},
+		RowFilter::NumericOnly => {
+			// Filter rows where all numeric columns have valid numeric values (not null)
+			let numeric_columns: Vec<String> = schema.fields().iter()
+				.filter(|f| matches!(f.data_type(), 
+					datafusion::arrow::datatypes::DataType::Int64 | 
+					datafusion::arrow::datatypes::DataType::Float64 | 
+					datafusion::arrow::datatypes::DataType::Int32 | 
+					datafusion::arrow::datatypes::DataType::Float32
+				))
+				.map(|f| f.name().clone())
+				.collect();
+			
+			if numeric_columns.is_empty() {
+				return Err(NailError::InvalidArgument("No numeric columns found".to_string()));
+			}
+			
+			// Create conditions that all numeric columns must not be null
+			let conditions: Vec<Expr> = numeric_columns.iter()
+				.map(|name| Expr::Column(datafusion::common::Column::new(None::<String>, name)).is_not_null())
+				.collect();
+			
+			conditions.into_iter().reduce(|acc, expr| acc.and(expr)).unwrap()
+		},
+		RowFilter::CharOnly => {
+			// Filter rows where all string columns have non-null values
+			let char_columns: Vec<String> = schema.fields().iter()
+				.filter(|f| matches!(f.data_type(), datafusion::arrow::datatypes::DataType::Utf8))
+				.map(|f| f.name().clone())
+				.collect();
+			
+			if char_columns.is_empty() {
+				return Err(NailError::InvalidArgument("No string columns found".to_string()));
+			}
+			
+			// Create conditions that all string columns must not be null and not empty
+			let conditions: Vec<Expr> = char_columns.iter()
+				.map(|name| {
+					let col_expr = Expr::Column(datafusion::common::Column::new(None::<String>, name));
+					col_expr.clone().is_not_null().and(col_expr.not_eq(lit("")))
+				})
+				.collect();
+			
+			conditions.into_iter().reduce(|acc, expr| acc.and(expr)).unwrap()
+		},
+		RowFilter::NoZeros => {
+			let conditions: Vec<Expr> = schema.fields().iter()
+				.filter_map(|f| {
+					match f.data_type() {
+						datafusion::arrow::datatypes::DataType::Int64 | 
+						datafusion::arrow::datatypes::DataType::Int32 => {
+							Some(Expr::Column(datafusion::common::Column::new(None::<String>, f.name())).not_eq(lit(0)))
+						},
+						datafusion::arrow::datatypes::DataType::Float64 | 
+						datafusion::arrow::datatypes::DataType::Float32 => {
+							Some(Expr::Column(datafusion::common::Column::new(None::<String>, f.name())).not_eq(lit(0.0)))
+						},
+						_ => None,
+					}
+				})
+				.collect();
+			
+			if conditions.is_empty() {
+				return Ok(df);
+			}
+			
+			conditions.into_iter().reduce(|acc, expr| acc.and(expr)).unwrap()
+		},
+	};
+	
+	Ok(df.filter(filter_expr)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'a', 0.5), (2, '', 0.0), (3, NULL, 1.5), (0, 'd', 2.5)) AS t(n, s, x)"
+		).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_filter_conditions() {
+		let options = FilterOptions { conditions: Some("n>1".to_string()), ..Default::default() };
+		assert_eq!(filter(sample_df().await, options).await.unwrap().count().await.unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_row_filters() {
+		let count = |rows| async move {
+			let options = FilterOptions { rows: Some(rows), ..Default::default() };
+			filter(sample_df().await, options).await.unwrap().count().await.unwrap()
+		};
+		assert_eq!(count(RowFilter::NoNan).await, 3);
+		assert_eq!(count(RowFilter::CharOnly).await, 2);
+		assert_eq!(count(RowFilter::NoZeros).await, 2);
+	}
+
+	#[tokio::test]
+	async fn test_filters_combine() {
+		let options = FilterOptions {
+			conditions: Some("x<1".to_string()),
+			rows: Some(RowFilter::NoZeros),
+			..Default::default()
+		};
+		assert_eq!(filter(sample_df().await, options).await.unwrap().count().await.unwrap(), 1);
+	}
+}
@@ -0,0 +1,124 @@
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::Column;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone)]
+pub struct FlattenOptions {
+	/// Struct columns to flatten; all struct columns when `None`.
+	pub columns: Option<String>,
+	/// Joins parent and child names, `parent.child` by default.
+	pub separator: String,
+	/// Number of struct levels to expand; unlimited when `None`.
+	pub depth: Option<usize>,
+}
+
+impl Default for FlattenOptions {
+	fn default() -> Self {
+		Self { columns: None, separator: ".".to_string(), depth: None }
+	}
+}
+
+/// Replaces struct columns with one column per field, named `parent<separator>child`,
+/// recursing into nested structs up to `depth` levels.
+pub async fn flatten(df: DataFrame, options: FlattenOptions) -> NailResult<DataFrame> {
+	if options.depth == Some(0) {
+		return Err(NailError::InvalidArgument("--depth must be at least 1".to_string()));
+	}
+
+	let schema = df.schema().clone();
+	let targets = match &options.columns {
+		Some(selector) => {
+			let selected = select_columns_by_pattern(schema.clone().into(), selector)?;
+			if let Some(name) = selected.iter().find(|name| {
+				schema.field_with_unqualified_name(name).map(|f| !matches!(f.data_type(), DataType::Struct(_))).unwrap_or(false)
+			}) {
+				return Err(NailError::InvalidArgument(format!("Column '{}' is not a struct column", name)));
+			}
+			selected
+		},
+		None => schema.fields().iter()
+			.filter(|f| matches!(f.data_type(), DataType::Struct(_)))
+			.map(|f| f.name().clone())
+			.collect(),
+	};
+
+	let mut select_exprs = Vec::new();
+	let mut names: Vec<String> = Vec::new();
+	for field in schema.fields() {
+		let source = Expr::Column(Column::new(None::<String>, field.name()));
+		if targets.contains(field.name()) {
+			expand(source, field, field.name(), options.depth, &options.separator, &mut select_exprs, &mut names);
+		} else {
+			select_exprs.push(source);
+			names.push(field.name().clone());
+		}
+	}
+
+	for (index, name) in names.iter().enumerate() {
+		if names[..index].contains(name) {
+			return Err(NailError::InvalidArgument(format!(
+				"Flattening would create column '{}' twice. Use a different --separator", name
+			)));
+		}
+	}
+
+	Ok(df.select(select_exprs)?)
+}
+
+fn expand(
+	expr: Expr,
+	field: &Field,
+	name: &str,
+	depth: Option<usize>,
+	separator: &str,
+	select_exprs: &mut Vec<Expr>,
+	names: &mut Vec<String>,
+) {
+	match field.data_type() {
+		DataType::Struct(children) if depth != Some(0) => {
+			for child in children {
+				let child_name = format!("{}{}{}", name, separator, child.name());
+				let child_expr = get_field(expr.clone(), child.name().as_str());
+				expand(child_expr, child, &child_name, depth.map(|d| d - 1), separator, select_exprs, names);
+			}
+		},
+		_ => {
+			select_exprs.push(expr.alias(name));
+			names.push(name.to_string());
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn nested() -> DataFrame {
+		SessionContext::new()
+			.sql("SELECT 1 AS id, named_struct('user', named_struct('name', 'ann', 'age', 31), 'ok', true) AS event")
+			.await.unwrap()
+	}
+
+	fn column_names(df: &DataFrame) -> Vec<String> {
+		df.schema().fields().iter().map(|f| f.name().clone()).collect()
+	}
+
+	#[tokio::test]
+	async fn test_flatten_all_levels() {
+		let df = flatten(nested().await, FlattenOptions::default()).await.unwrap();
+		assert_eq!(column_names(&df), vec!["id", "event.user.name", "event.user.age", "event.ok"]);
+	}
+
+	#[tokio::test]
+	async fn test_flatten_depth_and_separator() {
+		let df = flatten(nested().await, FlattenOptions {
+			separator: "__".to_string(),
+			depth: Some(1),
+			..Default::default()
+		}).await.unwrap();
+		assert_eq!(column_names(&df), vec!["id", "event__user", "event__ok"]);
+		assert!(matches!(df.schema().field(1).data_type(), DataType::Struct(_)));
+	}
+}
@@ -0,0 +1,45 @@
+use datafusion::functions_aggregate::expr_fn::count;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyOptions {
+	/// Columns whose value combinations are counted.
+	pub columns: String,
+}
+
+/// Counts each distinct combination of the selected columns, most frequent first,
+/// in a `frequency` column.
+pub async fn frequency(df: DataFrame, options: FrequencyOptions) -> NailResult<DataFrame> {
+	let columns = select_columns_by_pattern(df.schema().clone().into(), &options.columns)?;
+	if columns.is_empty() {
+		return Err(NailError::InvalidArgument("No column names provided".to_string()));
+	}
+
+	let group_by: Vec<Expr> = columns.iter().map(ident).collect();
+	Ok(df
+		.aggregate(group_by, vec![count(lit(1)).alias("frequency")])?
+		.sort(vec![col("frequency").sort(false, true)])?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{Int64Array, StringArray};
+
+	#[tokio::test]
+	async fn test_frequency_orders_by_count() {
+		let df = SessionContext::new().sql(
+			"SELECT * FROM (VALUES ('a'), ('b'), ('b'), ('c'), ('b')) AS t(\"Name\")"
+		).await.unwrap();
+		let options = FrequencyOptions { columns: "name".to_string() };
+		let batches = frequency(df, options).await.unwrap().collect().await.unwrap();
+
+		let names = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+		let counts = batches[0].column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+		assert_eq!(names.value(0), "b");
+		assert_eq!(counts.value(0), 3);
+		assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+	}
+}
@@ -0,0 +1,18 @@
+use datafusion::prelude::*;
+use crate::error::NailResult;
+
+#[derive(Debug, Clone)]
+pub struct HeadOptions {
+	pub number: usize,
+}
+
+impl Default for HeadOptions {
+	fn default() -> Self {
+		Self { number: 5 }
+	}
+}
+
+/// Keeps the first `number` rows.
+pub async fn head(df: DataFrame, options: HeadOptions) -> NailResult<DataFrame> {
+	Ok(df.limit(0, Some(options.number))?)
+}
@@ -0,0 +1,79 @@
+use datafusion::prelude::*;
+use datafusion::functions::string::expr_fn::concat;
+use datafusion::functions_window::expr_fn::row_number;
+use crate::error::NailResult;
+
+#[derive(Debug, Clone)]
+pub struct IdOptions {
+	pub column: String,
+	/// Text placed before each sequence number.
+	pub prefix: String,
+}
+
+impl Default for IdOptions {
+	fn default() -> Self {
+		Self {
+			column: "id".to_string(),
+			prefix: "id".to_string(),
+		}
+	}
+}
+
+/// Adds a sequential ID column as the first column.
+pub async fn add_id(df: DataFrame, options: IdOptions) -> NailResult<DataFrame> {
+	add_id_column(df, &options.column, &options.prefix)
+}
+
+fn add_id_column(df: DataFrame, col_name: &str, prefix: &str) -> NailResult<DataFrame> {
+	// Check if column already exists
+	let schema = df.schema();
+	if schema.field_with_name(None, col_name).is_ok() {
+		return Err(crate::error::NailError::InvalidArgument(
+			format!("Column '{}' already exists. Use --id-col-name to specify a different name.", col_name)
+		));
+	}
+	
+	let id_col = if prefix.is_empty() {
+		row_number()
+	} else {
+		concat(vec![lit(prefix), row_number()])
+	};
+	
+	let columns = std::iter::once(id_col.alias(col_name))
+		.chain(schema.fields().iter().map(|f| ident(f.name())))
+		.collect::<Vec<_>>();
+	
+	Ok(df.select(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{Array, StringArray, UInt64Array};
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql("SELECT * FROM (VALUES ('a'), ('b'), ('c')) AS t(name)").await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_add_id_with_prefix() {
+		let batches = add_id(sample_df().await, IdOptions::default()).await.unwrap().collect().await.unwrap();
+		assert_eq!(batches[0].schema().field(0).name(), "id");
+		let ids = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+		assert_eq!(ids.iter().flatten().collect::<Vec<_>>(), vec!["id1", "id2", "id3"]);
+	}
+
+	#[tokio::test]
+	async fn test_add_id_without_prefix() {
+		let options = IdOptions { column: "row".to_string(), prefix: String::new() };
+		let batches = add_id(sample_df().await, options).await.unwrap().collect().await.unwrap();
+		let ids = batches[0].column(0).as_any().downcast_ref::<UInt64Array>().unwrap();
+		assert_eq!(ids.values().to_vec(), vec![1, 2, 3]);
+	}
+
+	#[tokio::test]
+	async fn test_add_id_rejects_existing_column() {
+		let options = IdOptions { column: "name".to_string(), ..Default::default() };
+		assert!(add_id(sample_df().await, options).await.is_err());
+	}
+}
@@ -0,0 +1,388 @@
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::json::ReaderBuilder;
+use datafusion::arrow::json::reader::infer_json_schema_from_iterator;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::prelude::*;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use crate::error::{NailError, NailResult};
+use crate::utils::collect_in_session;
+use crate::utils::io::dataframe_from_batches;
+use crate::utils::column::select_columns_by_pattern;
+
+/// What to do with values that are not valid JSON or do not fit the inferred types.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum InvalidJsonPolicy {
+	/// Stop with an error showing the invalid values.
+	#[default]
+	Fail,
+	/// Leave the extracted columns null for those rows.
+	Null,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonExtractOptions {
+	/// String column holding JSON documents.
+	pub column: String,
+	/// Paths such as `$.user.id` or `$.items[0].sku`, each extracted into a column `<column>.<path>`.
+	pub paths: Vec<String>,
+	/// Infer the fields from the values instead, expanding nested objects into one column per field.
+	pub infer: bool,
+	/// Number of non-empty documents used to infer the column types.
+	pub sample: usize,
+	pub on_error: InvalidJsonPolicy,
+	/// Keep the JSON column next to the extracted columns.
+	pub keep: bool,
+}
+
+impl Default for JsonExtractOptions {
+	fn default() -> Self {
+		Self {
+			column: String::new(),
+			paths: Vec::new(),
+			infer: false,
+			sample: 1000,
+			on_error: InvalidJsonPolicy::default(),
+			keep: false,
+		}
+	}
+}
+
+/// Rows whose JSON could not be used; only non-empty with `InvalidJsonPolicy::Null`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonExtractReport {
+	pub invalid: usize,
+	/// A few of the invalid rows with the reason, for messages.
+	pub examples: Vec<String>,
+}
+
+const MAX_EXAMPLES: usize = 3;
+
+impl JsonExtractReport {
+	fn record(&mut self, row: usize, text: &str, reason: &str) {
+		self.invalid += 1;
+		if self.examples.len() < MAX_EXAMPLES {
+			let shown: String = text.chars().take(40).collect();
+			let ellipsis = if shown.len() < text.len() { "..." } else { "" };
+			self.examples.push(format!("row {}: {} ('{}{}')", row, reason, shown, ellipsis));
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+	Key(String),
+	Index(usize),
+}
+
+/// Splits comma-separated paths, keeping commas inside brackets such as `$['a,b']`.
+pub fn split_paths(spec: &str) -> Vec<String> {
+	let mut paths = Vec::new();
+	let mut current = String::new();
+	let mut depth = 0i32;
+	for c in spec.chars() {
+		match c {
+			'[' => depth += 1,
+			']' => depth -= 1,
+			',' if depth == 0 => {
+				paths.push(current.trim().to_string());
+				current.clear();
+				continue;
+			},
+			_ => {},
+		}
+		current.push(c);
+	}
+	paths.push(current.trim().to_string());
+	paths.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// Parses `$.user.id`, `$.items[0].sku` or `$['odd key']`; the leading `$` is optional.
+fn parse_path(path: &str) -> NailResult<Vec<PathSegment>> {
+	let invalid = |reason: &str| NailError::InvalidArgument(format!("Invalid JSON path '{}': {}", path, reason));
+	let trimmed = path.trim();
+	let mut rest = trimmed.strip_prefix('$').unwrap_or(trimmed);
+	let mut segments = Vec::new();
+	while !rest.is_empty() {
+		if let Some(tail) = rest.strip_prefix('[') {
+			let end = tail.find(']').ok_or_else(|| invalid("missing ']'"))?;
+			let inner = tail[..end].trim();
+			let quoted = inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\''))
+				.or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+			segments.push(match quoted {
+				Some(key) => PathSegment::Key(key.to_string()),
+				None => PathSegment::Index(inner.parse().map_err(|_| invalid("expected an index or a quoted key in brackets"))?),
+			});
+			rest = &tail[end + 1..];
+		} else {
+			let tail = match rest.strip_prefix('.') {
+				Some(tail) => tail,
+				None if segments.is_empty() => rest,
+				None => return Err(invalid("expected '.' or '['")),
+			};
+			let end = tail.find(['.', '[']).unwrap_or(tail.len());
+			if end == 0 {
+				return Err(invalid("empty key"));
+			}
+			segments.push(PathSegment::Key(tail[..end].to_string()));
+			rest = &tail[end..];
+		}
+	}
+	if segments.is_empty() {
+		return Err(invalid("select a field, e.g. $.user.id"));
+	}
+	Ok(segments)
+}
+
+/// Column name for a path: `payload` and `$.items[0].sku` give `payload.items[0].sku`.
+fn path_column_name(column: &str, segments: &[PathSegment]) -> String {
+	let mut name = column.to_string();
+	for segment in segments {
+		match segment {
+			PathSegment::Key(key) => {
+				name.push('.');
+				name.push_str(key);
+			},
+			PathSegment::Index(index) => name.push_str(&format!("[{}]", index)),
+		}
+	}
+	name
+}
+
+fn lookup<'a>(value: &'a Value, segments: &[PathSegment]) -> Option<&'a Value> {
+	segments.iter().try_fold(value, |value, segment| match segment {
+		PathSegment::Key(key) => value.get(key),
+		PathSegment::Index(index) => value.get(*index),
+	})
+}
+
+/// Moves every leaf of a nested object into `out` under `prefix.key.subkey`.
+fn flatten_object(prefix: &str, map: Map<String, Value>, out: &mut Map<String, Value>) {
+	for (key, value) in map {
+		let name = format!("{}.{}", prefix, key);
+		match value {
+			Value::Object(inner) => flatten_object(&name, inner, out),
+			value => {
+				out.insert(name, value);
+			},
+		}
+	}
+}
+
+/// Extracts fields from a column of JSON strings into typed columns, either at the given
+/// paths or, with `infer`, every field found in the sampled documents. Types are inferred
+/// from up to `sample` documents; nested arrays become list columns.
+pub async fn json_extract(df: DataFrame, options: JsonExtractOptions) -> NailResult<(DataFrame, JsonExtractReport)> {
+	if options.infer != options.paths.is_empty() {
+		return Err(NailError::InvalidArgument("Use either --paths or --infer".to_string()));
+	}
+
+	let input_schema = df.schema().as_arrow().clone();
+	let column = match select_columns_by_pattern(df.schema().clone().into(), &options.column)?.as_slice() {
+		[column] => column.clone(),
+		selected => return Err(NailError::InvalidArgument(format!(
+			"--column must select one column, '{}' selects {}", options.column, selected.len()
+		))),
+	};
+	let index = input_schema.index_of(&column)?;
+	if !matches!(input_schema.field(index).data_type(), DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View) {
+		return Err(NailError::InvalidArgument(format!(
+			"Column '{}' is {}, not a string column", column, input_schema.field(index).data_type()
+		)));
+	}
+	let paths = options.paths.iter()
+		.map(|path| parse_path(path).map(|segments| (path_column_name(&column, &segments), segments)))
+		.collect::<NailResult<Vec<_>>>()?;
+
+	let (ctx, batches) = collect_in_session(df).await?;
+	let mut report = JsonExtractReport::default();
+	let mut records: Vec<Vec<Value>> = Vec::with_capacity(batches.len());
+	let mut row = 0;
+	for batch in &batches {
+		let texts = cast(batch.column(index), &DataType::Utf8)?;
+		let texts = texts.as_string::<i32>();
+		let mut batch_records = Vec::with_capacity(texts.len());
+		for i in 0..texts.len() {
+			row += 1;
+			let mut record = Map::new();
+			if texts.is_valid(i) && !texts.value(i).trim().is_empty() {
+				match parse_document(texts.value(i), options.infer) {
+					Ok(Value::Object(object)) if options.infer => flatten_object(&column, object, &mut record),
+					Ok(document) => {
+						for (name, segments) in &paths {
+							record.insert(name.clone(), lookup(&document, segments).cloned().unwrap_or(Value::Null));
+						}
+					},
+					Err(reason) => report.record(row, texts.value(i), &reason),
+				}
+			}
+			batch_records.push(Value::Object(record));
+		}
+		records.push(batch_records);
+	}
+	check_invalid(&report, &options)?;
+
+	let extracted = Arc::new(extracted_schema(&records, &paths, &column, &options)?);
+	let kept: Vec<&Field> = input_schema.fields().iter()
+		.filter(|f| options.keep || *f.name() != column)
+		.map(|f| f.as_ref())
+		.collect();
+	if let Some(field) = extracted.fields().iter().find(|f| kept.iter().any(|k| k.name() == f.name())) {
+		return Err(NailError::InvalidArgument(format!("Column '{}' already exists", field.name())));
+	}
+
+	let mut fields: Vec<Field> = Vec::new();
+	for (i, field) in input_schema.fields().iter().enumerate() {
+		if options.keep || i != index {
+			fields.push(field.as_ref().clone());
+		}
+		if i == index {
+			fields.extend(extracted.fields().iter().map(|f| f.as_ref().clone()));
+		}
+	}
+	let schema = Arc::new(Schema::new(fields));
+
+	// Rows that do not fit the inferred types are found one by one, then treated as invalid
+	let mut output = Vec::with_capacity(batches.len());
+	let mut first_row = 1;
+	for (batch, mut batch_records) in batches.iter().zip(records) {
+		let decoded = match decode(extracted.clone(), &batch_records) {
+			Ok(decoded) => decoded,
+			Err(_) => {
+				let texts = cast(batch.column(index), &DataType::Utf8)?;
+				for (i, record) in batch_records.iter_mut().enumerate() {
+					if let Err(e) = decode(extracted.clone(), std::slice::from_ref(record)) {
+						report.record(first_row + i, texts.as_string::<i32>().value(i), &format!("does not fit the inferred types: {}", e));
+						*record = Value::Object(Map::new());
+					}
+				}
+				decode(extracted.clone(), &batch_records)?
+			},
+		};
+		first_row += batch.num_rows();
+
+		let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+		for (i, array) in batch.columns().iter().enumerate() {
+			if options.keep || i != index {
+				columns.push(array.clone());
+			}
+			if i == index {
+				columns.extend(decoded.columns().iter().cloned());
+			}
+		}
+		output.push(RecordBatch::try_new(schema.clone(), columns)?);
+	}
+	check_invalid(&report, &options)?;
+
+	Ok((dataframe_from_batches(&ctx, schema, output)?, report))
+}
+
+fn parse_document(text: &str, require_object: bool) -> Result<Value, String> {
+	let document: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+	if require_object && !document.is_object() {
+		return Err("expected a JSON object".to_string());
+	}
+	Ok(document)
+}
+
+fn check_invalid(report: &JsonExtractReport, options: &JsonExtractOptions) -> NailResult<()> {
+	if options.on_error == InvalidJsonPolicy::Fail && report.invalid > 0 {
+		return Err(NailError::InvalidArgument(format!(
+			"{} value(s) in '{}' cannot be extracted: {}. Use --on-error null to leave them null",
+			report.invalid, options.column, report.examples.join("; ")
+		)));
+	}
+	Ok(())
+}
+
+/// Types of the extracted columns, inferred from the first `sample` non-empty records.
+/// Fields never seen with a value are typed as strings.
+fn extracted_schema(records: &[Vec<Value>], paths: &[(String, Vec<PathSegment>)], column: &str, options: &JsonExtractOptions) -> NailResult<Schema> {
+	let sample = records.iter().flatten()
+		.filter(|record| record.as_object().is_some_and(|object| object.values().any(|v| !v.is_null())))
+		.take(options.sample.max(1));
+	let inferred = infer_json_schema_from_iterator(sample.map(Ok))?;
+	let typed = |name: &str, data_type: Option<&DataType>| match data_type {
+		Some(DataType::Null) | None => Field::new(name, DataType::Utf8, true),
+		Some(data_type) => Field::new(name, data_type.clone(), true),
+	};
+
+	if options.infer {
+		if inferred.fields().is_empty() {
+			return Err(NailError::InvalidArgument(format!("Column '{}' has no JSON fields to infer columns from", column)));
+		}
+		Ok(Schema::new(inferred.fields().iter().map(|f| typed(f.name(), Some(f.data_type()))).collect::<Vec<_>>()))
+	} else {
+		Ok(Schema::new(paths.iter()
+			.map(|(name, _)| typed(name, inferred.field_with_name(name).ok().map(|f| f.data_type())))
+			.collect::<Vec<_>>()))
+	}
+}
+
+fn decode(schema: SchemaRef, records: &[Value]) -> Result<RecordBatch, ArrowError> {
+	let mut decoder = ReaderBuilder::new(schema.clone())
+		.with_batch_size(records.len().max(1))
+		.with_coerce_primitive(true)
+		.build_decoder()?;
+	decoder.serialize(records)?;
+	Ok(decoder.flush()?.unwrap_or_else(|| RecordBatch::new_empty(schema)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::util::pretty::pretty_format_batches;
+
+	async fn payloads() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES \
+			 (1, '{\"user\": {\"id\": 7, \"name\": \"ann\"}, \"amount\": 12.5, \"tags\": [\"a\", \"b\"]}'), \
+			 (2, '{\"user\": {\"id\": 8}, \"amount\": 3}'), \
+			 (3, NULL)) AS t(id, payload)"
+		).await.unwrap()
+	}
+
+	#[test]
+	fn test_parse_path() {
+		assert_eq!(parse_path("$.items[0].sku").unwrap(), vec![
+			PathSegment::Key("items".to_string()), PathSegment::Index(0), PathSegment::Key("sku".to_string()),
+		]);
+		assert_eq!(parse_path("user.id").unwrap(), parse_path("$['user'].id").unwrap());
+		assert!(parse_path("$").is_err());
+		assert!(parse_path("$.a[x]").is_err());
+		assert_eq!(split_paths("$.a, $['b,c']"), vec!["$.a", "$['b,c']"]);
+	}
+
+	#[tokio::test]
+	async fn test_extract_paths() {
+		let (df, report) = json_extract(payloads().await, JsonExtractOptions {
+			column: "payload".to_string(),
+			paths: vec!["$.user.id".to_string(), "$.amount".to_string(), "$.tags[1]".to_string()],
+			..Default::default()
+		}).await.unwrap();
+		assert_eq!(report.invalid, 0);
+		let types: Vec<String> = df.schema().fields().iter().map(|f| format!("{}:{}", f.name(), f.data_type())).collect();
+		assert_eq!(types, vec!["id:Int64", "payload.user.id:Int64", "payload.amount:Float64", "payload.tags[1]:Utf8"]);
+		let table = pretty_format_batches(&df.collect().await.unwrap()).unwrap().to_string();
+		assert!(table.contains("| 1  | 7               | 12.5           | b               |"), "{}", table);
+	}
+
+	#[tokio::test]
+	async fn test_infer_and_invalid_json() {
+		let df = SessionContext::new().sql(
+			"SELECT * FROM (VALUES ('{\"user\": {\"id\": 7}, \"ok\": true}'), ('not json')) AS t(payload)"
+		).await.unwrap();
+		let options = JsonExtractOptions { column: "payload".to_string(), infer: true, ..Default::default() };
+		assert!(json_extract(df.clone(), options.clone()).await.is_err());
+
+		let (df, report) = json_extract(df, JsonExtractOptions { on_error: InvalidJsonPolicy::Null, ..options }).await.unwrap();
+		assert_eq!(report.invalid, 1);
+		assert!(report.examples[0].starts_with("row 2:"), "{:?}", report);
+		let types: Vec<String> = df.schema().fields().iter().map(|f| format!("{}:{}", f.name(), f.data_type())).collect();
+		assert_eq!(types, vec!["payload.ok:Boolean", "payload.user.id:Int64"]);
+		assert_eq!(df.count().await.unwrap(), 2);
+	}
+}
@@ -0,0 +1,136 @@
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::{column_hint, resolve_column_name, split_pair, unquoted_name};
+use datafusion::common::DFSchemaRef;
+
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+	pub join_type: JoinType,
+	/// Key column present in both tables, matched case-insensitively.
+	pub key: Option<String>,
+	/// `(left, right)` key columns when their names differ; takes precedence over `key`.
+	/// Each side is resolved like `key`, and may be quoted to match exactly.
+	pub key_mapping: Option<(String, String)>,
+}
+
+impl Default for MergeOptions {
+	fn default() -> Self {
+		Self {
+			join_type: JoinType::Inner,
+			key: None,
+			key_mapping: None,
+		}
+	}
+}
+
+/// Joins two tables on a key. Right-hand columns are prefixed with `r_`.
+pub async fn merge(left_df: DataFrame, right_df: DataFrame, options: MergeOptions) -> NailResult<DataFrame> {
+	let (left_key, right_key) = if let Some((left, right)) = &options.key_mapping {
+		(find_key(&left_df, left, "left")?, find_key(&right_df, right, "right")?)
+	} else if let Some(key) = &options.key {
+		(find_key(&left_df, key, "left")?, find_key(&right_df, key, "right")?)
+	} else {
+		return Err(NailError::InvalidArgument("Either --key or --key-mapping must be specified".to_string()));
+	};
+	
+	perform_join(left_df, right_df, &left_key, &right_key, options.join_type)
+}
+
+// Resolve the key with the shared column name rules
+fn find_key(df: &DataFrame, key: &str, side: &str) -> NailResult<String> {
+	let schema: DFSchemaRef = df.schema().clone().into();
+	resolve_column_name(&schema, key).map_err(|e| match e {
+		NailError::ColumnNotFound(_) => {
+			let available_cols: Vec<String> = schema.fields().iter()
+				.map(|f| f.name().clone())
+				.collect();
+			let key = unquoted_name(key);
+			NailError::ColumnNotFound(format!(
+				"Join key '{}' not found in {} table. {}", 
+				key, side, column_hint(&[&key], &available_cols)
+			))
+		},
+		other => other,
+	})
+}
+
+/// Parses a `left_col=right_col` key mapping. Names may be double-quoted; quotes are kept
+/// so the names resolve exactly.
+pub fn parse_key_mapping(mapping: &str) -> NailResult<(String, String)> {
+	match split_pair(mapping, '=') {
+		Some((left, right)) if !left.is_empty() && !right.is_empty() && split_pair(right, '=').is_none() => {
+			Ok((left.to_string(), right.to_string()))
+		},
+		_ => Err(NailError::InvalidArgument("Key mapping must be in format 'left_col=right_col'".to_string())),
+	}
+}
+
+fn perform_join(
+	left_df: DataFrame,
+	right_df: DataFrame,
+	left_key: &str,
+	right_key: &str,
+	join_type: JoinType,
+) -> NailResult<DataFrame> {
+	let right_name = |name: &str| format!("r_{}", name);
+	
+	// Prefix the right-hand columns first so names shared by both sides stay distinct
+	let right_df = right_df.clone().select(right_df.schema().fields().iter()
+		.map(|f| ident(f.name()).alias(right_name(f.name())))
+		.collect::<Vec<_>>())?;
+	
+	let output_columns: Vec<Expr> = left_df.schema().fields().iter()
+		.map(|f| ident(f.name()))
+		.chain(right_df.schema().fields().iter()
+			.filter(|f| *f.name() != right_name(right_key))
+			.map(|f| ident(f.name())))
+		.collect();
+	
+	let joined = left_df.join_on(right_df, join_type, [ident(left_key).eq(ident(right_name(right_key)))])?;
+	Ok(joined.select(output_columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_key_mapping_valid() {
+		let result = parse_key_mapping("left_col=right_col");
+		assert!(result.is_ok());
+		let (left, right) = result.unwrap();
+		assert_eq!(left, "left_col");
+		assert_eq!(right, "right_col");
+	}
+
+	#[test]
+	fn test_parse_key_mapping_with_spaces() {
+		let result = parse_key_mapping("  left_col  =  right_col  ");
+		assert!(result.is_ok());
+		let (left, right) = result.unwrap();
+		assert_eq!(left, "left_col");
+		assert_eq!(right, "right_col");
+	}
+
+	#[test]
+	fn test_parse_key_mapping_invalid() {
+		let result = parse_key_mapping("invalid_format");
+		assert!(result.is_err());
+		assert!(result.unwrap_err().to_string().contains("Key mapping must be in format"));
+	}
+
+	#[test]
+	fn test_parse_key_mapping_quoted() {
+		let (left, right) = parse_key_mapping("\"user=id\"=\"ID\"").unwrap();
+		assert_eq!(left, "\"user=id\"");
+		assert_eq!(right, "\"ID\"");
+		assert!(parse_key_mapping("a=b=c").is_err());
+		assert!(parse_key_mapping("a=").is_err());
+	}
+
+	#[test]
+	fn test_parse_key_mapping_empty() {
+		let result = parse_key_mapping("");
+		assert!(result.is_err());
+	}
+}
@@ -0,0 +1,75 @@
+//! Typed data operations on DataFusion DataFrames.
+//!
+//! Each module exposes one function taking a `DataFrame` and an options struct and
+//! returning the transformed `DataFrame`. The CLI commands are thin wrappers around
+//! these functions; nothing here reads files, writes output or prints.
+//!
+//! ```no_run
+//! use nail_parquet::ops::fill::{fill, FillMethod, FillOptions};
+//! # async fn example(df: datafusion::prelude::DataFrame) -> nail_parquet::NailResult<()> {
+//! let filled = fill(df, FillOptions { method: FillMethod::Mean, ..Default::default() }).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod append;
+pub mod binning;
+pub mod cast;
+pub mod correlations;
+pub mod create;
+pub mod dates;
+pub mod dedup;
+pub mod drop;
+pub mod explode;
+pub mod fill;
+pub mod filter;
+pub mod flatten;
+pub mod frequency;
+pub mod head;
+pub mod id;
+pub mod json_extract;
+pub mod merge;
+pub mod nest;
+pub mod outliers;
+pub mod pivot;
+pub mod rename;
+pub mod sample;
+pub mod search;
+pub mod select;
+pub mod shuffle;
+pub mod sort;
+pub mod split;
+pub mod stats;
+pub mod strings;
+pub mod tail;
+
+pub use append::{append, AppendOptions};
+pub use binning::{binning, BinningMethod, BinningOptions, Bins};
+pub use cast::{cast, CastErrorPolicy, CastOptions, CastReport};
+pub use correlations::{correlations, CorrelationType, CorrelationsOptions};
+pub use create::{create, CreateOptions};
+pub use dates::{dates, DateFormatReport, DatePart, DateTarget, DateUnit, DatesOptions};
+pub use dedup::{dedup, DedupMode, DedupOptions};
+pub use drop::{drop, DropOptions};
+pub use explode::{explode, ExplodeOptions};
+pub use fill::{fill, FillMethod, FillOptions};
+pub use filter::{filter, DateWindow, FilterOptions, RowFilter};
+pub use flatten::{flatten, FlattenOptions};
+pub use frequency::{frequency, FrequencyOptions};
+pub use head::{head, HeadOptions};
+pub use id::{add_id, IdOptions};
+pub use json_extract::{json_extract, InvalidJsonPolicy, JsonExtractOptions, JsonExtractReport};
+pub use merge::{merge, MergeOptions};
+pub use nest::{nest, NestOptions};
+pub use outliers::{outliers, OutlierMethod, OutliersOptions};
+pub use pivot::{pivot, AggregationFunction, PivotOptions};
+pub use rename::{rename, RenameOptions};
+pub use sample::{sample, SampleMethod, SampleOptions};
+pub use search::{search, SearchOptions};
+pub use select::{select, SelectOptions};
+pub use shuffle::{shuffle, ShuffleOptions};
+pub use sort::{sort, NullHandling, SortOptions, SortStrategy};
+pub use split::{split, SplitOptions};
+pub use stats::{stats, StatsOptions, StatsType};
+pub use strings::{strings, CaseStyle, PadSide, Padding, StringsOptions, UnicodeForm};
+pub use tail::{tail, TailOptions};
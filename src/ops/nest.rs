@@ -0,0 +1,171 @@
+use datafusion::common::Column;
+use datafusion::functions_aggregate::expr_fn::array_agg;
+use datafusion::logical_expr::ExprFunctionExt;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub struct NestOptions {
+	/// `(struct name, column selector)` pairs; each selection is packed into one struct column.
+	pub pack: Vec<(String, String)>,
+	/// Keep the packed columns next to the new struct column.
+	pub keep: bool,
+	/// Key columns to group by before collecting.
+	pub by: Option<String>,
+	/// Columns aggregated into one list per group; all non-key columns when `None`.
+	pub collect: Option<String>,
+	/// Columns ordering the list elements. Without them the element order is unspecified:
+	/// partitions of multi-file or multi-row-group input are not combined in read order.
+	pub order_by: Option<String>,
+}
+
+/// Parses `name=columns` pack specs; the columns part is a column selector.
+pub fn parse_pack_specs(specs: &[String]) -> NailResult<Vec<(String, String)>> {
+	specs.iter()
+		.map(|spec| {
+			spec.split_once('=')
+				.map(|(name, columns)| (name.trim().to_string(), columns.trim().to_string()))
+				.filter(|(name, columns)| !name.is_empty() && !columns.is_empty())
+				.ok_or_else(|| NailError::InvalidArgument(format!(
+					"Invalid pack spec '{}': expected name=col1,col2", spec
+				)))
+		})
+		.collect()
+}
+
+fn column(name: &str) -> Expr {
+	Expr::Column(Column::new(None::<String>, name))
+}
+
+/// Packs columns into struct columns, then optionally groups rows by key columns and
+/// collects the other columns into list columns. Groups are sorted by their keys.
+pub async fn nest(df: DataFrame, options: NestOptions) -> NailResult<DataFrame> {
+	if options.pack.is_empty() && options.by.is_none() {
+		return Err(NailError::InvalidArgument("Nothing to do: use --pack NAME=COLUMNS and/or --by KEYS".to_string()));
+	}
+	if options.by.is_none() && (options.collect.is_some() || options.order_by.is_some()) {
+		return Err(NailError::InvalidArgument("--collect and --order-by need --by".to_string()));
+	}
+
+	let mut df = df;
+	for (name, selector) in &options.pack {
+		df = pack(df, name, selector, options.keep)?;
+	}
+
+	match &options.by {
+		Some(by) => collect(df, by, &options).await,
+		None => Ok(df),
+	}
+}
+
+fn pack(df: DataFrame, name: &str, selector: &str, keep: bool) -> NailResult<DataFrame> {
+	let packed = select_columns_by_pattern(df.schema().clone().into(), selector)?;
+	let existing: Vec<String> = df.schema().fields().iter().map(|f| f.name().clone()).collect();
+	if existing.iter().any(|e| e == name) && (keep || !packed.iter().any(|p| p == name)) {
+		return Err(NailError::InvalidArgument(format!("Column '{}' already exists", name)));
+	}
+
+	let struct_expr = named_struct(packed.iter().flat_map(|c| [lit(c.as_str()), column(c)]).collect());
+	let mut struct_expr = Some(struct_expr.alias(name));
+	let mut select_exprs = Vec::new();
+	for field in &existing {
+		if packed.contains(field) {
+			// The struct takes the place of its first field
+			if let Some(expr) = struct_expr.take() {
+				select_exprs.push(expr);
+			}
+			if !keep {
+				continue;
+			}
+		}
+		select_exprs.push(column(field));
+	}
+	Ok(df.select(select_exprs)?)
+}
+
+async fn collect(df: DataFrame, by: &str, options: &NestOptions) -> NailResult<DataFrame> {
+	let schema = df.schema().clone();
+	let keys = select_columns_by_pattern(schema.clone().into(), by)?;
+	let collected: Vec<String> = match &options.collect {
+		Some(selector) => select_columns_by_pattern(schema.clone().into(), selector)?,
+		None => schema.fields().iter().map(|f| f.name().clone()).filter(|name| !keys.contains(name)).collect(),
+	};
+	if let Some(overlap) = collected.iter().find(|name| keys.contains(name)) {
+		return Err(NailError::InvalidArgument(format!("Column '{}' cannot be both a key and collected", overlap)));
+	}
+	if collected.is_empty() {
+		return Err(NailError::InvalidArgument("No columns left to collect".to_string()));
+	}
+
+	let order: Vec<_> = match &options.order_by {
+		Some(selector) => select_columns_by_pattern(schema.clone().into(), selector)?.iter()
+			.map(|name| column(name).sort(true, false))
+			.collect(),
+		None => Vec::new(),
+	};
+
+	let aggregates = collected.iter()
+		.map(|name| {
+			let aggregate = array_agg(column(name));
+			let aggregate = if order.is_empty() { aggregate } else { aggregate.order_by(order.clone()).build()? };
+			Ok(aggregate.alias(name))
+		})
+		.collect::<NailResult<Vec<_>>>()?;
+	let key_exprs: Vec<Expr> = keys.iter().map(|name| column(name)).collect();
+	let sort_exprs = keys.iter().map(|name| column(name).sort(true, false)).collect();
+
+	Ok(df.aggregate(key_exprs, aggregates)?.sort(sort_exprs)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::util::pretty::pretty_format_batches;
+
+	async fn orders() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (2, 'pen', 3, 10), (1, 'ink', 1, 20), (2, 'cap', 5, 30), (1, 'nib', 2, 40)) AS t(user_id, sku, qty, ts)"
+		).await.unwrap()
+	}
+
+	async fn render(df: DataFrame) -> String {
+		pretty_format_batches(&df.collect().await.unwrap()).unwrap().to_string()
+	}
+
+	#[test]
+	fn test_parse_pack_specs() {
+		assert_eq!(
+			parse_pack_specs(&["item=sku,qty".to_string()]).unwrap(),
+			vec![("item".to_string(), "sku,qty".to_string())]
+		);
+		assert!(parse_pack_specs(&["item".to_string()]).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_pack_and_collect() {
+		let df = nest(orders().await, NestOptions {
+			pack: vec![("item".to_string(), "sku,qty".to_string())],
+			by: Some("user_id".to_string()),
+			collect: Some("item".to_string()),
+			order_by: Some("ts".to_string()),
+			..Default::default()
+		}).await.unwrap();
+		let table = render(df).await;
+		assert!(table.contains("| 1       | [{sku: ink, qty: 1}, {sku: nib, qty: 2}] |"), "{}", table);
+		assert!(table.contains("| 2       | [{sku: pen, qty: 3}, {sku: cap, qty: 5}] |"), "{}", table);
+	}
+
+	#[tokio::test]
+	async fn test_collect_without_order_by() {
+		let df = nest(orders().await, NestOptions {
+			by: Some("user_id".to_string()),
+			..Default::default()
+		}).await.unwrap();
+		let table = render(df).await;
+		// Without --order-by the elements may come in any order, but every row is collected once
+		let row = table.lines().find(|line| line.starts_with("| 2 ")).unwrap();
+		assert!(row.contains("[pen, cap]") || row.contains("[cap, pen]"), "{}", table);
+		assert!(row.contains("[3, 5]") || row.contains("[5, 3]"), "{}", table);
+	}
+}
@@ -0,0 +1,284 @@
+use datafusion::arrow::array::{Array, Float64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::functions_aggregate::expr_fn::{approx_percentile_cont, avg, max, min, stddev};
+use datafusion::functions_window::expr_fn::row_number;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub enum OutlierMethod {
+	/// Outside `multiplier` interquartile ranges from the quartiles.
+	#[default]
+	Iqr,
+	/// Absolute z-score above the threshold.
+	ZScore,
+	/// Absolute median-based z-score above the threshold.
+	ModifiedZScore,
+	/// Far from the mean and in the outer tenth of the range; a simplified isolation heuristic.
+	IsolationForest,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutliersOptions {
+	/// Column selector; every numeric column when `None`.
+	pub columns: Option<String>,
+	pub method: OutlierMethod,
+	pub iqr_multiplier: f64,
+	/// Threshold for the z-score and modified z-score methods.
+	pub z_score_threshold: f64,
+	/// List the outlying values with their score instead of flagging every row.
+	pub show_values: bool,
+	/// Add the 1-based input row number to each reported row.
+	pub include_row_numbers: bool,
+	/// Return the input without the rows that are outliers in any column.
+	pub remove: bool,
+}
+
+impl Default for OutliersOptions {
+	fn default() -> Self {
+		Self {
+			columns: None,
+			method: OutlierMethod::default(),
+			iqr_multiplier: 1.5,
+			z_score_threshold: 3.0,
+			show_values: false,
+			include_row_numbers: false,
+			remove: false,
+		}
+	}
+}
+
+/// How one column's outliers are found and described.
+struct OutlierRule {
+	column: String,
+	is_outlier: Expr,
+	detail: Expr,
+}
+
+/// Reports the outliers of each selected column, or removes them with `remove`.
+/// Columns whose spread is undefined or zero are skipped.
+pub async fn outliers(df: DataFrame, options: OutliersOptions) -> NailResult<DataFrame> {
+	let columns = match &options.columns {
+		Some(spec) => select_columns_by_pattern(df.schema().clone().into(), spec)?,
+		None => df.schema().fields().iter()
+			.filter(|field| matches!(field.data_type(),
+				DataType::Float64 | DataType::Float32 |
+				DataType::Int64 | DataType::Int32 |
+				DataType::Int16 | DataType::Int8
+			))
+			.map(|f| f.name().clone())
+			.collect(),
+	};
+
+	if columns.is_empty() {
+		return Err(NailError::InvalidArgument(
+			"No numeric columns found for outlier detection".to_string()
+		));
+	}
+
+	let mut rules = Vec::new();
+	for column in &columns {
+		if let Some(rule) = outlier_rule(&df, column, &options).await? {
+			rules.push(rule);
+		}
+	}
+
+	if options.remove {
+		// A null is never an outlier, so rows with nulls are kept
+		return match rules.into_iter().map(|rule| rule.is_outlier.is_true()).reduce(Expr::or) {
+			Some(any_outlier) => Ok(df.filter(not(any_outlier))?),
+			None => Ok(df),
+		};
+	}
+
+	let method_name = match options.method {
+		OutlierMethod::Iqr => "IQR",
+		OutlierMethod::ZScore => "Z-Score",
+		OutlierMethod::ModifiedZScore => "Modified Z-Score",
+		OutlierMethod::IsolationForest => "Isolation Forest",
+	};
+
+	// Number the rows before filtering so the numbers are positions in the input
+	let mut numbered_columns = vec![row_number().alias("row_number")];
+	numbered_columns.extend(df.schema().fields().iter().map(|f| ident(f.name())));
+	let numbered = df.select(numbered_columns)?;
+
+	let mut reports = Vec::new();
+	for rule in rules {
+		let mut report_columns = vec![lit(rule.column.as_str()).alias("column_name")];
+		if options.include_row_numbers {
+			report_columns.push(col("row_number"));
+		}
+		let report = if options.show_values {
+			report_columns.push(cast(ident(&rule.column), DataType::Float64).alias("value"));
+			report_columns.push(rule.detail);
+			report_columns.push(lit(method_name).alias("method"));
+			numbered.clone().filter(rule.is_outlier)?.select(report_columns)?
+		} else {
+			report_columns.push(rule.is_outlier.is_true().alias("is_outlier"));
+			report_columns.push(lit(method_name).alias("method"));
+			numbered.clone().select(report_columns)?
+		};
+		reports.push(report);
+	}
+
+	let mut iter = reports.into_iter();
+	let mut combined = iter.next()
+		.ok_or_else(|| NailError::Statistics("No outliers detected".to_string()))?;
+	for report in iter {
+		combined = combined.union(report)?;
+	}
+
+	Ok(combined)
+}
+
+/// Computes the statistics `column` needs for the method; `None` when they are null or degenerate.
+async fn outlier_rule(df: &DataFrame, column: &str, options: &OutliersOptions) -> NailResult<Option<OutlierRule>> {
+	let value = cast(ident(column), DataType::Float64);
+	let rule = |is_outlier: Expr, detail: Expr| Some(OutlierRule { column: column.to_string(), is_outlier, detail });
+
+	Ok(match options.method {
+		OutlierMethod::Iqr => {
+			let Some([q1, q3]) = aggregate_values(df, [
+				approx_percentile_cont(value.clone(), lit(0.25), None),
+				approx_percentile_cont(value.clone(), lit(0.75), None),
+			]).await? else {
+				return Ok(None);
+			};
+			let iqr = q3 - q1;
+			let lower_bound = q1 - options.iqr_multiplier * iqr;
+			let upper_bound = q3 + options.iqr_multiplier * iqr;
+			rule(
+				value.clone().lt(lit(lower_bound)).or(value.gt(lit(upper_bound))),
+				lit(format!("bounds: {:.3} to {:.3}", lower_bound, upper_bound)).alias("bounds"),
+			)
+		},
+		OutlierMethod::ZScore => {
+			let Some([mean_val, stddev_val]) = aggregate_values(df, [avg(value.clone()), stddev(value.clone())]).await? else {
+				return Ok(None);
+			};
+			if stddev_val == 0.0 {
+				return Ok(None);
+			}
+			let z_score = abs((value - lit(mean_val)) / lit(stddev_val));
+			rule(z_score.clone().gt(lit(options.z_score_threshold)), z_score.alias("z_score"))
+		},
+		OutlierMethod::ModifiedZScore => {
+			let Some([median_val]) = aggregate_values(df, [approx_percentile_cont(value.clone(), lit(0.5), None)]).await? else {
+				return Ok(None);
+			};
+			// MAD (Median Absolute Deviation)
+			let deviation = abs(value.clone() - lit(median_val));
+			let Some([mad_val]) = aggregate_values(df, [approx_percentile_cont(deviation, lit(0.5), None)]).await? else {
+				return Ok(None);
+			};
+			if mad_val == 0.0 {
+				return Ok(None);
+			}
+			let modified_z_score = lit(0.6745) * (value - lit(median_val)) / lit(mad_val);
+			rule(
+				abs(modified_z_score.clone()).gt(lit(options.z_score_threshold)),
+				modified_z_score.alias("modified_z_score"),
+			)
+		},
+		OutlierMethod::IsolationForest => {
+			let Some([mean_val, stddev_val, min_val, max_val]) = aggregate_values(df, [
+				avg(value.clone()),
+				stddev(value.clone()),
+				min(value.clone()),
+				max(value.clone()),
+			]).await? else {
+				return Ok(None);
+			};
+			if stddev_val == 0.0 {
+				return Ok(None);
+			}
+			let range_10th = min_val + 0.1 * (max_val - min_val);
+			let range_90th = max_val - 0.1 * (max_val - min_val);
+			let isolation_score = abs(value.clone() - lit(mean_val)) / lit(stddev_val);
+			rule(
+				isolation_score.clone().gt(lit(2.5))
+					.and(value.clone().lt(lit(range_10th)).or(value.gt(lit(range_90th)))),
+				isolation_score.alias("isolation_score"),
+			)
+		},
+	})
+}
+
+/// Evaluates Float64 aggregates over the whole frame; `None` if any of them is null.
+async fn aggregate_values<const N: usize>(df: &DataFrame, aggregates: [Expr; N]) -> NailResult<Option<[f64; N]>> {
+	let aliased = aggregates.into_iter().enumerate().map(|(i, expr)| expr.alias(format!("value_{}", i))).collect();
+	let batches = df.clone().aggregate(vec![], aliased)?.collect().await?;
+	let Some(batch) = batches.iter().find(|b| b.num_rows() > 0) else {
+		return Ok(None);
+	};
+
+	let mut values = [0.0; N];
+	for (i, value) in values.iter_mut().enumerate() {
+		let array = batch.column(i).as_any().downcast_ref::<Float64Array>()
+			.ok_or_else(|| NailError::Statistics("Unexpected statistic type".to_string()))?;
+		if array.is_null(0) {
+			return Ok(None);
+		}
+		*value = array.value(0);
+	}
+	Ok(Some(values))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{BooleanArray, StringArray, UInt64Array};
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 10), (2, 11), (3, 12), (4, 11), (5, 10), (6, 12), (7, 11), (8, 500)) AS t(id, amount)"
+		).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_iqr_values_with_row_numbers() {
+		let options = OutliersOptions {
+			columns: Some("amount".to_string()),
+			show_values: true,
+			include_row_numbers: true,
+			..Default::default()
+		};
+		let batches = outliers(sample_df().await, options).await.unwrap().collect().await.unwrap();
+		let batch = batches.iter().find(|b| b.num_rows() > 0).unwrap();
+		assert_eq!(batch.num_rows(), 1);
+		let rows = batch.column(1).as_any().downcast_ref::<UInt64Array>().unwrap();
+		assert_eq!(rows.value(0), 8);
+		let values = batch.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+		assert_eq!(values.value(0), 500.0);
+	}
+
+	#[tokio::test]
+	async fn test_flags_cover_every_column() {
+		let batches = outliers(sample_df().await, OutliersOptions::default()).await.unwrap().collect().await.unwrap();
+		let mut flagged = Vec::new();
+		for batch in &batches {
+			let names = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+			let flags = batch.column(1).as_any().downcast_ref::<BooleanArray>().unwrap();
+			for i in 0..batch.num_rows() {
+				flagged.push((names.value(i).to_string(), flags.value(i)));
+			}
+		}
+		assert_eq!(flagged.len(), 16);
+		assert_eq!(flagged.iter().filter(|(_, outlier)| *outlier).count(), 1);
+		assert!(flagged.contains(&("amount".to_string(), true)));
+	}
+
+	#[tokio::test]
+	async fn test_remove_outliers() {
+		let options = OutliersOptions {
+			method: OutlierMethod::ZScore,
+			z_score_threshold: 2.0,
+			remove: true,
+			..Default::default()
+		};
+		let result = outliers(sample_df().await, options).await.unwrap();
+		assert_eq!(result.count().await.unwrap(), 7);
+	}
+}
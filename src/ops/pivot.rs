@@ -0,0 +1,147 @@
+use datafusion::functions_aggregate::expr_fn::{avg, count, max, min, sum};
+use datafusion::prelude::*;
+use std::fmt;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::{column_not_found, select_columns_by_pattern};
+
+#[derive(Debug, Clone, Default)]
+pub enum AggregationFunction {
+	#[default]
+	Sum,
+	Mean,
+	Count,
+	Min,
+	Max,
+}
+
+impl fmt::Display for AggregationFunction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AggregationFunction::Sum => write!(f, "Sum"),
+			AggregationFunction::Mean => write!(f, "Mean"),
+			AggregationFunction::Count => write!(f, "Count"),
+			AggregationFunction::Min => write!(f, "Min"),
+			AggregationFunction::Max => write!(f, "Max"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PivotOptions {
+	/// Row index columns.
+	pub index: String,
+	/// Pivot columns.
+	pub columns: String,
+	/// Numeric columns to aggregate; every other numeric column when `None`.
+	pub values: Option<String>,
+	pub agg: AggregationFunction,
+}
+
+/// Aggregates each value column per combination of index and pivot values.
+/// Aggregated columns are named `<value>_<agg>`, e.g. `sales_sum`.
+pub async fn pivot(df: DataFrame, options: PivotOptions) -> NailResult<DataFrame> {
+	let index_cols = select_columns_by_pattern(df.schema().clone().into(), &options.index)?;
+	let pivot_cols = select_columns_by_pattern(df.schema().clone().into(), &options.columns)?;
+
+	let schema = df.schema();
+	let available_cols = || schema.fields().iter().map(|f| f.name().to_string()).collect::<Vec<_>>();
+	for column in index_cols.iter().chain(pivot_cols.iter()) {
+		if schema.field_with_name(None, column).is_err() {
+			return Err(column_not_found(column, &available_cols()));
+		}
+	}
+
+	let value_cols: Vec<String> = match &options.values {
+		Some(spec) => {
+			let cols = select_columns_by_pattern(df.schema().clone().into(), spec)?;
+			// Validate value columns exist and are numeric
+			for column in &cols {
+				match schema.field_with_name(None, column) {
+					Ok(field) if field.data_type().is_numeric() => {},
+					Ok(field) => {
+						return Err(NailError::InvalidArgument(
+							format!("Value column '{}' must be numeric (type: {:?})", column, field.data_type())
+						));
+					},
+					Err(_) => return Err(column_not_found(column, &available_cols())),
+				}
+			}
+			cols
+		},
+		// Without value columns, aggregate every numeric column not used as index or pivot
+		None => schema.fields().iter()
+			.filter(|field| {
+				!index_cols.contains(field.name())
+					&& !pivot_cols.contains(field.name())
+					&& field.data_type().is_numeric()
+			})
+			.map(|field| field.name().clone())
+			.collect(),
+	};
+
+	if value_cols.is_empty() {
+		return Err(NailError::InvalidArgument(
+			"No numeric value columns found to aggregate. Please specify value columns with --values".to_string()
+		));
+	}
+
+	let group_exprs: Vec<Expr> = index_cols.iter().chain(pivot_cols.iter()).map(ident).collect();
+	let agg_name = options.agg.to_string().to_lowercase();
+	let agg_exprs: Vec<Expr> = value_cols.iter()
+		.map(|value_col| {
+			let value = ident(value_col);
+			let agg_expr = match options.agg {
+				AggregationFunction::Sum => sum(value),
+				AggregationFunction::Mean => avg(value),
+				AggregationFunction::Count => count(value),
+				AggregationFunction::Min => min(value),
+				AggregationFunction::Max => max(value),
+			};
+			agg_expr.alias(format!("{}_{}", value_col, agg_name))
+		})
+		.collect();
+
+	Ok(df.aggregate(group_exprs, agg_exprs)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{Int64Array, StringArray};
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES ('a', 'x', 1, 'p'), ('a', 'x', 2, 'q'), ('a', 'y', 3, 'r'), ('b', 'x', 4, 's')) AS t(region, kind, sales, note)"
+		).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_pivot_sums_numeric_columns() {
+		let options = PivotOptions {
+			index: "region".to_string(),
+			columns: "kind".to_string(),
+			..Default::default()
+		};
+		let result = pivot(sample_df().await, options).await.unwrap()
+			.sort(vec![col("region").sort(true, false), col("kind").sort(true, false)]).unwrap();
+		let batches = result.collect().await.unwrap();
+		let batch = &batches[0];
+		assert_eq!(batch.schema().field(2).name(), "sales_sum");
+		let regions = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+		assert_eq!(regions.iter().flatten().collect::<Vec<_>>(), vec!["a", "a", "b"]);
+		let sums = batch.column(2).as_any().downcast_ref::<Int64Array>().unwrap();
+		assert_eq!(sums.values().to_vec(), vec![3, 3, 4]);
+	}
+
+	#[tokio::test]
+	async fn test_pivot_rejects_non_numeric_values() {
+		let options = PivotOptions {
+			index: "region".to_string(),
+			columns: "kind".to_string(),
+			values: Some("note".to_string()),
+			agg: AggregationFunction::Count,
+		};
+		let err = pivot(sample_df().await, options).await.unwrap_err();
+		assert!(err.to_string().contains("must be numeric"));
+	}
+}
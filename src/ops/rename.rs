@@ -0,0 +1,117 @@
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::{resolve_column_name, split_pair, split_selectors, unquoted_name};
+use datafusion::common::DFSchemaRef;
+
+#[derive(Debug, Clone, Default)]
+pub struct RenameOptions {
+    /// `(before, after)` pairs. `before` is resolved like any column name (quoted, or
+    /// case-insensitively when unambiguous); `after` is used as given.
+    pub columns: Vec<(String, String)>,
+}
+
+/// Parses comma-separated `before=after` pairs. Either side may be a double-quoted name
+/// containing commas or `=`; `before` keeps its quotes so it resolves exactly.
+pub fn parse_rename_spec(spec: &str) -> NailResult<Vec<(String, String)>> {
+    let mut rename_map = Vec::new();
+    for pair in split_selectors(spec) {
+        let (before, after) = match split_pair(&pair, '=') {
+            Some((before, after)) if !before.is_empty() && !after.is_empty() => (before, after),
+            _ => return Err(NailError::InvalidArgument(format!("Invalid column spec: {}", pair))),
+        };
+        rename_map.push((before.to_string(), unquoted_name(after)));
+    }
+    Ok(rename_map)
+}
+
+/// Renames columns, keeping their order.
+pub async fn rename(df: DataFrame, options: RenameOptions) -> NailResult<DataFrame> {
+    // Resolve source columns with the shared name rules
+    let schema: DFSchemaRef = df.schema().clone().into();
+    let rename_map = options.columns.iter()
+        .map(|(source, target)| Ok((resolve_column_name(&schema, source)?, target.clone())))
+        .collect::<NailResult<Vec<(String, String)>>>()?;
+
+    // Validate no duplicate target column names
+    let mut target_names = Vec::new();
+    let mut final_column_names = Vec::new();
+    
+    for field in schema.fields() {
+        let name = field.name();
+        if let Some(new_name) = rename_map.iter().find(|(b, _)| b == name).map(|(_, a)| a) {
+            target_names.push(new_name.clone());
+            final_column_names.push(new_name.clone());
+        } else {
+            final_column_names.push(name.clone());
+        }
+    }
+    
+    // Check for duplicates in target names
+    for (i, name) in target_names.iter().enumerate() {
+        if target_names.iter().skip(i + 1).any(|other| other == name) {
+            return Err(NailError::InvalidArgument(format!("Duplicate target column name: '{}'", name)));
+        }
+    }
+    
+    // Check for conflicts between renamed columns and existing columns
+    for target in &target_names {
+        if final_column_names.iter().filter(|&n| n == target).count() > 1 {
+            return Err(NailError::InvalidArgument(format!("Target column name '{}' conflicts with existing column", target)));
+        }
+    }
+
+    // Build select expressions with aliases
+    let select_exprs: Vec<Expr> = schema.fields().iter()
+        .map(|f| {
+            let name = f.name();
+            if let Some(new_name) = rename_map.iter().find(|(b, _)| b == name).map(|(_, a)| a) {
+                Expr::Column(datafusion::common::Column::new(None::<String>, name)).alias(new_name)
+            } else {
+                Expr::Column(datafusion::common::Column::new(None::<String>, name))
+            }
+        })
+        .collect();
+    
+    Ok(df.select(select_exprs)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn sample_df() -> DataFrame {
+        SessionContext::new().sql("SELECT * FROM (VALUES (1, 'a', true)) AS t(id, name, flag)").await.unwrap()
+    }
+
+    fn column_names(df: &DataFrame) -> Vec<String> {
+        df.schema().fields().iter().map(|f| f.name().clone()).collect()
+    }
+
+    #[test]
+    fn test_parse_rename_spec() {
+        assert_eq!(
+            parse_rename_spec("id=key,\"a,b\"=\"c=d\"").unwrap(),
+            vec![("id".to_string(), "key".to_string()), ("\"a,b\"".to_string(), "c=d".to_string())]
+        );
+        assert!(parse_rename_spec("id=").is_err());
+        assert!(parse_rename_spec("id").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rename_keeps_order() {
+        let options = RenameOptions { columns: vec![("NAME".to_string(), "label".to_string())] };
+        let result = rename(sample_df().await, options).await.unwrap();
+        assert_eq!(column_names(&result), vec!["id", "label", "flag"]);
+    }
+
+    #[tokio::test]
+    async fn test_rename_rejects_conflicts() {
+        let options = RenameOptions { columns: vec![("name".to_string(), "id".to_string())] };
+        assert!(rename(sample_df().await, options).await.is_err());
+
+        let options = RenameOptions {
+            columns: vec![("name".to_string(), "x".to_string()), ("flag".to_string(), "x".to_string())],
+        };
+        assert!(rename(sample_df().await, options).await.is_err());
+    }
+}
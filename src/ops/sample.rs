@@ -0,0 +1,198 @@
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::prelude::*;
+use rand::seq::index;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::BTreeMap;
+use crate::error::{NailError, NailResult};
+use crate::ops::select::filter_row_numbers;
+use crate::utils::column::resolve_column_name;
+use datafusion::arrow::array::{StringArray, Array};
+
+#[derive(Clone, Debug, Default)]
+pub enum SampleMethod {
+	#[default]
+	Random,
+	Stratified,
+	First,
+	Last,
+}
+
+#[derive(Debug, Clone)]
+pub struct SampleOptions {
+	pub number: usize,
+	pub method: SampleMethod,
+	/// Column used to build strata, required for `SampleMethod::Stratified`.
+	pub stratify_by: Option<String>,
+	/// Random seed for reproducible samples.
+	pub seed: Option<u64>,
+}
+
+impl Default for SampleOptions {
+	fn default() -> Self {
+		Self {
+			number: 10,
+			method: SampleMethod::default(),
+			stratify_by: None,
+			seed: None,
+		}
+	}
+}
+
+/// Draws `number` rows; returns the input unchanged when it has no more rows than that.
+pub async fn sample(df: DataFrame, options: SampleOptions) -> NailResult<DataFrame> {
+	let total_rows = df.clone().count().await?;
+
+	if options.number >= total_rows {
+		return Ok(df);
+	}
+
+	let sampled_df = match options.method {
+		SampleMethod::Random => {
+			let mut rng = seeded_rng(options.seed);
+			let mut indices = index::sample(&mut rng, total_rows, options.number).into_vec();
+			indices.sort_unstable();
+			filter_row_numbers(df, &indices, true)?
+		},
+		SampleMethod::Stratified => {
+			if let Some(col) = &options.stratify_by {
+				sample_stratified(df, options.number, col, options.seed).await?
+			} else {
+				return Err(NailError::InvalidArgument("--stratify-by required for stratified sampling".to_string()));
+			}
+		},
+		SampleMethod::First => df.limit(0, Some(options.number))?,
+		SampleMethod::Last => {
+			let skip = total_rows.saturating_sub(options.number);
+			df.limit(skip, Some(options.number))?
+		},
+	};
+
+	Ok(sampled_df)
+}
+
+fn seeded_rng(seed: Option<u64>) -> StdRng {
+	match seed {
+		Some(s) => StdRng::seed_from_u64(s),
+		None => StdRng::from_entropy(),
+	}
+}
+
+/// Samples each non-null category of `stratify_col` in proportion to its size, keeping input order.
+async fn sample_stratified(
+	df: DataFrame,
+	n: usize,
+	stratify_col: &str,
+	seed: Option<u64>,
+) -> NailResult<DataFrame> {
+	let actual_col_name = resolve_column_name(&df.schema().clone().into(), stratify_col)?;
+
+	// Row positions per category; a BTreeMap keeps the allocation deterministic for a seed.
+	let batches = df.clone().select(vec![ident(&actual_col_name)])?.collect().await?;
+	let mut category_rows: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+	let mut row = 0;
+	for batch in &batches {
+		let values = cast(batch.column(0), &DataType::Utf8)?;
+		let values = values.as_any().downcast_ref::<StringArray>()
+			.ok_or_else(|| NailError::Statistics(format!("Cannot read column '{}' as text", actual_col_name)))?;
+		for i in 0..values.len() {
+			if values.is_valid(i) {
+				category_rows.entry(values.value(i).to_string()).or_default().push(row);
+			}
+			row += 1;
+		}
+	}
+
+	if category_rows.is_empty() {
+		return Err(NailError::Statistics("No categories found for stratified sampling".to_string()));
+	}
+	let total_count: usize = category_rows.values().map(Vec::len).sum();
+
+	// Calculate samples per category proportionally
+	let mut samples_per_category = BTreeMap::new();
+	let mut total_samples = 0;
+
+	for (cat, rows) in &category_rows {
+		let proportion = rows.len() as f64 / total_count as f64;
+		let samples = ((n as f64 * proportion).round() as usize).min(rows.len()); // Don't sample more than available
+		samples_per_category.insert(cat.as_str(), samples);
+		total_samples += samples;
+	}
+
+	// Adjust if we're short on samples due to rounding
+	if total_samples < n {
+		let mut remaining = n - total_samples;
+		for (cat, rows) in &category_rows {
+			let current_samples = samples_per_category[cat.as_str()];
+			if current_samples < rows.len() && remaining > 0 {
+				let additional = remaining.min(rows.len() - current_samples);
+				samples_per_category.insert(cat.as_str(), current_samples + additional);
+				remaining -= additional;
+			}
+		}
+	}
+
+	let mut rng = seeded_rng(seed);
+	let mut indices = Vec::with_capacity(n);
+	for (cat, rows) in &category_rows {
+		let samples = samples_per_category[cat.as_str()];
+		indices.extend(index::sample(&mut rng, rows.len(), samples).into_iter().map(|i| rows[i]));
+	}
+	if indices.is_empty() {
+		return Err(NailError::Statistics("No data sampled".to_string()));
+	}
+	indices.sort_unstable();
+
+	filter_row_numbers(df, &indices, true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::Int64Array;
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'a'), (2, 'a'), (3, 'a'), (4, 'a'), (5, 'a'), (6, 'a'), (7, 'b'), (8, 'b'), (9, 'b'), (10, 'b')) AS t(id, kind)"
+		).await.unwrap()
+	}
+
+	async fn ids(df: DataFrame) -> Vec<i64> {
+		df.collect().await.unwrap().iter()
+			.flat_map(|b| b.column(0).as_any().downcast_ref::<Int64Array>().unwrap().values().to_vec())
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn test_random_sample_is_reproducible() {
+		let options = SampleOptions { number: 4, seed: Some(3), ..Default::default() };
+		let first = ids(sample(sample_df().await, options.clone()).await.unwrap()).await;
+		assert_eq!(first.len(), 4);
+		assert_eq!(first, ids(sample(sample_df().await, options).await.unwrap()).await);
+	}
+
+	#[tokio::test]
+	async fn test_first_and_last() {
+		let options = SampleOptions { number: 3, method: SampleMethod::First, ..Default::default() };
+		assert_eq!(ids(sample(sample_df().await, options).await.unwrap()).await, vec![1, 2, 3]);
+
+		let options = SampleOptions { number: 3, method: SampleMethod::Last, ..Default::default() };
+		assert_eq!(ids(sample(sample_df().await, options).await.unwrap()).await, vec![8, 9, 10]);
+	}
+
+	#[tokio::test]
+	async fn test_stratified_sample_keeps_proportions() {
+		let options = SampleOptions {
+			number: 5,
+			method: SampleMethod::Stratified,
+			stratify_by: Some("kind".to_string()),
+			seed: Some(1),
+		};
+		let sampled = ids(sample(sample_df().await, options).await.unwrap()).await;
+		assert_eq!(sampled.len(), 5);
+		assert_eq!(sampled.iter().filter(|id| **id <= 6).count(), 3);
+
+		let options = SampleOptions { number: 5, method: SampleMethod::Stratified, ..Default::default() };
+		assert!(sample(sample_df().await, options).await.is_err());
+	}
+}
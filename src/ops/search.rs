@@ -0,0 +1,133 @@
+use datafusion::arrow::datatypes::DataType;
+use datafusion::functions::string::expr_fn::lower;
+use datafusion::functions_window::expr_fn::row_number;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+	pub value: String,
+	/// Column selector; all columns when `None`.
+	pub columns: Option<String>,
+	/// Return the 1-based numbers of matching rows instead of the rows themselves.
+	pub row_numbers: bool,
+	pub ignore_case: bool,
+	/// Match whole values only instead of substrings.
+	pub exact: bool,
+}
+
+/// Keeps the rows where any searched string or numeric column matches the value.
+pub async fn search(df: DataFrame, options: SearchOptions) -> NailResult<DataFrame> {
+	let columns = match &options.columns {
+		Some(spec) => select_columns_by_pattern(df.schema().clone().into(), spec)?,
+		None => df.schema().fields().iter().map(|f| f.name().clone()).collect(),
+	};
+
+	let mut conditions = Vec::new();
+	for column in &columns {
+		let field = df.schema().field_with_name(None, column)
+			.map_err(|_| NailError::ColumnNotFound(column.clone()))?;
+		if let Some(condition) = match_condition(ident(column), field.data_type(), &options) {
+			conditions.push(condition);
+		}
+	}
+
+	// Combine all conditions with OR
+	let combined_filter = conditions.into_iter()
+		.reduce(|acc, expr| acc.or(expr))
+		.ok_or_else(|| NailError::InvalidArgument("No searchable columns found".to_string()))?;
+
+	if !options.row_numbers {
+		return Ok(df.filter(combined_filter)?);
+	}
+
+	// Number the rows before filtering so the numbers are positions in the input
+	let mut numbered = vec![row_number().alias("row_number")];
+	numbered.extend(df.schema().fields().iter().map(|f| ident(f.name())));
+	Ok(df.select(numbered)?
+		.filter(combined_filter)?
+		.select(vec![
+			col("row_number"),
+			lit(options.value.as_str()).alias("search_value"),
+			lit(columns.join(",")).alias("matched_columns"),
+		])?)
+}
+
+/// Builds the match for one column; `None` for columns the value cannot match.
+fn match_condition(column: Expr, data_type: &DataType, options: &SearchOptions) -> Option<Expr> {
+	let value = options.value.as_str();
+	match data_type {
+		DataType::Utf8 => {
+			let (column, value) = if options.ignore_case {
+				(lower(column), value.to_lowercase())
+			} else {
+				(column, value.to_string())
+			};
+			if options.exact {
+				Some(column.eq(lit(value)))
+			} else {
+				Some(column.like(lit(format!("%{}%", value))))
+			}
+		},
+		DataType::Int64 | DataType::Float64 => {
+			let number = value.parse::<f64>().ok()?;
+			if options.exact {
+				Some(column.eq(lit(number)))
+			} else {
+				// Partial matches compare the text form of the number
+				Some(cast(column, DataType::Utf8).like(lit(format!("%{}%", value))))
+			}
+		},
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{StringArray, UInt64Array};
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob'), (12, 'alicia')) AS t(id, name)"
+		).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_search_ignore_case() {
+		let options = SearchOptions {
+			value: "ALI".to_string(),
+			columns: Some("name".to_string()),
+			ignore_case: true,
+			..Default::default()
+		};
+		let result = search(sample_df().await, options).await.unwrap();
+		assert_eq!(result.count().await.unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_search_row_numbers() {
+		let options = SearchOptions {
+			value: "2".to_string(),
+			row_numbers: true,
+			..Default::default()
+		};
+		let batches = search(sample_df().await, options).await.unwrap().collect().await.unwrap();
+		let rows = batches[0].column(0).as_any().downcast_ref::<UInt64Array>().unwrap();
+		assert_eq!(rows.values().to_vec(), vec![2, 3]);
+		let columns = batches[0].column(2).as_any().downcast_ref::<StringArray>().unwrap();
+		assert_eq!(columns.value(0), "id,name");
+	}
+
+	#[tokio::test]
+	async fn test_search_exact() {
+		let options = SearchOptions {
+			value: "Bob".to_string(),
+			exact: true,
+			..Default::default()
+		};
+		let result = search(sample_df().await, options).await.unwrap();
+		assert_eq!(result.count().await.unwrap(), 1);
+	}
+}
@@ -0,0 +1,154 @@
+use datafusion::prelude::*;
+use datafusion::functions_window::expr_fn::row_number;
+use crate::error::{NailError, NailResult};
+pub use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub struct SelectOptions {
+	/// Column names or patterns to keep, comma-separated.
+	pub columns: Option<String>,
+	/// Row numbers and ranges to keep, e.g. `1,3,5-10`.
+	pub rows: Option<String>,
+}
+
+/// Keeps the selected columns and rows.
+pub async fn select(df: DataFrame, options: SelectOptions) -> NailResult<DataFrame> {
+	let mut result_df = df;
+	
+	if let Some(col_spec) = &options.columns {
+		let schema = result_df.schema();
+		let selected_columns = select_columns_by_pattern(schema.clone().into(), col_spec)?;
+		
+		let select_exprs: Vec<Expr> = selected_columns.into_iter()
+			.map(|name| Expr::Column(datafusion::common::Column::new(None::<String>, &name)))
+			.collect();
+		
+		result_df = result_df.select(select_exprs)?;
+	}
+	
+	if let Some(row_spec) = &options.rows {
+		let row_indices = parse_row_specification(row_spec)?;
+		result_df = filter_row_numbers(result_df, &row_indices, true)?;
+	}
+	
+	Ok(result_df)
+}
+
+pub fn parse_row_specification(spec: &str) -> NailResult<Vec<usize>> {
+	let mut indices = Vec::new();
+	
+	for part in spec.split(',') {
+		let part = part.trim();
+		
+		if part.contains('-') {
+			let range_parts: Vec<&str> = part.split('-').collect();
+			if range_parts.len() != 2 {
+				return Err(NailError::InvalidArgument(format!("Invalid range: {}", part)));
+			}
+			
+			let start: usize = range_parts[0].trim().parse()
+				.map_err(|_| NailError::InvalidArgument(format!("Invalid start index: {}", range_parts[0].trim())))?;
+			let end: usize = range_parts[1].trim().parse()
+				.map_err(|_| NailError::InvalidArgument(format!("Invalid end index: {}", range_parts[1].trim())))?;
+			
+			if start > end {
+				return Err(NailError::InvalidArgument(format!("Start index {} greater than end index {}", start, end)));
+			}
+			
+			for i in start..=end {
+				indices.push(i.saturating_sub(1));
+			}
+		} else {
+			let index: usize = part.parse()
+				.map_err(|_| NailError::InvalidArgument(format!("Invalid index: {}", part)))?;
+			indices.push(index.saturating_sub(1));
+		}
+	}
+	
+	indices.sort();
+	indices.dedup();
+	Ok(indices)
+}
+
+/// Column holding the 1-based row number while rows are picked by position.
+const ROW_NUMBER_COLUMN: &str = "__nail_row_number";
+
+/// Keeps (or with `keep` false, removes) the rows at the given 0-based positions.
+pub(crate) fn filter_row_numbers(df: DataFrame, indices: &[usize], keep: bool) -> NailResult<DataFrame> {
+	let columns: Vec<Expr> = df.schema().fields().iter()
+		.map(|f| ident(f.name()))
+		.collect();
+	let row_numbers: Vec<Expr> = indices.iter()
+		.map(|&i| lit(i as u64 + 1))
+		.collect();
+	
+	Ok(df.with_column(ROW_NUMBER_COLUMN, row_number())?
+		.filter(ident(ROW_NUMBER_COLUMN).in_list(row_numbers, !keep))?
+		.select(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_row_specification_single_indices() {
+		let result = parse_row_specification("1,3,5");
+		assert!(result.is_ok());
+		let indices = result.unwrap();
+		assert_eq!(indices, vec![0, 2, 4]); // 1-based to 0-based conversion
+	}
+
+	#[test]
+	fn test_parse_row_specification_ranges() {
+		let result = parse_row_specification("1-3,5-7");
+		assert!(result.is_ok());
+		let indices = result.unwrap();
+		assert_eq!(indices, vec![0, 1, 2, 4, 5, 6]); // 1-based to 0-based conversion
+	}
+
+	#[test]
+	fn test_parse_row_specification_mixed() {
+		let result = parse_row_specification("1,3-5,10");
+		assert!(result.is_ok());
+		let indices = result.unwrap();
+		assert_eq!(indices, vec![0, 2, 3, 4, 9]); // 1-based to 0-based conversion
+	}
+
+	#[test]
+	fn test_parse_row_specification_with_spaces() {
+		let result = parse_row_specification("1, 3 - 5 , 10");
+		assert!(result.is_ok());
+		let indices = result.unwrap();
+		assert_eq!(indices, vec![0, 2, 3, 4, 9]);
+	}
+
+	#[test]
+	fn test_parse_row_specification_invalid_range() {
+		let result = parse_row_specification("5-3");
+		assert!(result.is_err());
+		assert!(result.unwrap_err().to_string().contains("Start index 5 greater than end index 3"));
+	}
+
+	#[test]
+	fn test_parse_row_specification_invalid_number() {
+		let result = parse_row_specification("1,abc,3");
+		assert!(result.is_err());
+		assert!(result.unwrap_err().to_string().contains("Invalid index: abc"));
+	}
+
+	#[test]
+	fn test_parse_row_specification_invalid_range_format() {
+		let result = parse_row_specification("1-2-3");
+		assert!(result.is_err());
+		assert!(result.unwrap_err().to_string().contains("Invalid range: 1-2-3"));
+	}
+
+	#[test]
+	fn test_parse_row_specification_deduplication() {
+		let result = parse_row_specification("1,3,1,3");
+		assert!(result.is_ok());
+		let indices = result.unwrap();
+		assert_eq!(indices, vec![0, 2]); // Duplicates removed
+	}
+}
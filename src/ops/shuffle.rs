@@ -0,0 +1,64 @@
+use datafusion::arrow::array::UInt64Array;
+use datafusion::arrow::compute::{concat_batches, take_record_batch};
+use datafusion::prelude::*;
+use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, SeedableRng};
+use crate::error::NailResult;
+use crate::utils::collect_in_session;
+use crate::utils::io::dataframe_from_batches;
+
+#[derive(Debug, Clone, Default)]
+pub struct ShuffleOptions {
+	/// Random seed for a reproducible order.
+	pub seed: Option<u64>,
+}
+
+/// Returns the rows in random order.
+pub async fn shuffle(df: DataFrame, options: ShuffleOptions) -> NailResult<DataFrame> {
+	let Some(seed) = options.seed else {
+		return Ok(df.sort(vec![random().sort(true, false)])?);
+	};
+
+	// A seeded order has to be drawn here, since RANDOM() cannot be seeded.
+	let schema = df.schema().inner().clone();
+	let (ctx, batches) = collect_in_session(df).await?;
+	let batch = concat_batches(&schema, &batches)?;
+	let mut order: Vec<u64> = (0..batch.num_rows() as u64).collect();
+	order.shuffle(&mut StdRng::seed_from_u64(seed));
+	let shuffled = take_record_batch(&batch, &UInt64Array::from(order))?;
+
+	dataframe_from_batches(&ctx, schema, vec![shuffled])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::Int64Array;
+
+	async fn ids(df: DataFrame) -> Vec<i64> {
+		df.collect().await.unwrap().iter()
+			.flat_map(|b| b.column(0).as_any().downcast_ref::<Int64Array>().unwrap().values().to_vec())
+			.collect()
+	}
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql("SELECT * FROM UNNEST(RANGE(1, 21)) AS t(id)").await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_seeded_shuffle_is_reproducible() {
+		let options = ShuffleOptions { seed: Some(42) };
+		let first = ids(shuffle(sample_df().await, options.clone()).await.unwrap()).await;
+		assert_eq!(first, ids(shuffle(sample_df().await, options).await.unwrap()).await);
+		assert_ne!(first, (1..21).collect::<Vec<_>>());
+	}
+
+	#[tokio::test]
+	async fn test_shuffle_keeps_rows() {
+		for seed in [None, Some(7)] {
+			let mut shuffled = ids(shuffle(sample_df().await, ShuffleOptions { seed }).await.unwrap()).await;
+			shuffled.sort();
+			assert_eq!(shuffled, (1..21).collect::<Vec<_>>());
+		}
+	}
+}
@@ -0,0 +1,357 @@
+use datafusion::prelude::*;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::logical_expr::SortExpr;
+use std::str::FromStr;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::column_not_found;
+use crate::utils::datetime::parse_date_expr;
+
+#[derive(Debug, Clone)]
+pub enum SortStrategy {
+    Numeric,
+    Date,
+    Alphabetic,
+    AlphabeticNumeric,
+    NumericAlphabetic,
+    Hour,
+    Auto,
+}
+
+impl FromStr for SortStrategy {
+    type Err = NailError;
+
+    fn from_str(s: &str) -> NailResult<Self> {
+        match s.to_lowercase().as_str() {
+            "numeric" | "num" => Ok(SortStrategy::Numeric),
+            "date" => Ok(SortStrategy::Date),
+            "alphabetic" | "alpha" => Ok(SortStrategy::Alphabetic),
+            "alphabetic-numeric" | "alpha-num" => Ok(SortStrategy::AlphabeticNumeric),
+            "numeric-alphabetic" | "num-alpha" => Ok(SortStrategy::NumericAlphabetic),
+            "hour" | "time" => Ok(SortStrategy::Hour),
+            "auto" => Ok(SortStrategy::Auto),
+            _ => Err(NailError::InvalidArgument(format!("Unknown sort strategy: {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum NullHandling {
+    First,
+    #[default]
+    Last,
+    Skip,
+}
+
+impl FromStr for NullHandling {
+    type Err = NailError;
+
+    fn from_str(s: &str) -> NailResult<Self> {
+        match s.to_lowercase().as_str() {
+            "first" => Ok(NullHandling::First),
+            "last" => Ok(NullHandling::Last),
+            "skip" => Ok(NullHandling::Skip),
+            _ => Err(NailError::InvalidArgument(format!("Unknown null handling: {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SortOptions {
+    /// Columns to sort by; all columns when empty.
+    pub columns: Vec<String>,
+    /// One strategy per column or a single one for all; `Auto` when empty.
+    pub strategies: Vec<SortStrategy>,
+    /// One flag per column or a single one for all; ascending when empty.
+    pub descending: Vec<bool>,
+    pub nulls: NullHandling,
+    pub case_insensitive: bool,
+    /// Date pattern such as `dd/mm/yyyy` for the `Date` strategy on text columns.
+    pub date_format: Option<String>,
+    /// Time pattern such as `hh:mm:ss` for the `Hour` strategy on text columns.
+    pub hour_format: Option<String>,
+}
+
+/// Sorts rows by one or more columns.
+pub async fn sort(df: DataFrame, options: SortOptions) -> NailResult<DataFrame> {
+    let schema = df.schema();
+    
+    // Determine columns to sort by
+    let sort_columns: Vec<String> = if options.columns.is_empty() {
+        schema.fields().iter().map(|f| f.name().clone()).collect()
+    } else {
+        options.columns.clone()
+    };
+    
+    // Validate columns exist
+    for col in &sort_columns {
+        if schema.field_with_name(None, col).is_err() {
+            let available_cols: Vec<String> = schema.fields()
+                .iter()
+                .map(|f| f.name().to_string())
+                .collect();
+            return Err(column_not_found(col, &available_cols));
+        }
+    }
+    
+    let strategies = match options.strategies.len() {
+        0 => vec![SortStrategy::Auto; sort_columns.len()],
+        1 => vec![options.strategies[0].clone(); sort_columns.len()],
+        n if n == sort_columns.len() => options.strategies.clone(),
+        n => {
+            return Err(NailError::InvalidArgument(
+                format!("Number of strategies ({}) must match number of columns ({}) or be 1 for all", 
+                        n, sort_columns.len())
+            ));
+        }
+    };
+    
+    let descending_flags = match options.descending.len() {
+        0 => vec![false; sort_columns.len()],
+        1 => vec![options.descending[0]; sort_columns.len()],
+        n if n == sort_columns.len() => options.descending.clone(),
+        n => {
+            return Err(NailError::InvalidArgument(
+                format!("Number of descending flags ({}) must match number of columns ({}) or be 1 for all", 
+                        n, sort_columns.len())
+            ));
+        }
+    };
+    
+    // If nulls should be skipped, filter them out first
+    let df = if matches!(options.nulls, NullHandling::Skip) {
+        filter_nulls(df, &sort_columns)?
+    } else {
+        df
+    };
+    let schema = df.schema();
+    
+    // Create sort expressions
+    let mut sort_exprs = Vec::new();
+    
+    for (idx, col_name) in sort_columns.iter().enumerate() {
+        let field = schema.field_with_name(None, col_name)?;
+        
+        let expr = create_sort_expression(
+            col_name,
+            field.data_type(),
+            strategies[idx].clone(),
+            descending_flags[idx],
+            matches!(options.nulls, NullHandling::First),
+            options.case_insensitive,
+            options.date_format.clone(),
+            options.hour_format.clone(),
+        )?;
+        
+        sort_exprs.push(expr);
+    }
+    
+    Ok(df.sort(sort_exprs)?)
+}
+
+fn filter_nulls(df: DataFrame, filter_columns: &[String]) -> NailResult<DataFrame> {
+    // Build filter expression: col1 IS NOT NULL AND col2 IS NOT NULL AND ...
+    let mut filter_expr = None;
+    
+    for col_name in filter_columns {
+        let not_null = col(col_name).is_not_null();
+        
+        filter_expr = match filter_expr {
+            None => Some(not_null),
+            Some(existing) => Some(existing.and(not_null)),
+        };
+    }
+    
+    if let Some(expr) = filter_expr {
+        Ok(df.filter(expr)?)
+    } else {
+        Ok(df)
+    }
+}
+
+fn create_sort_expression(
+    col_name: &str,
+    data_type: &DataType,
+    strategy: SortStrategy,
+    descending: bool,
+    nulls_first: bool,
+    case_insensitive: bool,
+    date_format: Option<String>,
+    hour_format: Option<String>,
+) -> NailResult<SortExpr> {
+    let base_col = col(col_name);
+    
+    let expr = match strategy {
+        SortStrategy::Auto => {
+            // Auto-detect based on data type
+            match data_type {
+                DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 |
+                DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 |
+                DataType::Float32 | DataType::Float64 | DataType::Decimal128(_, _) |
+                DataType::Decimal256(_, _) => {
+                    base_col
+                }
+                DataType::Date32 | DataType::Date64 | 
+                DataType::Timestamp(_, _) | DataType::Time32(_) | DataType::Time64(_) => {
+                    base_col
+                }
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    if case_insensitive {
+                        lower(base_col)
+                    } else {
+                        base_col
+                    }
+                }
+                _ => base_col
+            }
+        }
+        SortStrategy::Numeric => {
+            // Try to cast to numeric if not already
+            match data_type {
+                DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 |
+                DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 |
+                DataType::Float32 | DataType::Float64 | DataType::Decimal128(_, _) |
+                DataType::Decimal256(_, _) => base_col,
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    // Try to cast string to float64 for numeric sorting
+                    cast(base_col, DataType::Float64)
+                }
+                _ => base_col
+            }
+        }
+        SortStrategy::Date => {
+            // Handle date parsing
+            match data_type {
+                DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => base_col,
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    if let Some(format) = date_format {
+                        // Use the custom date parsing expression
+                        parse_date_expr(base_col, &format)?
+                    } else {
+                        // Try default date parsing (ISO format)
+                        to_date(vec![base_col])
+                    }
+                }
+                _ => base_col
+            }
+        }
+        SortStrategy::Hour => {
+            // Handle time/hour parsing
+            match data_type {
+                DataType::Time32(_) | DataType::Time64(_) => base_col,
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    if let Some(format) = hour_format {
+                        parse_time_expr(base_col, &format)?
+                    } else {
+                        // Default: try to extract hour component from timestamp
+                        to_timestamp(vec![base_col])
+                    }
+                }
+                _ => base_col
+            }
+        }
+        SortStrategy::Alphabetic => {
+            match data_type {
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    if case_insensitive {
+                        lower(base_col)
+                    } else {
+                        base_col
+                    }
+                }
+                _ => base_col
+            }
+        }
+        SortStrategy::AlphabeticNumeric => {
+            // Sort alphabetically first, then numerically
+            // This is complex and would require multiple sort keys
+            // For now, we'll use a simplified approach
+            match data_type {
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    // Extract leading alphabetic part for primary sort
+                    if case_insensitive {
+                        lower(base_col)
+                    } else {
+                        base_col
+                    }
+                }
+                _ => base_col
+            }
+        }
+        SortStrategy::NumericAlphabetic => {
+            // Sort numerically first, then alphabetically
+            // Similar to above, simplified approach
+            match data_type {
+                DataType::Utf8 | DataType::LargeUtf8 => {
+                    // Try to extract leading numeric part
+                    base_col
+                }
+                _ => base_col
+            }
+        }
+    };
+    
+    Ok(expr.sort(!descending, nulls_first))
+}
+
+fn parse_time_expr(col_expr: Expr, format: &str) -> NailResult<Expr> {
+    // For time parsing, we'll convert to a sortable numeric representation
+    // This is a simplified approach - in practice, you might want more sophisticated parsing
+    
+    if format.contains("xxx") || format.contains("XXX") {
+        // Handle numeric suffix
+        // For now, just use the column as-is
+        Ok(col_expr)
+    } else {
+        // Convert time format to timestamp format
+        let timestamp_format = convert_time_format(format)?;
+        Ok(to_timestamp(vec![col_expr, lit(timestamp_format)]))
+    }
+}
+
+fn convert_time_format(format: &str) -> NailResult<String> {
+    // Convert user-friendly time format to strptime format
+    let result = format
+        .replace("hh", "%H")
+        .replace("HH", "%H")
+        .replace("mm", "%M")
+        .replace("MM", "%M")
+        .replace("ss", "%S")
+        .replace("SS", "%S")
+        .replace(":", ":");
+    
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_strategy_from_str() {
+        assert!(matches!(SortStrategy::from_str("numeric").unwrap(), SortStrategy::Numeric));
+        assert!(matches!(SortStrategy::from_str("date").unwrap(), SortStrategy::Date));
+        assert!(matches!(SortStrategy::from_str("alphabetic").unwrap(), SortStrategy::Alphabetic));
+        assert!(matches!(SortStrategy::from_str("alpha-num").unwrap(), SortStrategy::AlphabeticNumeric));
+        assert!(matches!(SortStrategy::from_str("num-alpha").unwrap(), SortStrategy::NumericAlphabetic));
+        assert!(matches!(SortStrategy::from_str("hour").unwrap(), SortStrategy::Hour));
+        assert!(matches!(SortStrategy::from_str("auto").unwrap(), SortStrategy::Auto));
+        
+        assert!(SortStrategy::from_str("invalid").is_err());
+    }
+
+    #[test]
+    fn test_null_handling_from_str() {
+        assert!(matches!(NullHandling::from_str("first").unwrap(), NullHandling::First));
+        assert!(matches!(NullHandling::from_str("last").unwrap(), NullHandling::Last));
+        assert!(matches!(NullHandling::from_str("skip").unwrap(), NullHandling::Skip));
+        
+        assert!(NullHandling::from_str("invalid").is_err());
+    }
+
+    #[test]
+    fn test_convert_time_format() {
+        assert_eq!(convert_time_format("hh:mm:ss").unwrap(), "%H:%M:%S");
+        assert_eq!(convert_time_format("HH:MM:SS").unwrap(), "%H:%M:%S");
+        assert_eq!(convert_time_format("mm:ss").unwrap(), "%M:%S");
+    }
+}
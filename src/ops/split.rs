@@ -0,0 +1,173 @@
+use datafusion::arrow::array::{Array, Int64Array, StringArray};
+use datafusion::arrow::compute::cast as cast_array;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::functions_aggregate::expr_fn::count;
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::ops::shuffle::{shuffle, ShuffleOptions};
+use crate::utils::column::resolve_column_name;
+
+#[derive(Debug, Clone, Default)]
+pub struct SplitOptions {
+	/// Share of the rows for each split; must sum to 1.0.
+	pub ratios: Vec<f64>,
+	/// Column whose categories keep their share in every split.
+	pub stratify_by: Option<String>,
+	/// Random seed for a reproducible assignment of rows.
+	pub seed: Option<u64>,
+}
+
+/// Shuffles the rows and cuts them into one DataFrame per ratio.
+/// Returns each split with its row count.
+pub async fn split(df: DataFrame, options: SplitOptions) -> NailResult<Vec<(DataFrame, usize)>> {
+	let sum: f64 = options.ratios.iter().sum();
+	if options.ratios.is_empty() || options.ratios.iter().any(|r| *r <= 0.0) || (sum - 1.0).abs() >= 0.001 {
+		return Err(NailError::InvalidArgument(
+			format!("Ratios must be positive and sum to 1.0, got: {:?}", options.ratios)
+		));
+	}
+
+	// The shuffled order is materialized, so every split cuts the same order
+	let seed = options.seed.unwrap_or_else(rand::random);
+	match &options.stratify_by {
+		Some(column) => stratified_split(df, &options.ratios, column, seed).await,
+		None => {
+			let total_rows = df.clone().count().await?;
+			let shuffled = shuffle(df, ShuffleOptions { seed: Some(seed) }).await?;
+			cut(&shuffled, total_rows, &options.ratios)
+		},
+	}
+}
+
+/// Splits each category separately so that it keeps its share in every split.
+async fn stratified_split(df: DataFrame, ratios: &[f64], stratify_col: &str, seed: u64) -> NailResult<Vec<(DataFrame, usize)>> {
+	let actual_col_name = resolve_column_name(&df.schema().clone().into(), stratify_col)?;
+	let category = cast(ident(&actual_col_name), DataType::Utf8);
+
+	let counts = df.clone()
+		.filter(ident(&actual_col_name).is_not_null())?
+		.aggregate(vec![category.clone().alias("category")], vec![count(lit(1)).alias("count")])?
+		.collect()
+		.await?;
+
+	let mut category_counts = Vec::new();
+	for batch in &counts {
+		let names = cast_array(batch.column(0), &DataType::Utf8)?;
+		let names = names.as_any().downcast_ref::<StringArray>()
+			.ok_or_else(|| NailError::Statistics(format!("Cannot read column '{}' as text", actual_col_name)))?;
+		let rows = batch.column(1).as_any().downcast_ref::<Int64Array>()
+			.ok_or_else(|| NailError::Statistics("Unexpected category count type".to_string()))?;
+		for i in 0..batch.num_rows() {
+			if names.is_valid(i) {
+				category_counts.push((names.value(i).to_string(), rows.value(i) as usize));
+			}
+		}
+	}
+	category_counts.sort();
+
+	let mut split_dfs: Vec<Option<DataFrame>> = vec![None; ratios.len()];
+	let mut split_rows = vec![0; ratios.len()];
+
+	for (name, category_rows) in &category_counts {
+		let category_df = df.clone().filter(category.clone().eq(lit(name.as_str())))?;
+		let shuffled = shuffle(category_df, ShuffleOptions { seed: Some(seed.wrapping_add(name.len() as u64)) }).await?;
+
+		for (i, (category_split, rows)) in cut(&shuffled, *category_rows, ratios)?.into_iter().enumerate() {
+			if rows == 0 {
+				continue;
+			}
+			split_dfs[i] = Some(match split_dfs[i].take() {
+				None => category_split,
+				Some(existing) => existing.union(category_split)?,
+			});
+			split_rows[i] += rows;
+		}
+	}
+
+	let empty_df = df.limit(0, Some(0))?;
+	Ok(split_dfs.into_iter()
+		.zip(split_rows)
+		.map(|(split_df, rows)| (split_df.unwrap_or_else(|| empty_df.clone()), rows))
+		.collect())
+}
+
+/// Cuts consecutive slices of `total_rows` rows by ratio; the last slice takes the remainder.
+fn cut(df: &DataFrame, total_rows: usize, ratios: &[f64]) -> NailResult<Vec<(DataFrame, usize)>> {
+	let mut splits = Vec::with_capacity(ratios.len());
+	let mut current_offset = 0;
+
+	for (i, ratio) in ratios.iter().enumerate() {
+		let split_size = if i == ratios.len() - 1 {
+			total_rows.saturating_sub(current_offset)
+		} else {
+			((total_rows as f64 * ratio).round() as usize).min(total_rows.saturating_sub(current_offset))
+		};
+
+		splits.push((df.clone().limit(current_offset, Some(split_size))?, split_size));
+		current_offset += split_size;
+	}
+
+	Ok(splits)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'a'), (2, 'a'), (3, 'a'), (4, 'a'), (5, 'b'), (6, 'b'), (7, 'b'), (8, 'b'), (9, 'b'), (10, 'b')) AS t(id, kind)"
+		).await.unwrap()
+	}
+
+	async fn ids(df: DataFrame) -> Vec<i64> {
+		let mut ids: Vec<i64> = df.collect().await.unwrap().iter()
+			.flat_map(|b| b.column(0).as_any().downcast_ref::<Int64Array>().unwrap().values().to_vec())
+			.collect();
+		ids.sort();
+		ids
+	}
+
+	#[tokio::test]
+	async fn test_split_partitions_rows() {
+		let options = SplitOptions { ratios: vec![0.7, 0.3], seed: Some(7), ..Default::default() };
+		let splits = split(sample_df().await, options).await.unwrap();
+		assert_eq!(splits.iter().map(|(_, rows)| *rows).collect::<Vec<_>>(), vec![7, 3]);
+
+		let mut all = ids(splits[0].0.clone()).await;
+		all.extend(ids(splits[1].0.clone()).await);
+		all.sort();
+		assert_eq!(all, (1..=10).collect::<Vec<_>>());
+	}
+
+	#[tokio::test]
+	async fn test_split_without_seed_is_disjoint() {
+		let options = SplitOptions { ratios: vec![0.5, 0.5], ..Default::default() };
+		let splits = split(sample_df().await, options).await.unwrap();
+		let mut all = ids(splits[0].0.clone()).await;
+		all.extend(ids(splits[1].0.clone()).await);
+		all.sort();
+		assert_eq!(all, (1..=10).collect::<Vec<_>>());
+	}
+
+	#[tokio::test]
+	async fn test_stratified_split_keeps_shares() {
+		let options = SplitOptions {
+			ratios: vec![0.5, 0.5],
+			stratify_by: Some("kind".to_string()),
+			seed: Some(1),
+		};
+		let splits = split(sample_df().await, options).await.unwrap();
+		for (split_df, rows) in splits {
+			assert_eq!(rows, 5);
+			let first = split_df.filter(col("kind").eq(lit("a"))).unwrap().count().await.unwrap();
+			assert_eq!(first, 2);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_split_rejects_bad_ratios() {
+		let options = SplitOptions { ratios: vec![0.5, 0.6], ..Default::default() };
+		assert!(split(sample_df().await, options).await.is_err());
+	}
+}
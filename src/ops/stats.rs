@@ -0,0 +1,155 @@
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::ScalarValue;
+use datafusion::functions_aggregate::expr_fn::{approx_percentile_cont, avg, count, count_distinct, max, min, stddev, var_pop};
+use datafusion::prelude::*;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Debug, Clone, Default)]
+pub enum StatsType {
+	#[default]
+	Basic,
+	Exhaustive,
+	Hypothesis,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StatsOptions {
+	/// Column selector; all columns when `None`.
+	pub columns: Option<String>,
+	pub stats_type: StatsType,
+}
+
+/// Computes one row of statistics per numeric or string column; other columns are skipped.
+pub async fn stats(df: DataFrame, options: StatsOptions) -> NailResult<DataFrame> {
+	let target_columns = match &options.columns {
+		Some(spec) => select_columns_by_pattern(df.schema().clone().into(), spec)?,
+		None => df.schema().fields().iter().map(|f| f.name().clone()).collect(),
+	};
+
+	let exhaustive = match options.stats_type {
+		StatsType::Basic => false,
+		StatsType::Exhaustive => true,
+		StatsType::Hypothesis => {
+			return Err(NailError::Statistics("Hypothesis tests not yet implemented".to_string()));
+		},
+	};
+
+	let mut stats_rows = Vec::new();
+	for column in &target_columns {
+		let field = df.schema().field_with_name(None, column)
+			.map_err(|_| NailError::ColumnNotFound(column.clone()))?;
+		let numeric = match field.data_type() {
+			DataType::Int64 | DataType::Float64 | DataType::Int32 | DataType::Float32 => true,
+			DataType::Utf8 => false,
+			_ => continue,
+		};
+		stats_rows.push(column_stats(&df, column, numeric, exhaustive)?);
+	}
+
+	let mut iter = stats_rows.into_iter();
+	let mut combined = iter.next()
+		.ok_or_else(|| NailError::Statistics("No suitable columns for statistics".to_string()))?;
+	for row in iter {
+		combined = combined.union(row)?;
+	}
+
+	Ok(combined)
+}
+
+/// Aggregates `column` into a single statistics row; numeric measures are null for strings.
+fn column_stats(df: &DataFrame, column: &str, numeric: bool, exhaustive: bool) -> NailResult<DataFrame> {
+	let value = ident(column);
+	let mut aggregates = vec![
+		count(value.clone()).alias("count"),
+		count_distinct(value.clone()).alias("num_classes"),
+	];
+	let measures: Vec<(&str, Expr)> = if exhaustive {
+		vec![
+			("mean", avg(value.clone())),
+			("std_dev", stddev(value.clone())),
+			("min_val", min(value.clone())),
+			("q25", approx_percentile_cont(value.clone(), lit(0.25), None)),
+			("median", approx_percentile_cont(value.clone(), lit(0.5), None)),
+			("q75", approx_percentile_cont(value.clone(), lit(0.75), None)),
+			("max_val", max(value.clone())),
+			("variance", var_pop(value)),
+		]
+	} else {
+		vec![
+			("mean", avg(value.clone())),
+			("q25", approx_percentile_cont(value.clone(), lit(0.25), None)),
+			("q50", approx_percentile_cont(value.clone(), lit(0.5), None)),
+			("q75", approx_percentile_cont(value, lit(0.75), None)),
+		]
+	};
+	if numeric {
+		aggregates.extend(measures.iter().map(|(name, expr)| expr.clone().alias(*name)));
+	}
+
+	let mut columns = vec![lit(column).alias("column_name"), ident("count")];
+	for (name, _) in &measures {
+		let measure = if numeric {
+			cast(ident(*name), DataType::Float64)
+		} else {
+			lit(ScalarValue::Float64(None))
+		};
+		columns.push(measure.alias(*name));
+	}
+	columns.push(ident("num_classes"));
+	if exhaustive {
+		columns.push((ident("count") - ident("num_classes")).alias("duplicates"));
+	}
+
+	Ok(df.clone().aggregate(vec![], aggregates)?.select(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::array::{Array, Float64Array, Int64Array, StringArray};
+
+	async fn sample_df() -> DataFrame {
+		SessionContext::new().sql(
+			"SELECT * FROM (VALUES (1, 'a', true), (2, 'b', false), (3, 'b', true), (4, NULL, NULL)) AS t(n, s, flag)"
+		).await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_basic_stats() {
+		let result = stats(sample_df().await, StatsOptions::default()).await.unwrap()
+			.sort(vec![col("column_name").sort(true, false)]).unwrap();
+		let batches = result.collect().await.unwrap();
+		let batch = &batches[0];
+
+		// The boolean column is skipped
+		assert_eq!(batch.num_rows(), 2);
+		let names = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+		assert_eq!(names.value(0), "n");
+		assert_eq!(names.value(1), "s");
+		let counts = batch.column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+		assert_eq!(counts.value(1), 3);
+		let means = batch.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+		assert_eq!(means.value(0), 2.5);
+		assert!(means.is_null(1));
+	}
+
+	#[tokio::test]
+	async fn test_exhaustive_stats() {
+		let options = StatsOptions {
+			columns: Some("s".to_string()),
+			stats_type: StatsType::Exhaustive,
+		};
+		let batches = stats(sample_df().await, options).await.unwrap().collect().await.unwrap();
+		let schema = batches[0].schema();
+		assert_eq!(schema.fields().len(), 12);
+		let duplicates = batches[0].column(11).as_any().downcast_ref::<Int64Array>().unwrap();
+		assert_eq!(duplicates.value(0), 1);
+	}
+
+	#[tokio::test]
+	async fn test_no_suitable_columns() {
+		let options = StatsOptions { columns: Some("flag".to_string()), ..Default::default() };
+		assert!(stats(sample_df().await, options).await.is_err());
+	}
+}
@@ -0,0 +1,414 @@
+use datafusion::arrow::array::{Array, ArrayRef, ListBuilder, StringArray, StringBuilder};
+use datafusion::arrow::compute::cast as cast_array;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::prelude::*;
+use regex::Regex;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+use crate::error::{NailError, NailResult};
+use crate::utils::collect_in_session;
+use crate::utils::io::dataframe_from_batches;
+use crate::utils::column::select_columns_by_pattern;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CaseStyle {
+	Lower,
+	Upper,
+	/// Upper-case the first letter of every word, lower-case the rest.
+	Title,
+}
+
+/// Unicode normalization forms.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnicodeForm {
+	Nfc,
+	Nfd,
+	Nfkc,
+	Nfkd,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum PadSide {
+	#[default]
+	Left,
+	Right,
+	Both,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Padding {
+	/// Minimum length in characters; longer values are left as they are.
+	pub width: usize,
+	pub fill: char,
+	pub side: PadSide,
+}
+
+/// Text transformations. Value operations run in a fixed order: `normalize`,
+/// `strip_accents`, `normalize_whitespace`, `trim`, `replace`, `case`, `pad`;
+/// `extract` and `split` then work on the transformed value.
+#[derive(Debug, Clone, Default)]
+pub struct StringsOptions {
+	/// Column selector; all string columns when `None`.
+	pub columns: Option<String>,
+	pub normalize: Option<UnicodeForm>,
+	pub strip_accents: bool,
+	/// Collapse runs of whitespace, including non-breaking spaces, into one space and trim.
+	pub normalize_whitespace: bool,
+	pub trim: bool,
+	/// `(regex, replacement)` pairs applied in order; replacements may refer to groups as `$1` or `${name}`.
+	pub replace: Vec<(String, String)>,
+	pub case: Option<CaseStyle>,
+	pub pad: Option<Padding>,
+	/// Regex whose capture groups become new columns `<column>_<group name or number>`.
+	pub extract: Option<String>,
+	/// Separator for `split`; without `split_into` the column becomes a list of strings.
+	pub split: Option<String>,
+	/// Split into this many columns `<column>_1..<column>_N`; the last one keeps the remainder.
+	pub split_into: Option<usize>,
+	/// Write results to `<column><suffix>` instead of replacing the column.
+	pub suffix: Option<String>,
+}
+
+/// Compiled form of the options, shared by every value.
+struct Transform {
+	options: StringsOptions,
+	replace: Vec<(Regex, String)>,
+	extract: Option<Regex>,
+}
+
+impl Transform {
+	fn new(options: StringsOptions) -> NailResult<Self> {
+		let replace = options.replace.iter()
+			.map(|(pattern, replacement)| Ok((compile(pattern)?, replacement.clone())))
+			.collect::<NailResult<Vec<_>>>()?;
+		let extract = options.extract.as_deref().map(compile).transpose()?;
+		if options.split.as_deref() == Some("") {
+			return Err(NailError::InvalidArgument("Split separator cannot be empty".to_string()));
+		}
+		if options.split_into == Some(0) {
+			return Err(NailError::InvalidArgument("--split-into must be at least 1".to_string()));
+		}
+		Ok(Self { options, replace, extract })
+	}
+
+	/// Whether the column values themselves change (as opposed to only deriving new columns).
+	fn rewrites_values(&self) -> bool {
+		let o = &self.options;
+		o.normalize.is_some() || o.strip_accents || o.normalize_whitespace || o.trim
+			|| !o.replace.is_empty() || o.case.is_some() || o.pad.is_some()
+	}
+
+	fn splits_to_list(&self) -> bool {
+		self.options.split.is_some() && self.options.split_into.is_none()
+	}
+
+	fn clean(&self, value: &str) -> String {
+		let o = &self.options;
+		let mut text = match o.normalize {
+			Some(UnicodeForm::Nfc) => value.nfc().collect(),
+			Some(UnicodeForm::Nfd) => value.nfd().collect(),
+			Some(UnicodeForm::Nfkc) => value.nfkc().collect(),
+			Some(UnicodeForm::Nfkd) => value.nfkd().collect(),
+			None => value.to_string(),
+		};
+		if o.strip_accents {
+			text = strip_accents(&text);
+		}
+		if o.normalize_whitespace {
+			text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+		}
+		if o.trim {
+			text = text.trim().to_string();
+		}
+		for (pattern, replacement) in &self.replace {
+			text = pattern.replace_all(&text, replacement.as_str()).into_owned();
+		}
+		text = match o.case {
+			Some(CaseStyle::Lower) => text.to_lowercase(),
+			Some(CaseStyle::Upper) => text.to_uppercase(),
+			Some(CaseStyle::Title) => title_case(&text),
+			None => text,
+		};
+		if let Some(padding) = &o.pad {
+			text = pad(&text, padding);
+		}
+		text
+	}
+
+	/// Names of the columns derived from `column` by `extract` and `split_into`.
+	fn derived_columns(&self, column: &str) -> Vec<String> {
+		let mut names = Vec::new();
+		if let Some(pattern) = &self.extract {
+			if pattern.captures_len() == 1 {
+				names.push(format!("{}_match", column));
+			}
+			for (index, name) in pattern.capture_names().enumerate().skip(1) {
+				names.push(format!("{}_{}", column, name.map(str::to_string).unwrap_or_else(|| index.to_string())));
+			}
+		}
+		if let Some(parts) = self.options.split_into {
+			names.extend((1..=parts).map(|i| format!("{}_{}", column, i)));
+		}
+		names
+	}
+
+	/// Values of the derived columns for one (cleaned) value, in `derived_columns` order.
+	fn derived_values<'a>(&self, value: &'a str) -> Vec<Option<&'a str>> {
+		let mut values = Vec::new();
+		if let Some(pattern) = &self.extract {
+			let captures = pattern.captures(value);
+			let groups = pattern.captures_len();
+			let first = if groups == 1 { 0 } else { 1 };
+			for index in first..groups {
+				values.push(captures.as_ref().and_then(|c| c.get(index)).map(|m| m.as_str()));
+			}
+		}
+		if let (Some(separator), Some(parts)) = (&self.options.split, self.options.split_into) {
+			let mut pieces = value.splitn(parts, separator.as_str());
+			values.extend((0..parts).map(|_| pieces.next()));
+		}
+		values
+	}
+}
+
+fn compile(pattern: &str) -> NailResult<Regex> {
+	Regex::new(pattern)
+		.map_err(|e| NailError::InvalidArgument(format!("Invalid regular expression '{}': {}", pattern, e)))
+}
+
+pub fn strip_accents(text: &str) -> String {
+	text.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+}
+
+pub fn title_case(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut in_word = false;
+	for c in text.chars() {
+		if in_word {
+			result.extend(c.to_lowercase());
+		} else {
+			result.extend(c.to_uppercase());
+		}
+		in_word = c.is_alphanumeric() || c == '\'';
+	}
+	result
+}
+
+pub fn pad(text: &str, padding: &Padding) -> String {
+	let missing = padding.width.saturating_sub(text.chars().count());
+	let (left, right) = match padding.side {
+		PadSide::Left => (missing, 0),
+		PadSide::Right => (0, missing),
+		PadSide::Both => (missing / 2, missing - missing / 2),
+	};
+	let fill = |n: usize| std::iter::repeat_n(padding.fill, n).collect::<String>();
+	format!("{}{}{}", fill(left), text, fill(right))
+}
+
+fn is_string_type(data_type: &DataType) -> bool {
+	matches!(data_type, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View)
+}
+
+/// Applies text transformations to string columns, in place or into suffixed columns.
+/// Columns derived by `extract` and `split_into` are inserted after their source column.
+pub async fn strings(df: DataFrame, options: StringsOptions) -> NailResult<DataFrame> {
+	let transform = Transform::new(options)?;
+	if !transform.rewrites_values() && transform.extract.is_none() && transform.options.split.is_none() {
+		return Err(NailError::InvalidArgument(
+			"No string operation given. Use e.g. --trim, --case lower, --replace PATTERN REPLACEMENT, --extract REGEX or --split SEP".to_string()
+		));
+	}
+
+	let input_schema = df.schema().as_arrow().clone();
+	let selected = match &transform.options.columns {
+		Some(selector) => select_columns_by_pattern(df.schema().clone().into(), selector)?,
+		None => input_schema.fields().iter().map(|f| f.name().clone()).collect(),
+	};
+	let targets: Vec<String> = selected.into_iter()
+		.filter(|name| input_schema.field_with_name(name).map(|f| is_string_type(f.data_type())).unwrap_or(false))
+		.collect();
+	if targets.is_empty() {
+		return Err(NailError::InvalidArgument(
+			"No string columns selected. Convert other columns first with 'nail cast -c column=utf8'".to_string()
+		));
+	}
+
+	let schema = Arc::new(output_schema(&input_schema, &targets, &transform)?);
+	let (ctx, batches) = collect_in_session(df).await?;
+	let mut output = Vec::with_capacity(batches.len());
+	for batch in &batches {
+		let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+		for (field, column) in input_schema.fields().iter().zip(batch.columns()) {
+			if targets.contains(field.name()) {
+				columns.extend(transform_column(column, &transform)?);
+			} else {
+				columns.push(column.clone());
+			}
+		}
+		output.push(RecordBatch::try_new(schema.clone(), columns)?);
+	}
+
+	dataframe_from_batches(&ctx, schema, output)
+}
+
+fn output_schema(input: &Schema, targets: &[String], transform: &Transform) -> NailResult<Schema> {
+	let value_type = if transform.splits_to_list() {
+		DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+	} else {
+		DataType::Utf8
+	};
+	let changes_column = transform.rewrites_values() || transform.splits_to_list();
+
+	let mut fields = Vec::new();
+	for field in input.fields() {
+		if !targets.contains(field.name()) {
+			fields.push(field.as_ref().clone());
+			continue;
+		}
+		match &transform.options.suffix {
+			Some(suffix) if changes_column => {
+				fields.push(field.as_ref().clone());
+				fields.push(Field::new(format!("{}{}", field.name(), suffix), value_type.clone(), true));
+			},
+			_ if changes_column => fields.push(Field::new(field.name(), value_type.clone(), true)),
+			_ => fields.push(field.as_ref().clone()),
+		}
+		fields.extend(transform.derived_columns(field.name()).into_iter().map(|name| Field::new(name, DataType::Utf8, true)));
+	}
+
+	for (index, field) in fields.iter().enumerate() {
+		if fields[..index].iter().any(|f| f.name() == field.name()) {
+			return Err(NailError::InvalidArgument(match input.field_with_name(field.name()) {
+				Ok(_) => format!("Column '{}' already exists. Use --suffix or rename it first", field.name()),
+				Err(_) => format!("Column '{}' would be created twice", field.name()),
+			}));
+		}
+	}
+	Ok(Schema::new(fields))
+}
+
+/// Output arrays for one source column: the source or its replacement, an optional
+/// suffixed copy, then the derived columns.
+fn transform_column(column: &ArrayRef, transform: &Transform) -> NailResult<Vec<ArrayRef>> {
+	let utf8 = cast_array(column, &DataType::Utf8)?;
+	let source = utf8.as_any().downcast_ref::<StringArray>()
+		.ok_or_else(|| NailError::InvalidArgument("Expected a string column".to_string()))?;
+
+	let cleaned: Vec<Option<String>> = source.iter()
+		.map(|value| value.map(|v| if transform.rewrites_values() { transform.clean(v) } else { v.to_string() }))
+		.collect();
+
+	let mut arrays = Vec::new();
+	let changes_column = transform.rewrites_values() || transform.splits_to_list();
+	if !changes_column || transform.options.suffix.is_some() {
+		arrays.push(column.clone());
+	}
+	if changes_column {
+		let values: ArrayRef = match (&transform.options.split, transform.splits_to_list()) {
+			(Some(separator), true) => {
+				let mut builder = ListBuilder::new(StringBuilder::new());
+				for value in &cleaned {
+					match value {
+						Some(v) => {
+							for piece in v.split(separator.as_str()) {
+								builder.values().append_value(piece);
+							}
+							builder.append(true);
+						},
+						None => builder.append(false),
+					}
+				}
+				Arc::new(builder.finish())
+			},
+			_ => Arc::new(StringArray::from(cleaned.clone())),
+		};
+		arrays.push(values);
+	}
+
+	let derived = transform.derived_columns("").len();
+	if derived > 0 {
+		let mut builders: Vec<StringBuilder> = (0..derived).map(|_| StringBuilder::new()).collect();
+		for value in &cleaned {
+			match value {
+				Some(v) => {
+					for (builder, part) in builders.iter_mut().zip(transform.derived_values(v)) {
+						builder.append_option(part);
+					}
+				},
+				None => builders.iter_mut().for_each(|b| b.append_null()),
+			}
+		}
+		arrays.extend(builders.into_iter().map(|mut b| Arc::new(b.finish()) as ArrayRef));
+	}
+	Ok(arrays)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn transform(options: StringsOptions) -> Transform {
+		Transform::new(options).unwrap()
+	}
+
+	#[test]
+	fn test_clean_pipeline() {
+		let t = transform(StringsOptions {
+			strip_accents: true,
+			normalize_whitespace: true,
+			case: Some(CaseStyle::Title),
+			..Default::default()
+		});
+		assert_eq!(t.clean("  josé\u{a0}  GARCÍA "), "Jose Garcia");
+
+		let t = transform(StringsOptions {
+			trim: true,
+			replace: vec![(r"(\d{3})(\d{4})".to_string(), "$1-$2".to_string())],
+			..Default::default()
+		});
+		assert_eq!(t.clean(" 5551234 "), "555-1234");
+
+		let t = transform(StringsOptions { normalize: Some(UnicodeForm::Nfkc), ..Default::default() });
+		assert_eq!(t.clean("ﬁle①"), "file1");
+	}
+
+	#[test]
+	fn test_title_case() {
+		assert_eq!(title_case("hello wORLD"), "Hello World");
+		assert_eq!(title_case("o'neil-smith"), "O'neil-Smith");
+	}
+
+	#[test]
+	fn test_pad() {
+		let zero = |side| Padding { width: 5, fill: '0', side };
+		assert_eq!(pad("42", &zero(PadSide::Left)), "00042");
+		assert_eq!(pad("42", &zero(PadSide::Right)), "42000");
+		assert_eq!(pad("42", &zero(PadSide::Both)), "04200");
+		assert_eq!(pad("123456", &zero(PadSide::Left)), "123456");
+	}
+
+	#[test]
+	fn test_derived_columns() {
+		let t = transform(StringsOptions {
+			extract: Some(r"(?P<year>\d{4})-(?P<month>\d{2})".to_string()),
+			split: Some("-".to_string()),
+			split_into: Some(2),
+			..Default::default()
+		});
+		assert_eq!(t.derived_columns("d"), vec!["d_year", "d_month", "d_1", "d_2"]);
+		assert_eq!(t.derived_values("2024-05-17"), vec![Some("2024"), Some("05"), Some("2024"), Some("05-17")]);
+		assert_eq!(t.derived_values("n/a"), vec![None, None, Some("n/a"), None]);
+
+		let t = transform(StringsOptions { extract: Some(r"\d+".to_string()), ..Default::default() });
+		assert_eq!(t.derived_columns("code"), vec!["code_match"]);
+		assert_eq!(t.derived_values("AB12"), vec![Some("12")]);
+	}
+
+	#[test]
+	fn test_invalid_options() {
+		assert!(Transform::new(StringsOptions { replace: vec![("(".to_string(), "".to_string())], ..Default::default() }).is_err());
+		assert!(Transform::new(StringsOptions { split: Some(String::new()), ..Default::default() }).is_err());
+	}
+}
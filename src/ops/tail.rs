@@ -0,0 +1,30 @@
+use datafusion::prelude::*;
+use crate::error::NailResult;
+
+#[derive(Debug, Clone)]
+pub struct TailOptions {
+	pub number: usize,
+	/// Row count of the input when already known (e.g. from Parquet metadata); counted otherwise.
+	pub total_rows: Option<usize>,
+}
+
+impl Default for TailOptions {
+	fn default() -> Self {
+		Self {
+			number: 5,
+			total_rows: None,
+		}
+	}
+}
+
+/// Keeps the last `number` rows; returns the input unchanged when it has no more rows than that.
+pub async fn tail(df: DataFrame, options: TailOptions) -> NailResult<DataFrame> {
+	let total_rows = match options.total_rows {
+		Some(rows) => rows,
+		None => df.clone().count().await?,
+	};
+	if total_rows <= options.number {
+		return Ok(df);
+	}
+	Ok(df.limit(total_rows - options.number, Some(options.number))?)
+}
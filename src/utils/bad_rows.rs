@@ -0,0 +1,512 @@
+use datafusion::prelude::{DataFrame, SessionContext};
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use arrow::compute::kernels::cast_utils::{Parser, string_to_timestamp_nanos};
+use arrow::datatypes::{Date32Type, Float64Type, Int64Type};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::cli::BadRowPolicy;
+use crate::error::{NailError, NailResult};
+use crate::utils::io::{dataframe_from_batches, infer_csv_schema, open_text_input, CsvDialect, ReadOptions};
+
+/// Same sample size DataFusion uses when inferring a CSV schema.
+pub(crate) const SCHEMA_INFER_MAX_RECORDS: usize = 1000;
+
+/// A row that could not be parsed, kept for reporting or quarantine.
+#[derive(Debug, Clone)]
+pub struct RejectedRow {
+	pub line: u64,
+	pub raw: String,
+	pub error: String,
+}
+
+/// Parses CSV input record by record, dropping rows with a wrong field count, invalid
+/// UTF-8 or values that do not parse as the inferred column type. Rejected rows are
+/// written to the quarantine file as they are found.
+pub async fn read_csv_lenient(
+	ctx: &SessionContext,
+	path: &Path,
+	input: Box<dyn Read + Send>,
+	dialect: &CsvDialect,
+	schema: Option<SchemaRef>,
+	options: &ReadOptions,
+) -> NailResult<DataFrame> {
+	let mut rejects = RejectSink::new(path, options)?;
+	let reopen = || open_text_input(path, options.encoding.as_deref()).map(|(input, _)| input);
+	let (schema, batches) = parse_csv_lenient(input, reopen, dialect, schema, options.session.batch_size, &mut |row| rejects.push(row))?;
+	rejects.finish(options.verbose)?;
+
+	dataframe_from_batches(ctx, schema, batches)
+}
+
+/// Reads a newline-delimited JSON file line by line, dropping lines that are not JSON
+/// objects or do not fit the schema. Rejected lines are written to the quarantine file as
+/// they are found.
+pub async fn read_ndjson_lenient(ctx: &SessionContext, path: &Path, schema: Option<SchemaRef>, options: &ReadOptions) -> NailResult<DataFrame> {
+	let mut rejects = RejectSink::new(path, options)?;
+	let open = || Ok(BufReader::new(File::open(path)?));
+	let (schema, batches) = parse_ndjson_lenient(open()?, open, schema, options.session.batch_size, &mut |row| rejects.push(row))?;
+	rejects.finish(options.verbose)?;
+
+	dataframe_from_batches(ctx, schema, batches)
+}
+
+/// Streams CSV records from `input` into batches of `batch_size` rows, passing rows that do
+/// not fit the schema to `reject`. Without a schema, one is inferred from the leading
+/// well-formed records and the input is read again from `reopen`.
+pub(crate) fn parse_csv_lenient<R: Read>(
+	input: R,
+	reopen: impl FnOnce() -> NailResult<R>,
+	dialect: &CsvDialect,
+	schema: Option<SchemaRef>,
+	batch_size: usize,
+	reject: &mut dyn FnMut(RejectedRow) -> NailResult<()>,
+) -> NailResult<(SchemaRef, Vec<RecordBatch>)> {
+	let (schema, input) = match schema {
+		Some(schema) => (schema, input),
+		None => (Arc::new(infer_lenient_schema(input, dialect)?), reopen()?),
+	};
+
+	let mut decoder = arrow::csv::ReaderBuilder::new(schema.clone())
+		.with_header(false)
+		.with_delimiter(dialect.delimiter)
+		.with_quote(dialect.quote)
+		.with_batch_size(batch_size)
+		.build_decoder();
+	let mut batches = Vec::new();
+	let mut pending = Vec::new();
+	let mut pending_rows = 0;
+
+	let mut reader = record_reader(input, dialect);
+	let mut record = csv::ByteRecord::new();
+	let mut raw = Vec::new();
+	let mut header_seen = !dialect.has_header;
+	loop {
+		match reader.read_byte_record(&mut record) {
+			Ok(false) => break,
+			Ok(true) => {
+				if !header_seen {
+					header_seen = true;
+					continue;
+				}
+				raw.clear();
+				write_record(&mut raw, &record, dialect);
+				match check_record(&schema, &record) {
+					Ok(()) => {
+						pending.extend_from_slice(&raw);
+						pending.push(b'\n');
+						pending_rows += 1;
+						if pending_rows == batch_size {
+							decode_rows(&mut decoder, &pending, &mut batches)?;
+							pending.clear();
+							pending_rows = 0;
+						}
+					},
+					Err(error) => reject(RejectedRow {
+						line: record.position().map_or(0, |p| p.line()),
+						raw: raw_text(&raw),
+						error,
+					})?,
+				}
+			},
+			Err(e) => {
+				let line = e.position().map_or(reader.position().line(), |p| p.line());
+				let error = e.to_string();
+				if let csv::ErrorKind::Io(e) = e.into_kind() {
+					return Err(e.into());
+				}
+				reject(RejectedRow { line, raw: String::new(), error })?;
+			},
+		}
+	}
+	if !header_seen {
+		return Err(NailError::InvalidArgument("CSV file is empty".to_string()));
+	}
+	decode_rows(&mut decoder, &pending, &mut batches)?;
+
+	Ok((schema, batches))
+}
+
+/// Infers the schema from the header and the leading well-formed records, as DataFusion would.
+fn infer_lenient_schema(input: impl Read, dialect: &CsvDialect) -> NailResult<Schema> {
+	let mut reader = record_reader(input, dialect);
+	let mut record = csv::ByteRecord::new();
+	let mut sample = Vec::new();
+	// Field count of the header, or of the first record without one
+	let mut expected = None;
+	let mut rows = 0;
+	while rows < SCHEMA_INFER_MAX_RECORDS {
+		match reader.read_byte_record(&mut record) {
+			Ok(false) => break,
+			Ok(true) => {},
+			Err(e) => match e.into_kind() {
+				csv::ErrorKind::Io(e) => return Err(e.into()),
+				_ => continue,
+			},
+		}
+		let is_header = expected.is_none() && dialect.has_header;
+		let width = *expected.get_or_insert(record.len());
+		if !is_header && (record.len() != width || record.iter().any(|f| std::str::from_utf8(f).is_err())) {
+			continue;
+		}
+		write_record(&mut sample, &record, dialect);
+		sample.push(b'\n');
+		if !is_header {
+			rows += 1;
+		}
+	}
+	if expected.is_none() {
+		return Err(NailError::InvalidArgument("CSV file is empty".to_string()));
+	}
+
+	infer_csv_schema(sample.as_slice(), dialect)
+}
+
+fn record_reader<R: Read>(input: R, dialect: &CsvDialect) -> csv::Reader<R> {
+	csv::ReaderBuilder::new()
+		.has_headers(false)
+		.delimiter(dialect.delimiter)
+		.quote(dialect.quote)
+		.flexible(true)
+		.from_reader(input)
+}
+
+/// Writes a record back as one CSV line without the line ending, quoting fields as needed.
+fn write_record(out: &mut Vec<u8>, record: &csv::ByteRecord, dialect: &CsvDialect) {
+	let quote = dialect.quote;
+	for (index, field) in record.iter().enumerate() {
+		if index > 0 {
+			out.push(dialect.delimiter);
+		}
+		// A lone empty field would otherwise be an empty line, which readers skip
+		let needs_quotes = (record.len() == 1 && field.is_empty())
+			|| field.iter().any(|&b| b == dialect.delimiter || b == quote || b == b'\n' || b == b'\r');
+		if !needs_quotes {
+			out.extend_from_slice(field);
+			continue;
+		}
+		out.push(quote);
+		for &b in field {
+			if b == quote {
+				out.push(quote);
+			}
+			out.push(b);
+		}
+		out.push(quote);
+	}
+}
+
+fn decode_rows(decoder: &mut arrow::csv::reader::Decoder, mut rows: &[u8], batches: &mut Vec<RecordBatch>) -> NailResult<()> {
+	while !rows.is_empty() {
+		let consumed = decoder.decode(rows)?;
+		rows = &rows[consumed..];
+		if let Some(batch) = decoder.flush()? {
+			batches.push(batch);
+		}
+	}
+	Ok(())
+}
+
+/// Streams NDJSON lines from `input` into batches of `batch_size` rows, passing lines that
+/// are not JSON objects or do not fit the schema to `reject`. Without a schema, one is
+/// inferred from the leading objects and the input is read again from `reopen`.
+pub(crate) fn parse_ndjson_lenient<R: BufRead>(
+	input: R,
+	reopen: impl FnOnce() -> NailResult<R>,
+	schema: Option<SchemaRef>,
+	batch_size: usize,
+	reject: &mut dyn FnMut(RejectedRow) -> NailResult<()>,
+) -> NailResult<(SchemaRef, Vec<RecordBatch>)> {
+	let (schema, mut input) = match schema {
+		Some(schema) => (schema, input),
+		None => (Arc::new(infer_ndjson_schema(input)?), reopen()?),
+	};
+
+	let mut batches = Vec::new();
+	let mut pending: Vec<(u64, Vec<u8>)> = Vec::new();
+	let mut raw = Vec::new();
+	let mut line = 0;
+	while read_line(&mut input, &mut raw)? {
+		line += 1;
+		if raw.iter().all(|b| b.is_ascii_whitespace()) {
+			continue;
+		}
+		match parse_json_object(&raw) {
+			Ok(_) => {
+				pending.push((line, raw.clone()));
+				if pending.len() == batch_size {
+					decode_json_rows(&schema, &pending, &mut batches, reject)?;
+					pending.clear();
+				}
+			},
+			Err(error) => reject(RejectedRow { line, raw: raw_text(&raw), error })?,
+		}
+	}
+	decode_json_rows(&schema, &pending, &mut batches, reject)?;
+
+	Ok((schema, batches))
+}
+
+/// Infers the schema from the leading JSON objects, skipping lines that are not objects.
+fn infer_ndjson_schema(mut input: impl BufRead) -> NailResult<Schema> {
+	let mut values = Vec::new();
+	let mut raw = Vec::new();
+	while values.len() < SCHEMA_INFER_MAX_RECORDS && read_line(&mut input, &mut raw)? {
+		if let Ok(value) = parse_json_object(&raw) {
+			values.push(value);
+		}
+	}
+	Ok(arrow::json::reader::infer_json_schema_from_iterator(values.into_iter().map(Ok))?)
+}
+
+/// Reads the next line into `raw`, returning false at the end of the input.
+fn read_line(input: &mut impl BufRead, raw: &mut Vec<u8>) -> NailResult<bool> {
+	raw.clear();
+	Ok(input.read_until(b'\n', raw)? > 0)
+}
+
+fn parse_json_object(raw: &[u8]) -> Result<serde_json::Value, String> {
+	match serde_json::from_slice::<serde_json::Value>(raw) {
+		Ok(value) if value.is_object() => Ok(value),
+		Ok(_) => Err("expected a JSON object".to_string()),
+		Err(e) => Err(e.to_string()),
+	}
+}
+
+/// Decodes buffered lines as one batch. If the batch fails, e.g. on a value that does not
+/// fit its column type, the lines are decoded one at a time and only the failing ones rejected.
+fn decode_json_rows(
+	schema: &SchemaRef,
+	rows: &[(u64, Vec<u8>)],
+	batches: &mut Vec<RecordBatch>,
+	reject: &mut dyn FnMut(RejectedRow) -> NailResult<()>,
+) -> NailResult<()> {
+	if rows.is_empty() {
+		return Ok(());
+	}
+	if let Ok(batch) = decode_json(schema, rows.iter().map(|(_, raw)| raw.as_slice())) {
+		batches.extend(batch);
+		return Ok(());
+	}
+
+	let mut kept = Vec::new();
+	for (line, raw) in rows {
+		match decode_json(schema, std::iter::once(raw.as_slice())) {
+			Ok(batch) => kept.extend(batch),
+			Err(e) => reject(RejectedRow { line: *line, raw: raw_text(raw), error: e.to_string() })?,
+		}
+	}
+	if !kept.is_empty() {
+		batches.push(arrow::compute::concat_batches(schema, &kept)?);
+	}
+	Ok(())
+}
+
+fn decode_json<'a>(schema: &SchemaRef, rows: impl ExactSizeIterator<Item = &'a [u8]>) -> NailResult<Option<RecordBatch>> {
+	let mut decoder = arrow::json::ReaderBuilder::new(schema.clone())
+		.with_batch_size(rows.len().max(1))
+		.build_decoder()?;
+	for raw in rows {
+		decoder.decode(raw)?;
+		// Separates a last line that has no line ending from the next one
+		decoder.decode(b"\n")?;
+	}
+	Ok(decoder.flush()?)
+}
+
+/// Checks the field count, UTF-8 and that every value parses as its column type.
+fn check_record(schema: &Schema, record: &csv::ByteRecord) -> Result<(), String> {
+	if record.len() != schema.fields().len() {
+		return Err(format!("expected {} fields, found {}", schema.fields().len(), record.len()));
+	}
+	for (field, value) in schema.fields().iter().zip(record.iter()) {
+		let value = std::str::from_utf8(value).map_err(|e| format!("invalid UTF-8: {}", e))?;
+		// Empty values are read as nulls
+		if value.is_empty() {
+			continue;
+		}
+
+		if !value_matches_type(value, field.data_type()) {
+			return Err(format!(
+				"column '{}': cannot parse '{}' as {:?}",
+				field.name(), value, field.data_type()
+			));
+		}
+	}
+	Ok(())
+}
+
+/// Whether a CSV value parses as the given type using Arrow's CSV parsing rules.
+pub(crate) fn value_matches_type(value: &str, data_type: &DataType) -> bool {
+	match data_type {
+		DataType::Int64 => Int64Type::parse(value).is_some(),
+		DataType::Float64 => Float64Type::parse(value).is_some(),
+		DataType::Boolean => value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false"),
+		DataType::Date32 => Date32Type::parse(value).is_some(),
+		DataType::Timestamp(TimeUnit::Nanosecond, _) |
+		DataType::Timestamp(TimeUnit::Microsecond, _) |
+		DataType::Timestamp(TimeUnit::Millisecond, _) |
+		DataType::Timestamp(TimeUnit::Second, _) => string_to_timestamp_nanos(value).is_ok(),
+		_ => true,
+	}
+}
+
+/// Handles rejected rows according to the bad row policy, writing quarantined rows as they come.
+struct RejectSink {
+	policy: BadRowPolicy,
+	path: PathBuf,
+	writer: Option<csv::Writer<File>>,
+	count: usize,
+}
+
+impl RejectSink {
+	fn new(input: &Path, options: &ReadOptions) -> NailResult<Self> {
+		let path = options.rejects_file.clone().unwrap_or_else(|| default_rejects_path(input));
+		let writer = match options.on_bad_rows {
+			BadRowPolicy::Quarantine => {
+				let mut writer = csv::Writer::from_path(&path).map_err(|e| rejects_error(&path, e))?;
+				writer.write_record(["line", "error", "raw"]).map_err(|e| rejects_error(&path, e))?;
+				Some(writer)
+			},
+			_ => None,
+		};
+		Ok(Self { policy: options.on_bad_rows.clone(), path, writer, count: 0 })
+	}
+
+	fn push(&mut self, row: RejectedRow) -> NailResult<()> {
+		self.count += 1;
+		match (&self.policy, &mut self.writer) {
+			(BadRowPolicy::Fail, _) => Err(NailError::InvalidArgument(format!(
+				"Malformed row at line {}: {}", row.line, row.error
+			))),
+			(_, Some(writer)) => writer
+				.write_record([row.line.to_string().as_str(), row.error.as_str(), row.raw.as_str()])
+				.map_err(|e| rejects_error(&self.path, e)),
+			_ => Ok(()),
+		}
+	}
+
+	fn finish(mut self, verbose: bool) -> NailResult<()> {
+		if let Some(writer) = &mut self.writer {
+			writer.flush()?;
+		}
+		if verbose {
+			match self.policy {
+				BadRowPolicy::Skip => eprintln!("Skipped {} malformed rows", self.count),
+				BadRowPolicy::Quarantine => eprintln!("Quarantined {} malformed rows to {}", self.count, self.path.display()),
+				BadRowPolicy::Fail => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+fn rejects_error(path: &Path, e: csv::Error) -> NailError {
+	NailError::InvalidArgument(format!("Cannot write rejects file {}: {}", path.display(), e))
+}
+
+pub fn default_rejects_path(input: &Path) -> PathBuf {
+	input.with_extension("rejects.csv")
+}
+
+fn raw_text(raw: &[u8]) -> String {
+	String::from_utf8_lossy(raw).trim_end_matches(['\r', '\n']).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn comma_dialect() -> CsvDialect {
+		CsvDialect { delimiter: b',', quote: b'"', has_header: true }
+	}
+
+	fn parse_csv(data: &[u8], batch_size: usize) -> (SchemaRef, Vec<RecordBatch>, Vec<RejectedRow>) {
+		let mut rejected = Vec::new();
+		let (schema, batches) = parse_csv_lenient(data, || Ok(data), &comma_dialect(), None, batch_size, &mut |row| {
+			rejected.push(row);
+			Ok(())
+		}).unwrap();
+		(schema, batches, rejected)
+	}
+
+	#[test]
+	fn test_csv_rejects_wrong_field_count() {
+		let data = b"id,name\n1,Alice\n2,Bob,extra\n3,Carol\n";
+		let (_, batches, rejected) = parse_csv(data, 8192);
+
+		let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+		assert_eq!(rows, 2);
+		assert_eq!(rejected.len(), 1);
+		assert_eq!(rejected[0].line, 3);
+		assert_eq!(rejected[0].raw, "2,Bob,extra");
+		assert!(rejected[0].error.contains("expected 2 fields"));
+	}
+
+	#[test]
+	fn test_csv_rejects_type_mismatch() {
+		let mut data = String::from("id,value\n");
+		for i in 0..SCHEMA_INFER_MAX_RECORDS {
+			data.push_str(&format!("{},{}\n", i, i * 2));
+		}
+		data.push_str("x,abc\n");
+
+		let (schema, batches, rejected) = parse_csv(data.as_bytes(), 300);
+
+		assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+		let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+		assert_eq!(rows, SCHEMA_INFER_MAX_RECORDS);
+		assert_eq!(batches.len(), 4);
+		assert_eq!(rejected.len(), 1);
+		assert!(rejected[0].error.contains("column 'id'"));
+	}
+
+	#[test]
+	fn test_csv_keeps_quoted_fields() {
+		let data = b"id,note\n1,\"a,b\"\n2,\"say \"\"hi\"\"\nthere\"\n";
+		let (_, batches, rejected) = parse_csv(data, 8192);
+
+		assert!(rejected.is_empty());
+		let notes = batches[0].column(1).as_any().downcast_ref::<datafusion::arrow::array::StringArray>().unwrap();
+		assert_eq!(notes.value(0), "a,b");
+		assert_eq!(notes.value(1), "say \"hi\"\nthere");
+	}
+
+	fn parse_ndjson(data: &[u8], schema: Option<SchemaRef>, batch_size: usize) -> (SchemaRef, Vec<RecordBatch>, Vec<RejectedRow>) {
+		let mut rejected = Vec::new();
+		let (schema, batches) = parse_ndjson_lenient(data, || Ok(data), schema, batch_size, &mut |row| {
+			rejected.push(row);
+			Ok(())
+		}).unwrap();
+		(schema, batches, rejected)
+	}
+
+	#[test]
+	fn test_ndjson_rejects_invalid_lines() {
+		let data = b"{\"a\": 1}\nnot json\n[1, 2]\n\n{\"a\": 2}";
+		let (_, batches, rejected) = parse_ndjson(data, None, 8192);
+
+		let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+		assert_eq!(rows, 2);
+		assert_eq!(rejected.len(), 2);
+		assert_eq!(rejected[0].line, 2);
+		assert_eq!(rejected[1].line, 3);
+		assert_eq!(rejected[1].error, "expected a JSON object");
+	}
+
+	#[test]
+	fn test_ndjson_rejects_type_mismatch() {
+		let schema = Arc::new(Schema::new(vec![datafusion::arrow::datatypes::Field::new("a", DataType::Int64, true)]));
+		let data = b"{\"a\": 1}\n{\"a\": \"x\"}\n{\"a\": 3}\n{\"a\": 4}\n{\"a\": 5}\n";
+		let (_, batches, rejected) = parse_ndjson(data, Some(schema), 2);
+
+		let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+		assert_eq!(rows, 4);
+		assert_eq!(batches.len(), 3);
+		assert_eq!(rejected.len(), 1);
+		assert_eq!(rejected[0].line, 2);
+		assert_eq!(rejected[0].raw, "{\"a\": \"x\"}");
+	}
+}
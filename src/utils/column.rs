@@ -1,28 +1,485 @@
+//! Column selectors shared by every command that takes column names.
+//!
+//! A selector list is comma-separated; each entry is one of
+//! - `name` - exact name; falls back to a case-insensitive match when unambiguous
+//! - `"name"` - quoted name, matched exactly; may contain commas (`""` escapes a quote)
+//! - `/regex/` or any entry with regex syntax (`^`, `$`, `.*`, `[`, `(`, `|`, `+`, `\`)
+//! - `glob*` - shell-style wildcards `*` and `?`, matching the whole name
+//! - `first:last` - inclusive range in schema order; either side may be omitted
+//! - `type:numeric` - columns of a type class (see [`TYPE_CLASSES`])
+//! - `!selector` - exclusion; a list of only exclusions starts from all columns
+//!
+//! An entry that is exactly the name of a column always selects that column. Regex and
+//! glob entries are case-sensitive and retry case-insensitively when nothing matches.
+
+use datafusion::arrow::datatypes::{DataType, Field};
 use datafusion::common::DFSchemaRef;
+use regex::Regex;
 use crate::error::{NailError, NailResult};
 
+/// Type classes accepted by `type:` selectors.
+pub const TYPE_CLASSES: &[&str] = &["numeric", "integer", "float", "string", "boolean", "date", "timestamp", "temporal", "binary", "nested"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name { name: String, quoted: bool },
+    Regex(String),
+    Glob(String),
+    Range(Option<Box<Selector>>, Option<Box<Selector>>),
+    Type(String),
+}
+
+/// Resolves a single column name, quoted or not, to the name in the schema.
 pub fn resolve_column_name(
     schema: &DFSchemaRef,
     column_input: &str,
 ) -> NailResult<String> {
-    // Strip quotes from column name if present
-    let clean_column = if column_input.starts_with('"') && column_input.ends_with('"') && column_input.len() > 1 {
-        &column_input[1..column_input.len()-1]
-    } else {
-        column_input
+    let names = column_names(schema);
+    let (name, quoted) = match unquote(column_input.trim()) {
+        Some(name) => (name, true),
+        None => (column_input.trim().to_string(), false),
     };
-    
-    schema.fields().iter()
-        .find(|f| f.name().to_lowercase() == clean_column.to_lowercase())
-        .map(|f| f.name().clone())
-        .ok_or_else(|| {
-            let available_cols: Vec<String> = schema.fields().iter()
-                .map(|f| f.name().clone())
-                .collect();
-            NailError::ColumnNotFound(format!(
-                "Column '{}' not found. Available columns: {:?}",
-                clean_column, available_cols
-            ))
+
+    match match_name(&names, &name, quoted)? {
+        Some(index) => Ok(names[index].clone()),
+        None => Err(column_not_found(&name, &names)),
+    }
+}
+
+/// Resolves a selector list (see the module docs) to column names in selection order.
+pub fn select_columns_by_pattern(schema: DFSchemaRef, pattern: &str) -> NailResult<Vec<String>> {
+    let names = column_names(&schema);
+    let fields: Vec<&Field> = schema.fields().iter().map(|f| f.as_ref()).collect();
+
+    let entries = split_selectors(pattern);
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut included: Vec<usize> = Vec::new();
+    let mut excluded: Vec<usize> = Vec::new();
+    let mut not_found: Vec<String> = Vec::new();
+    let only_exclusions = entries.iter().all(|e| e.starts_with('!') && !names.contains(e));
+
+    for entry in &entries {
+        let (negated, body) = match entry.strip_prefix('!') {
+            Some(rest) if !names.contains(entry) => (true, rest.trim()),
+            _ => (false, entry.as_str()),
+        };
+
+        let matched = match parse_selector(body, &names)? {
+            Some(selector) => match_selector(&selector, &names, &fields)?,
+            None => Vec::new(),
+        };
+        if matched.is_empty() {
+            not_found.push(unquoted_name(body));
+            continue;
+        }
+
+        let target = if negated { &mut excluded } else { &mut included };
+        for index in matched {
+            if !target.contains(&index) {
+                target.push(index);
+            }
+        }
+    }
+
+    if !not_found.is_empty() {
+        let missing: Vec<&str> = not_found.iter().map(String::as_str).collect();
+        return Err(columns_not_found(&missing, &names));
+    }
+
+    if only_exclusions {
+        included = (0..names.len()).collect();
+    }
+    let selected: Vec<String> = included.into_iter()
+        .filter(|index| !excluded.contains(index))
+        .map(|index| names[index].clone())
+        .collect();
+
+    if selected.is_empty() {
+        return Err(NailError::ColumnNotFound(format!("No columns matched pattern: {}", pattern)));
+    }
+    Ok(selected)
+}
+
+fn column_names(schema: &DFSchemaRef) -> Vec<String> {
+    schema.fields().iter().map(|f| f.name().clone()).collect()
+}
+
+/// Splits on commas that are not inside quotes or regex brackets/braces/parentheses.
+pub fn split_selectors(spec: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut depth = 0usize;
+
+    for c in spec.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '(' | '[' | '{' if !in_quotes => depth += 1,
+            ')' | ']' | '}' if !in_quotes => depth = depth.saturating_sub(1),
+            ',' if !in_quotes && depth == 0 => {
+                entries.push(std::mem::take(&mut current));
+                continue;
+            },
+            _ => {},
+        }
+        current.push(c);
+    }
+    entries.push(current);
+
+    entries.into_iter()
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// Returns the name inside double quotes, with `""` unescaped.
+fn unquote(entry: &str) -> Option<String> {
+    let inner = entry.strip_prefix('"')?.strip_suffix('"')?;
+    Some(inner.replace("\"\"", "\""))
+}
+
+/// The name an entry refers to literally: quotes removed if present, otherwise as written.
+pub fn unquoted_name(entry: &str) -> String {
+    unquote(entry.trim()).unwrap_or_else(|| entry.trim().to_string())
+}
+
+fn looks_like_regex(entry: &str) -> bool {
+    entry.contains(['^', '$', '[', '(', '|', '+', '\\']) || entry.contains(".*") || entry.contains(".+")
+}
+
+fn parse_selector(entry: &str, names: &[String]) -> NailResult<Option<Selector>> {
+    if entry.is_empty() {
+        return Ok(None);
+    }
+    // An exact column name wins over any other reading of the entry
+    if names.iter().any(|n| n == entry) {
+        return Ok(Some(Selector::Name { name: entry.to_string(), quoted: true }));
+    }
+    if let Some(name) = unquote(entry) {
+        return Ok(Some(Selector::Name { name, quoted: true }));
+    }
+    if entry.len() >= 2 && entry.starts_with('/') && entry.ends_with('/') {
+        return Ok(Some(Selector::Regex(entry[1..entry.len() - 1].to_string())));
+    }
+    if let Some(class) = entry.strip_prefix("type:") {
+        let class = class.trim().to_lowercase();
+        if !TYPE_CLASSES.contains(&class.as_str()) {
+            return Err(NailError::InvalidArgument(format!(
+                "Unknown column type '{}'. Valid types: {}", class, TYPE_CLASSES.join(", ")
+            )));
+        }
+        return Ok(Some(Selector::Type(class)));
+    }
+    if looks_like_regex(entry) {
+        return Ok(Some(Selector::Regex(entry.to_string())));
+    }
+    if let Some((first, last)) = split_range(entry) {
+        let endpoint = |side: &str| -> NailResult<Option<Box<Selector>>> {
+            Ok(parse_selector(side, names)?.map(Box::new))
+        };
+        return Ok(Some(Selector::Range(endpoint(first)?, endpoint(last)?)));
+    }
+    if entry.contains(['*', '?']) {
+        return Ok(Some(Selector::Glob(entry.to_string())));
+    }
+    Ok(Some(Selector::Name { name: entry.to_string(), quoted: false }))
+}
+
+/// Splits `first:last` on the colon outside quotes.
+fn split_range(entry: &str) -> Option<(&str, &str)> {
+    split_pair(entry, ':')
+}
+
+/// Splits an entry such as `before=after` on the first `separator` outside quotes, trimming both sides.
+pub fn split_pair(entry: &str, separator: char) -> Option<(&str, &str)> {
+    let mut in_quotes = false;
+    for (i, c) in entry.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c == separator && !in_quotes => return Some((entry[..i].trim(), entry[i + c.len_utf8()..].trim())),
+            _ => {},
+        }
+    }
+    None
+}
+
+fn match_selector(selector: &Selector, names: &[String], fields: &[&Field]) -> NailResult<Vec<usize>> {
+    match selector {
+        Selector::Name { name, quoted } => Ok(match_name(names, name, *quoted)?.into_iter().collect()),
+        Selector::Regex(pattern) => match_regex(names, pattern),
+        Selector::Glob(pattern) => {
+            let regex = format!("^{}$", regex::escape(pattern).replace("\\*", ".*").replace("\\?", "."));
+            match_regex(names, &regex)
+        },
+        Selector::Range(first, last) => {
+            let endpoint = |side: &Option<Box<Selector>>, default: usize| -> NailResult<Option<usize>> {
+                match side {
+                    None => Ok(Some(default)),
+                    Some(selector) => Ok(match_selector(selector, names, fields)?.first().copied()),
+                }
+            };
+            let last_index = names.len().saturating_sub(1);
+            match (endpoint(first, 0)?, endpoint(last, last_index)?) {
+                (Some(start), Some(end)) if !names.is_empty() => Ok((start.min(end)..=start.max(end)).collect()),
+                _ => Ok(Vec::new()),
+            }
+        },
+        Selector::Type(class) => Ok(fields.iter()
+            .enumerate()
+            .filter(|(_, field)| type_matches(class, field.data_type()))
+            .map(|(index, _)| index)
+            .collect()),
+    }
+}
+
+/// Exact match first; otherwise a unique case-insensitive match unless `exact` is set.
+fn match_name(names: &[String], name: &str, exact: bool) -> NailResult<Option<usize>> {
+    if let Some(index) = names.iter().position(|n| n == name) {
+        return Ok(Some(index));
+    }
+    if exact {
+        return Ok(None);
+    }
+    let lower = name.to_lowercase();
+    let candidates: Vec<usize> = names.iter()
+        .enumerate()
+        .filter(|(_, n)| n.to_lowercase() == lower)
+        .map(|(index, _)| index)
+        .collect();
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [index] => Ok(Some(*index)),
+        _ => Err(NailError::InvalidArgument(format!(
+            "Column '{}' is ambiguous: {}. Quote the exact name to choose one",
+            name,
+            candidates.iter().map(|&i| format!("'{}'", names[i])).collect::<Vec<_>>().join(", ")
+        ))),
+    }
+}
+
+fn match_regex(names: &[String], pattern: &str) -> NailResult<Vec<usize>> {
+    let regex = Regex::new(pattern)?;
+    let matched: Vec<usize> = names.iter().enumerate()
+        .filter(|(_, n)| regex.is_match(n))
+        .map(|(index, _)| index)
+        .collect();
+    if !matched.is_empty() {
+        return Ok(matched);
+    }
+    let regex = Regex::new(&format!("(?i){}", pattern))?;
+    Ok(names.iter().enumerate()
+        .filter(|(_, n)| regex.is_match(n))
+        .map(|(index, _)| index)
+        .collect())
+}
+
+fn type_matches(class: &str, data_type: &DataType) -> bool {
+    match class {
+        "numeric" => data_type.is_numeric(),
+        "integer" => data_type.is_integer(),
+        "float" => data_type.is_floating() || matches!(data_type, DataType::Decimal128(_, _) | DataType::Decimal256(_, _)),
+        "string" => match data_type {
+            DataType::Dictionary(_, value) => type_matches(class, value),
+            other => matches!(other, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View),
+        },
+        "boolean" => matches!(data_type, DataType::Boolean),
+        "date" => matches!(data_type, DataType::Date32 | DataType::Date64),
+        "timestamp" => matches!(data_type, DataType::Timestamp(_, _)),
+        "temporal" => data_type.is_temporal(),
+        "binary" => matches!(data_type, DataType::Binary | DataType::LargeBinary | DataType::BinaryView | DataType::FixedSizeBinary(_)),
+        "nested" => data_type.is_nested(),
+        _ => false,
+    }
+}
+
+/// Tables wider than this only report the column count instead of listing every name.
+const MAX_LISTED_COLUMNS: usize = 20;
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Error for a column name that is not in `available`, with "did you mean" suggestions.
+pub fn column_not_found(column: &str, available: &[String]) -> NailError {
+    NailError::ColumnNotFound(format!(
+        "Column '{}' not found. {}",
+        column, column_hint(&[column], available)
+    ))
+}
+
+/// Error for several missing column names at once.
+pub fn columns_not_found(columns: &[&str], available: &[String]) -> NailError {
+    if let [column] = columns {
+        return column_not_found(column, available);
+    }
+    NailError::ColumnNotFound(format!(
+        "Columns not found: {:?}. {}",
+        columns, column_hint(columns, available)
+    ))
+}
+
+/// Suggestions for the missing `columns` followed by the available columns, or
+/// just their count on wide tables.
+pub fn column_hint(columns: &[&str], available: &[String]) -> String {
+    let suggestions: Vec<String> = columns.iter()
+        .filter_map(|column| {
+            let matches = suggest_columns(column, available);
+            if matches.is_empty() {
+                return None;
+            }
+            let quoted = matches.iter().map(|m| format!("'{}'", m)).collect::<Vec<_>>().join(" or ");
+            Some(if columns.len() > 1 { format!("{} for '{}'", quoted, column) } else { quoted })
+        })
+        .collect();
+
+    let mut hint = String::new();
+    if !suggestions.is_empty() {
+        hint.push_str(&format!("Did you mean {}? ", suggestions.join(", ")));
+    }
+    if available.len() <= MAX_LISTED_COLUMNS {
+        hint.push_str(&format!("Available columns: {:?}", available));
+    } else {
+        hint.push_str(&format!("The table has {} columns; run 'nail headers' to list them", available.len()));
+    }
+    hint
+}
+
+/// Column names close to `name`: equal after ignoring case, `_`, `-`, spaces and dots,
+/// within a small edit distance, or containing it.
+pub fn suggest_columns(name: &str, available: &[String]) -> Vec<String> {
+    let target = normalize(name);
+    if target.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (target.chars().count() / 3).clamp(1, 3);
+
+    let mut ranked: Vec<(usize, &String)> = available.iter()
+        .filter_map(|candidate| {
+            let normalized = normalize(candidate);
+            let distance = edit_distance(&target, &normalized);
+            if distance <= max_distance {
+                Some((distance, candidate))
+            } else if target.chars().count() >= 3 && normalized.contains(&target) {
+                Some((max_distance + 1, candidate))
+            } else {
+                None
+            }
         })
+        .collect();
+    ranked.sort();
+    ranked.into_iter().take(MAX_SUGGESTIONS).map(|(_, c)| c.clone()).collect()
 }
 
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' ' | '.'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent characters each count as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn schema() -> DFSchemaRef {
+        use datafusion::arrow::datatypes::{Schema, TimeUnit};
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("Name", DataType::Utf8, true),
+            Field::new("price", DataType::Float64, true),
+            Field::new("qty_sold", DataType::Int32, true),
+            Field::new("city, state", DataType::Utf8, true),
+            Field::new("created_at", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+        ]);
+        std::sync::Arc::new(datafusion::common::DFSchema::try_from(schema).unwrap())
+    }
+
+    fn select(spec: &str) -> Vec<String> {
+        select_columns_by_pattern(schema(), spec).unwrap()
+    }
+
+    #[test]
+    fn test_selector_grammar() {
+        assert_eq!(select("price,id"), vec!["price", "id"]);
+        assert_eq!(select("name"), vec!["Name"]);
+        assert_eq!(select("\"city, state\",id"), vec!["city, state", "id"]);
+        assert_eq!(select("/^(id|price)$/"), vec!["id", "price"]);
+        assert_eq!(select("^qty"), vec!["qty_sold"]);
+        assert_eq!(select("*_*"), vec!["qty_sold", "created_at"]);
+        assert_eq!(select("Name:qty_sold"), vec!["Name", "price", "qty_sold"]);
+        assert_eq!(select("qty_sold:"), vec!["qty_sold", "city, state", "created_at"]);
+        assert_eq!(select("type:numeric"), vec!["id", "price", "qty_sold"]);
+        assert_eq!(select("type:string,!\"city, state\""), vec!["Name"]);
+        assert_eq!(select("!id,!type:string"), vec!["price", "qty_sold", "created_at"]);
+        assert_eq!(select("type:temporal"), vec!["created_at"]);
+    }
+
+    #[test]
+    fn test_selector_errors() {
+        let err = select_columns_by_pattern(schema(), "id,pricee").unwrap_err().to_string();
+        assert!(err.contains("Column 'pricee' not found") && err.contains("Did you mean 'price'?"));
+        let err = select_columns_by_pattern(schema(), "pricee,nmae").unwrap_err().to_string();
+        assert!(err.contains("Columns not found") && err.contains("'price' for 'pricee', 'Name' for 'nmae'"));
+        assert!(select_columns_by_pattern(schema(), "\"name\"").is_err());
+        assert!(select_columns_by_pattern(schema(), "type:colour").unwrap_err().to_string().contains("Valid types"));
+        assert!(select_columns_by_pattern(schema(), "!id,!Name,!price,!qty_sold,!\"city, state\",!created_at").is_err());
+        assert_eq!(resolve_column_name(&schema(), "NAME").unwrap(), "Name");
+    }
+
+    #[test]
+    fn test_split_pair() {
+        assert_eq!(split_pair(" a = b ", '='), Some(("a", "b")));
+        assert_eq!(split_pair("\"a=b\"=c", '='), Some(("\"a=b\"", "c")));
+        assert_eq!(split_pair("abc", '='), None);
+        assert_eq!(unquoted_name(" \"say \"\"hi\"\"\" "), "say \"hi\"");
+        assert_eq!(unquoted_name("plain"), "plain");
+    }
+
+    #[test]
+    fn test_suggest_columns() {
+        let available = names(&["unit_price", "price", "quantity", "customer_id", "CustomerName"]);
+        assert_eq!(suggest_columns("pricee", &available)[0], "price");
+        assert_eq!(suggest_columns("qunatity", &available), vec!["quantity"]);
+        assert_eq!(suggest_columns("customerid", &available)[0], "customer_id");
+        assert_eq!(suggest_columns("customer_name", &available), vec!["CustomerName"]);
+        assert!(suggest_columns("zzz", &available).is_empty());
+    }
+
+    #[test]
+    fn test_column_hint_on_wide_tables() {
+        let available: Vec<String> = (0..400).map(|i| format!("feature_{}", i)).collect();
+        let message = column_not_found("feature_O", &available).to_string();
+        assert!(message.contains("Did you mean 'feature_0'"));
+        assert!(message.contains("400 columns"));
+        assert!(!message.contains("feature_399"));
+    }
+}
@@ -0,0 +1,353 @@
+use clap::parser::ValueSource;
+use clap::{ArgAction, Command};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use crate::error::{NailError, NailResult};
+
+/// Project-local config file, looked up from the current directory upwards.
+pub const PROJECT_CONFIG_FILE: &str = ".nail.toml";
+
+/// Environment variable that overrides the location of the user config file.
+pub const CONFIG_PATH_ENV: &str = "NAIL_CONFIG";
+
+const ENV_PREFIX: &str = "NAIL_";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingSource {
+	User(PathBuf),
+	Project(PathBuf),
+	Env(String),
+}
+
+impl fmt::Display for SettingSource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SettingSource::User(path) => write!(f, "user config {}", path.display()),
+			SettingSource::Project(path) => write!(f, "project config {}", path.display()),
+			SettingSource::Env(name) => write!(f, "environment {}", name),
+		}
+	}
+}
+
+/// One effective default. `command` is `None` for settings that apply to every command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting {
+	pub command: Option<String>,
+	pub key: String,
+	pub value: String,
+	pub source: SettingSource,
+}
+
+/// Config files in increasing order of precedence: user, then project.
+#[derive(Debug, Default)]
+pub struct Config {
+	pub user_path: Option<PathBuf>,
+	pub project_path: Option<PathBuf>,
+	layers: Vec<(SettingSource, toml::Table)>,
+}
+
+impl Config {
+	/// Loads `~/.config/nail/config.toml` (or `$NAIL_CONFIG`) and the nearest `.nail.toml`.
+	pub fn load() -> NailResult<Self> {
+		let cwd = std::env::current_dir()?;
+		Self::load_from(user_config_path(), &cwd)
+	}
+
+	pub fn load_from(user_path: Option<PathBuf>, cwd: &Path) -> NailResult<Self> {
+		let project_path = cwd.ancestors()
+			.map(|dir| dir.join(PROJECT_CONFIG_FILE))
+			.find(|path| path.is_file());
+
+		let mut layers = Vec::new();
+		if let Some(path) = user_path.as_ref().filter(|p| p.is_file()) {
+			layers.push((SettingSource::User(path.clone()), read_table(path)?));
+		}
+		if let Some(path) = &project_path {
+			layers.push((SettingSource::Project(path.clone()), read_table(path)?));
+		}
+
+		Ok(Self { user_path, project_path, layers })
+	}
+
+	/// Resolves the effective settings, one per key, from the files and `NAIL_*` variables.
+	/// With `command`, only settings that apply to that command are returned.
+	pub fn settings(&self, root: &Command, command: Option<&str>, env: &[(String, String)]) -> Vec<Setting> {
+		let mut settings: Vec<Setting> = Vec::new();
+		let mut set = |setting: Setting| {
+			settings.retain(|s| !(s.command == setting.command && s.key == setting.key));
+			settings.push(setting);
+		};
+
+		for (source, table) in &self.layers {
+			for (key, value) in table {
+				match value {
+					toml::Value::Table(section) => {
+						if command.is_some_and(|c| c != key) {
+							continue;
+						}
+						for (option, value) in section {
+							if let Some(value) = scalar_to_string(value) {
+								set(Setting { command: Some(key.clone()), key: normalize_key(option), value, source: source.clone() });
+							}
+						}
+					},
+					value => {
+						if let Some(value) = scalar_to_string(value) {
+							set(Setting { command: None, key: normalize_key(key), value, source: source.clone() });
+						}
+					},
+				}
+			}
+		}
+
+		for (name, value) in env {
+			let Some(rest) = name.strip_prefix(ENV_PREFIX) else { continue };
+			if name == CONFIG_PATH_ENV {
+				continue;
+			}
+			let rest = rest.to_lowercase().replace('_', "-");
+
+			let scoped = root.get_subcommands()
+				.filter(|sub| command.is_none_or(|c| c == sub.get_name()))
+				.find_map(|sub| {
+					let option = rest.strip_prefix(&format!("{}-", sub.get_name()))?;
+					long_names(sub).contains(&option.to_string()).then(|| (sub.get_name().to_string(), option.to_string()))
+				});
+
+			match scoped {
+				Some((sub, option)) => set(Setting { command: Some(sub), key: option, value: value.clone(), source: SettingSource::Env(name.clone()) }),
+				None => set(Setting { command: None, key: rest, value: value.clone(), source: SettingSource::Env(name.clone()) }),
+			}
+		}
+
+		// Command sections and command-scoped variables win over global keys of the same layer or lower
+		let rank = |s: &Setting| match (&s.source, s.command.is_some()) {
+			(SettingSource::User(_), scoped) => scoped as u8,
+			(SettingSource::Project(_), scoped) => 2 + scoped as u8,
+			(SettingSource::Env(_), scoped) => 4 + scoped as u8,
+		};
+		if let Some(command) = command {
+			let mut effective: Vec<Setting> = Vec::new();
+			for setting in settings.into_iter().filter(|s| s.command.as_deref().is_none_or(|c| c == command)) {
+				match effective.iter_mut().find(|s| s.key == setting.key) {
+					Some(existing) if rank(existing) > rank(&setting) => {},
+					Some(existing) => *existing = setting,
+					None => effective.push(setting),
+				}
+			}
+			settings = effective;
+		}
+
+		settings.sort_by(|a, b| (&a.command, &a.key).cmp(&(&b.command, &b.key)));
+		settings
+	}
+}
+
+fn user_config_path() -> Option<PathBuf> {
+	if let Some(path) = std::env::var_os(CONFIG_PATH_ENV) {
+		return Some(PathBuf::from(path));
+	}
+	std::env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+		.map(|dir| dir.join("nail").join("config.toml"))
+}
+
+fn read_table(path: &Path) -> NailResult<toml::Table> {
+	let content = std::fs::read_to_string(path)?;
+	content.parse::<toml::Table>()
+		.map_err(|e| NailError::InvalidArgument(format!("Invalid config file {}: {}", path.display(), e.message())))
+}
+
+fn normalize_key(key: &str) -> String {
+	key.replace('_', "-")
+}
+
+fn scalar_to_string(value: &toml::Value) -> Option<String> {
+	match value {
+		toml::Value::String(s) => Some(s.clone()),
+		toml::Value::Integer(i) => Some(i.to_string()),
+		toml::Value::Float(f) => Some(f.to_string()),
+		toml::Value::Boolean(b) => Some(b.to_string()),
+		toml::Value::Array(items) => items.iter().map(scalar_to_string).collect::<Option<Vec<_>>>().map(|v| v.join(",")),
+		toml::Value::Datetime(d) => Some(d.to_string()),
+		toml::Value::Table(_) => None,
+	}
+}
+
+fn long_names(command: &Command) -> Vec<String> {
+	command.get_arguments()
+		.filter_map(|arg| arg.get_long())
+		.map(str::to_string)
+		.collect()
+}
+
+fn is_true(value: &str) -> bool {
+	matches!(value.to_lowercase().as_str(), "true" | "1" | "yes" | "on")
+}
+
+/// Removes `--no-<flag>` arguments for boolean flags of the chosen subcommand and returns
+/// the negated flag names, so a flag switched on by config can be switched off again.
+fn strip_negations(root: &Command, args: Vec<OsString>) -> (Vec<OsString>, Vec<String>) {
+	let Some((position, sub)) = args.iter().enumerate().skip(1)
+		.find_map(|(i, arg)| root.find_subcommand(arg.to_str()?).map(|sub| (i, sub)))
+	else {
+		return (args, Vec::new());
+	};
+
+	let mut negated = Vec::new();
+	let mut result = Vec::with_capacity(args.len());
+	let mut options_ended = false;
+	for (i, arg) in args.iter().enumerate() {
+		options_ended |= arg == "--";
+		let flag = arg.to_str()
+			.filter(|_| i > position && !options_ended)
+			.and_then(|a| a.strip_prefix("--no-"))
+			.filter(|flag| {
+				let declared = |long: &str| sub.get_arguments().find(|a| a.get_long() == Some(long));
+				declared(&format!("no-{}", flag)).is_none()
+					&& declared(flag).is_some_and(|a| matches!(a.get_action(), ArgAction::SetTrue))
+			});
+		match flag {
+			Some(flag) => negated.push(flag.to_string()),
+			None => result.push(arg.clone()),
+		}
+	}
+	(result, negated)
+}
+
+/// Inserts defaults from the config files and environment into `args` for every option
+/// of the chosen subcommand that was not given on the command line. `--no-<flag>` keeps a
+/// boolean default from config or the environment from being applied.
+pub fn apply_config(root: &Command, config: &Config, args: Vec<OsString>, env: &[(String, String)]) -> Vec<OsString> {
+	let (args, negated) = strip_negations(root, args);
+	let Ok(matches) = root.clone().try_get_matches_from(&args) else {
+		return args;
+	};
+	let Some((name, sub_matches)) = matches.subcommand() else {
+		return args;
+	};
+	let Some(sub) = root.find_subcommand(name) else {
+		return args;
+	};
+
+	let mut injected: Vec<OsString> = Vec::new();
+	for setting in config.settings(root, Some(name), env) {
+		// Global options such as --error-format are declared on the root command
+		let Some(arg) = sub.get_arguments().chain(root.get_arguments().filter(|a| a.is_global_set()))
+			.find(|a| a.get_long() == Some(setting.key.as_str()))
+		else {
+			continue;
+		};
+		let id = arg.get_id().as_str();
+		let given = |m: &clap::ArgMatches| m.try_contains_id(id).is_ok() && m.value_source(id) == Some(ValueSource::CommandLine);
+		if given(sub_matches) || given(&matches) || negated.contains(&setting.key) {
+			continue;
+		}
+
+		let flag = format!("--{}", setting.key);
+		if arg.get_action().takes_values() {
+			injected.push(OsString::from(format!("{}={}", flag, setting.value)));
+		} else if is_true(&setting.value) {
+			injected.push(OsString::from(flag));
+		}
+	}
+
+	let position = args.iter().position(|a| a == name).map(|i| i + 1).unwrap_or(args.len());
+	let mut result = args;
+	result.splice(position..position, injected);
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{CommandFactory, Parser};
+	use crate::cli::Cli;
+
+	fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+		vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+	}
+
+	fn os_args(args: &[&str]) -> Vec<OsString> {
+		args.iter().map(OsString::from).collect()
+	}
+
+	#[test]
+	fn test_precedence_user_project_env() {
+		let dir = tempfile::tempdir().unwrap();
+		let user = dir.path().join("config.toml");
+		std::fs::write(&user, "jobs = 2\nformat = \"csv\"\nverbose = true\n\n[sort]\nnulls = \"first\"\n").unwrap();
+		let project_dir = dir.path().join("project");
+		std::fs::create_dir_all(project_dir.join("sub")).unwrap();
+		std::fs::write(project_dir.join(PROJECT_CONFIG_FILE), "jobs = 4\n").unwrap();
+
+		let config = Config::load_from(Some(user), &project_dir.join("sub")).unwrap();
+		let root = Cli::command();
+		let settings = config.settings(&root, Some("sort"), &env(&[("NAIL_FORMAT", "json"), ("NAIL_SORT_NULLS", "skip")]));
+
+		let get = |key: &str| settings.iter().find(|s| s.key == key).map(|s| (s.value.clone(), s.source.clone()));
+		assert_eq!(get("jobs").unwrap().0, "4");
+		assert!(matches!(get("jobs").unwrap().1, SettingSource::Project(_)));
+		assert_eq!(get("format").unwrap(), ("json".to_string(), SettingSource::Env("NAIL_FORMAT".to_string())));
+		assert_eq!(get("nulls").unwrap().0, "skip");
+	}
+
+	#[test]
+	fn test_apply_config_respects_command_line() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), "jobs = 3\nverbose = true\ncompression = \"zstd\"\n").unwrap();
+		let config = Config::load_from(None, dir.path()).unwrap();
+		let root = Cli::command();
+
+		let args = apply_config(&root, &config, os_args(&["nail", "head", "data.csv", "-j", "8"]), &[]);
+		assert_eq!(args, os_args(&["nail", "head", "--verbose", "data.csv", "-j", "8"]));
+
+		// --no-<flag> turns off a boolean default and is not passed on to the parser
+		let args = apply_config(&root, &config, os_args(&["nail", "head", "data.csv", "--no-verbose"]), &[]);
+		assert_eq!(args, os_args(&["nail", "head", "--jobs=3", "data.csv"]));
+		let args = apply_config(&root, &config, os_args(&["nail", "head", "data.csv", "--no-header"]), &[]);
+		assert_eq!(args, os_args(&["nail", "head", "--jobs=3", "--verbose", "data.csv", "--no-header"]));
+
+		// Options the command does not have are left out
+		let args = apply_config(&root, &config, os_args(&["nail", "update"]), &[]);
+		assert_eq!(args, os_args(&["nail", "update", "--verbose"]));
+	}
+
+	#[test]
+	fn test_override_order_cli_env_project_user() {
+		let dir = tempfile::tempdir().unwrap();
+		let user = dir.path().join("config.toml");
+		std::fs::write(&user, "jobs = 2\nverbose = true\nno_pruning = true\n").unwrap();
+		let project_dir = dir.path().join("project");
+		std::fs::create_dir_all(&project_dir).unwrap();
+		std::fs::write(project_dir.join(PROJECT_CONFIG_FILE), "jobs = 4\nverbose = false\n").unwrap();
+		let config = Config::load_from(Some(user), &project_dir).unwrap();
+		let root = Cli::command();
+		let parse = |args: &[&str], vars: &[(&str, &str)]| {
+			let args = apply_config(&root, &config, os_args(args), &env(vars));
+			match Cli::try_parse_from(args).unwrap().command {
+				crate::commands::Commands::Head(head) => head.common,
+				_ => panic!("expected head"),
+			}
+		};
+
+		// Project overrides user, including switching a boolean back off
+		let common = parse(&["nail", "head", "data.csv"], &[]);
+		assert_eq!(common.read.jobs, Some(4));
+		assert!(!common.verbose);
+		assert!(common.read.no_pruning);
+
+		// Environment overrides project
+		let vars = [("NAIL_JOBS", "6"), ("NAIL_VERBOSE", "true")];
+		let common = parse(&["nail", "head", "data.csv"], &vars);
+		assert_eq!(common.read.jobs, Some(6));
+		assert!(common.verbose);
+
+		// The command line overrides everything, and --no-<flag> turns a configured flag off
+		let common = parse(&["nail", "head", "data.csv", "-j", "8", "--no-verbose"], &vars);
+		assert_eq!(common.read.jobs, Some(8));
+		assert!(!common.verbose);
+	}
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use datafusion::prelude::*;
+use crate::error::NailResult;
+
+/// Formats tried, in order, when detecting how the dates in a column are written.
+/// Month-first comes before day-first, as in most US-produced exports.
+const CANDIDATE_FORMATS: &[&str] = &[
+	"%Y-%m-%dT%H:%M:%S%.f%:z",
+	"%Y-%m-%d %H:%M:%S%.f%:z",
+	"%Y-%m-%dT%H:%M:%S%.fZ",
+	"%Y-%m-%dT%H:%M:%S%.f",
+	"%Y-%m-%d %H:%M:%S%.f",
+	"%Y-%m-%dT%H:%M",
+	"%Y-%m-%d %H:%M",
+	"%Y-%m-%d",
+	"%Y/%m/%d %H:%M:%S",
+	"%Y/%m/%d %H:%M",
+	"%Y/%m/%d",
+	"%m/%d/%Y %H:%M:%S",
+	"%m/%d/%Y %H:%M",
+	"%m/%d/%Y",
+	"%d/%m/%Y %H:%M:%S",
+	"%d/%m/%Y %H:%M",
+	"%d/%m/%Y",
+	"%d.%m.%Y %H:%M:%S",
+	"%d.%m.%Y %H:%M",
+	"%d.%m.%Y",
+	"%m-%d-%Y",
+	"%d-%m-%Y",
+	"%Y%m%d",
+	"%d %b %Y",
+	"%d %B %Y",
+	"%b %d %Y",
+	"%b %d, %Y",
+	"%B %d, %Y",
+	"%a, %d %b %Y %H:%M:%S %z",
+];
+
+/// Converts a user-friendly pattern such as `dd/mm/yyyy HH:mm` into a chrono format.
+/// `mm` means minutes right after an hour or before seconds, months otherwise.
+/// Patterns that already contain `%` are used as they are.
+pub fn convert_date_format(format: &str) -> NailResult<String> {
+	if format.contains('%') {
+		return Ok(format.to_string());
+	}
+
+	const TOKENS: &[(&str, &str)] = &[
+		("yyyy", "%Y"), ("YYYY", "%Y"), ("yy", "%y"), ("YY", "%y"),
+		("MMMM", "%B"), ("MMM", "%b"), ("MM", "%m"),
+		("dd", "%d"), ("DD", "%d"),
+		("HH", "%H"), ("hh", "%H"),
+		("ss", "%S"), ("SS", "%S"),
+	];
+
+	let mut result = String::new();
+	let mut rest = format;
+	let mut after_hour = false;
+	while !rest.is_empty() {
+		if let Some(tail) = rest.strip_prefix("mm") {
+			let minutes = after_hour || tail.trim_start_matches(':').starts_with("ss");
+			result.push_str(if minutes { "%M" } else { "%m" });
+			rest = tail;
+			continue;
+		}
+		if let Some((token, replacement)) = TOKENS.iter().find(|(token, _)| rest.starts_with(token)) {
+			result.push_str(replacement);
+			after_hour = *replacement == "%H";
+			rest = &rest[token.len()..];
+			continue;
+		}
+		let c = rest.chars().next().unwrap_or_default();
+		if !c.is_ascii_punctuation() && !c.is_whitespace() {
+			after_hour = false;
+		}
+		result.push(c);
+		rest = &rest[c.len_utf8()..];
+	}
+	Ok(result)
+}
+
+/// Parses a string column into dates using a user-friendly or chrono format.
+pub fn parse_date_expr(col_expr: Expr, format: &str) -> NailResult<Expr> {
+	let strptime_format = convert_date_format(format)?;
+	Ok(to_date(vec![col_expr, lit(strptime_format)]))
+}
+
+/// Whether a chrono format includes a time of day.
+pub fn has_time_fields(format: &str) -> bool {
+	["%H", "%I", "%M", "%S", "%T", "%R", "%s", "%z", "%:z"].iter().any(|field| format.contains(field))
+}
+
+/// Whether `value` can be parsed with the chrono `format`.
+pub fn matches_format(value: &str, format: &str) -> bool {
+	if format.contains("%z") || format.contains("%:z") {
+		DateTime::parse_from_str(value, format).is_ok()
+	} else if has_time_fields(format) {
+		NaiveDateTime::parse_from_str(value, format).is_ok()
+	} else {
+		NaiveDate::parse_from_str(value, format).is_ok()
+	}
+}
+
+/// Formats among the known candidates that parse every one of `values`, most likely first.
+/// Several matches mean the values are ambiguous, e.g. `01/02/2024`.
+pub fn detect_date_formats(values: &[&str]) -> Vec<&'static str> {
+	if values.is_empty() {
+		return Vec::new();
+	}
+	CANDIDATE_FORMATS.iter()
+		.copied()
+		.filter(|format| values.iter().all(|value| matches_format(value.trim(), format)))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_convert_date_format() {
+		assert_eq!(convert_date_format("yyyy-mm-dd").unwrap(), "%Y-%m-%d");
+		assert_eq!(convert_date_format("dd/mm/yyyy").unwrap(), "%d/%m/%Y");
+		assert_eq!(convert_date_format("mm-dd-yy").unwrap(), "%m-%d-%y");
+		assert_eq!(convert_date_format("dd/MM/yyyy HH:mm").unwrap(), "%d/%m/%Y %H:%M");
+		assert_eq!(convert_date_format("yyyy-mm-dd hh:mm:ss").unwrap(), "%Y-%m-%d %H:%M:%S");
+		assert_eq!(convert_date_format("dd MMM yyyy").unwrap(), "%d %b %Y");
+		assert_eq!(convert_date_format("%d.%m.%Y").unwrap(), "%d.%m.%Y");
+	}
+
+	#[test]
+	fn test_detect_date_formats() {
+		assert_eq!(detect_date_formats(&["2024-01-05", "2024-12-31"]), vec!["%Y-%m-%d"]);
+		assert_eq!(detect_date_formats(&["2024-01-05T10:00:00+02:00"])[0], "%Y-%m-%dT%H:%M:%S%.f%:z");
+		assert_eq!(detect_date_formats(&["2024-01-05 10:00:00.250"]), vec!["%Y-%m-%d %H:%M:%S%.f"]);
+		assert_eq!(detect_date_formats(&["31/01/2024", "15/02/2024"]), vec!["%d/%m/%Y"]);
+		assert_eq!(detect_date_formats(&["01/02/2024"]), vec!["%m/%d/%Y", "%d/%m/%Y"]);
+		assert_eq!(detect_date_formats(&["5 Mar 2024"])[0], "%d %b %Y");
+		assert!(detect_date_formats(&["2024-01-05", "tomorrow"]).is_empty());
+	}
+
+	#[test]
+	fn test_has_time_fields() {
+		assert!(has_time_fields("%Y-%m-%d %H:%M"));
+		assert!(!has_time_fields("%d/%m/%Y"));
+	}
+}
@@ -0,0 +1,341 @@
+use encoding_rs::{Decoder, DecoderResult, Encoder, EncoderResult, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Chain, Cursor, Read, Write};
+use std::path::Path;
+use crate::error::{NailError, NailResult};
+
+/// Encoding label that enables BOM and content based detection.
+pub const AUTO_ENCODING: &str = "auto";
+
+/// Leading bytes inspected to detect the encoding of a stream.
+const DETECT_PREFIX_BYTES: u64 = 64 * 1024;
+
+const DECODE_BUFFER_BYTES: usize = 64 * 1024;
+
+/// The bytes read for detection followed by the rest of the stream.
+pub type PrefixedReader<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// Resolves an encoding label such as `utf-8`, `windows-1252`, `latin1` or `utf-16le`.
+pub fn resolve_encoding(label: &str) -> NailResult<&'static Encoding> {
+	Encoding::for_label(label.trim().as_bytes())
+		.ok_or_else(|| NailError::InvalidArgument(format!(
+			"Unknown encoding: '{}'. Use a label such as utf-8, windows-1252, latin1, utf-16le or 'auto'",
+			label
+		)))
+}
+
+/// Detects the encoding of raw input: a byte order mark wins, then valid UTF-8,
+/// then a statistical guess over the content.
+pub fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+	if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+		return encoding;
+	}
+	match std::str::from_utf8(bytes) {
+		Ok(_) => return UTF_8,
+		// A sample may end in the middle of a multi-byte character
+		Err(e) if e.error_len().is_none() => return UTF_8,
+		Err(_) => {},
+	}
+
+	let mut detector = chardetng::EncodingDetector::new();
+	detector.feed(bytes, true);
+	detector.guess(None, true)
+}
+
+/// Wraps `reader` to transcode it to UTF-8 as it is read, using the encoding named by
+/// `label` or, for `auto`, the one detected from the leading bytes. A byte order mark takes
+/// precedence over the requested encoding and is stripped. Returns the encoding in use.
+pub fn decode_reader<R: Read>(mut reader: R, label: &str) -> NailResult<(DecodeReader<PrefixedReader<R>>, &'static Encoding)> {
+	let mut prefix = Vec::new();
+	(&mut reader).take(DETECT_PREFIX_BYTES).read_to_end(&mut prefix)?;
+
+	let encoding = if label.trim().eq_ignore_ascii_case(AUTO_ENCODING) {
+		detect_encoding(&prefix)
+	} else {
+		resolve_encoding(label)?
+	};
+	let used = Encoding::for_bom(&prefix).map_or(encoding, |(bom_encoding, _)| bom_encoding);
+
+	Ok((DecodeReader::new(Cursor::new(prefix).chain(reader), encoding), used))
+}
+
+/// A reader yielding the UTF-8 transcoding of another reader. Input that is not valid in
+/// the source encoding fails with an `InvalidData` error instead of being replaced.
+pub struct DecodeReader<R> {
+	inner: R,
+	decoder: Decoder,
+	input: Vec<u8>,
+	input_start: usize,
+	input_end: usize,
+	output: Vec<u8>,
+	output_start: usize,
+	output_end: usize,
+	eof: bool,
+	done: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+	fn new(inner: R, encoding: &'static Encoding) -> Self {
+		Self {
+			inner,
+			decoder: encoding.new_decoder(),
+			input: vec![0; DECODE_BUFFER_BYTES],
+			input_start: 0,
+			input_end: 0,
+			output: vec![0; DECODE_BUFFER_BYTES],
+			output_start: 0,
+			output_end: 0,
+			eof: false,
+			done: false,
+		}
+	}
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		loop {
+			if self.output_start < self.output_end {
+				let n = buf.len().min(self.output_end - self.output_start);
+				buf[..n].copy_from_slice(&self.output[self.output_start..self.output_start + n]);
+				self.output_start += n;
+				return Ok(n);
+			}
+			if self.done {
+				return Ok(0);
+			}
+
+			if self.input_start == self.input_end && !self.eof {
+				self.input_end = self.inner.read(&mut self.input)?;
+				self.input_start = 0;
+				self.eof = self.input_end == 0;
+			}
+
+			let (result, read, written) = self.decoder.decode_to_utf8_without_replacement(
+				&self.input[self.input_start..self.input_end],
+				&mut self.output,
+				self.eof,
+			);
+			self.input_start += read;
+			self.output_start = 0;
+			self.output_end = written;
+			match result {
+				DecoderResult::Malformed(_, _) => {
+					return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+						"Input is not valid {}. Try --encoding auto or specify the correct encoding",
+						self.decoder.encoding().name()
+					)));
+				},
+				DecoderResult::InputEmpty if self.eof => self.done = true,
+				DecoderResult::InputEmpty | DecoderResult::OutputFull => {},
+			}
+		}
+	}
+}
+
+/// A writer that encodes the UTF-8 text written to it into another encoding as it goes.
+/// UTF-16 output starts with a byte order mark. Characters the encoding cannot represent
+/// fail the write with an `InvalidData` error.
+pub struct EncodeWriter<W: Write> {
+	inner: W,
+	encoding: &'static Encoding,
+	encoder: Encoder,
+	/// Leading bytes of a UTF-8 sequence split across writes.
+	pending: Vec<u8>,
+	output: Vec<u8>,
+}
+
+impl<W: Write> EncodeWriter<W> {
+	pub fn new(mut inner: W, encoding: &'static Encoding) -> io::Result<Self> {
+		if encoding == UTF_16LE || encoding == UTF_16BE {
+			inner.write_all(&utf16_bytes(0xFEFF, encoding))?;
+		}
+		Ok(Self {
+			inner,
+			encoding,
+			encoder: encoding.new_encoder(),
+			pending: Vec::new(),
+			output: vec![0; DECODE_BUFFER_BYTES],
+		})
+	}
+
+	/// Flushes the encoder and returns the inner writer.
+	pub fn finish(mut self) -> io::Result<W> {
+		if !self.pending.is_empty() {
+			return Err(invalid_utf8());
+		}
+		self.encode("", true)?;
+		self.inner.flush()?;
+		Ok(self.inner)
+	}
+
+	fn encode(&mut self, mut text: &str, last: bool) -> io::Result<()> {
+		if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+			self.output.clear();
+			for unit in text.encode_utf16() {
+				self.output.extend_from_slice(&utf16_bytes(unit, self.encoding));
+			}
+			return self.inner.write_all(&self.output);
+		}
+
+		self.output.resize(DECODE_BUFFER_BYTES, 0);
+		loop {
+			let (result, read, written) = self.encoder.encode_from_utf8_without_replacement(text, &mut self.output, last);
+			self.inner.write_all(&self.output[..written])?;
+			text = &text[read..];
+			match result {
+				EncoderResult::InputEmpty => return Ok(()),
+				EncoderResult::OutputFull => {},
+				EncoderResult::Unmappable(c) => {
+					return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+						"Character '{}' cannot be represented in {}",
+						c, self.encoding.name()
+					)));
+				},
+			}
+		}
+	}
+}
+
+impl<W: Write> Write for EncodeWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let mut bytes = std::mem::take(&mut self.pending);
+		bytes.extend_from_slice(buf);
+		let complete = match std::str::from_utf8(&bytes) {
+			Ok(text) => text.len(),
+			Err(e) if e.error_len().is_none() => e.valid_up_to(),
+			Err(_) => return Err(invalid_utf8()),
+		};
+		let text = std::str::from_utf8(&bytes[..complete]).map_err(|_| invalid_utf8())?;
+		self.encode(text, false)?;
+		self.pending = bytes[complete..].to_vec();
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+fn utf16_bytes(unit: u16, encoding: &'static Encoding) -> [u8; 2] {
+	if encoding == UTF_16LE {
+		unit.to_le_bytes()
+	} else {
+		unit.to_be_bytes()
+	}
+}
+
+fn invalid_utf8() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "Output is not valid UTF-8")
+}
+
+/// Rewrites a UTF-8 text file using the given output encoding, streaming it through a
+/// sibling temporary file that replaces the original once complete.
+pub fn transcode_file(path: &Path, label: &str) -> NailResult<()> {
+	let encoding = resolve_encoding(label)?;
+	if encoding == UTF_8 {
+		return Ok(());
+	}
+
+	let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+	temp_name.push(".transcoding");
+	let temp_path = path.with_file_name(temp_name);
+	let result = (|| -> NailResult<()> {
+		let mut input = BufReader::new(File::open(path)?);
+		let mut writer = EncodeWriter::new(BufWriter::new(File::create(&temp_path)?), encoding)?;
+		io::copy(&mut input, &mut writer)?;
+		writer.finish()?.into_inner().map_err(|e| e.into_error())?;
+		std::fs::rename(&temp_path, path)?;
+		Ok(())
+	})();
+	if result.is_err() {
+		let _ = std::fs::remove_file(&temp_path);
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn encode_from_utf8(text: &str, label: &str) -> NailResult<Vec<u8>> {
+		let mut writer = EncodeWriter::new(Vec::new(), resolve_encoding(label)?)?;
+		writer.write_all(text.as_bytes())?;
+		Ok(writer.finish()?)
+	}
+
+	fn decode_to_utf8(bytes: &[u8], label: &str) -> NailResult<(Vec<u8>, &'static Encoding)> {
+		let (mut reader, used) = decode_reader(bytes, label)?;
+		let mut decoded = Vec::new();
+		reader.read_to_end(&mut decoded)?;
+		Ok((decoded, used))
+	}
+
+	#[test]
+	fn test_decode_windows_1252() {
+		let bytes = b"name\ncaf\xe9\n";
+		let (decoded, used) = decode_to_utf8(bytes, "windows-1252").unwrap();
+		assert_eq!(String::from_utf8(decoded).unwrap(), "name\ncafé\n");
+		assert_eq!(used.name(), "windows-1252");
+	}
+
+	#[test]
+	fn test_bom_overrides_requested_encoding() {
+		let bytes = b"\xef\xbb\xbfid,name\n1,caf\xc3\xa9\n";
+		let (decoded, used) = decode_to_utf8(bytes, "latin1").unwrap();
+		assert_eq!(String::from_utf8(decoded).unwrap(), "id,name\n1,café\n");
+		assert_eq!(used, UTF_8);
+	}
+
+	#[test]
+	fn test_auto_detects_utf16_bom() {
+		let bytes = encode_from_utf8("id\n1\n", "utf-16le").unwrap();
+		let (decoded, used) = decode_to_utf8(&bytes, "auto").unwrap();
+		assert_eq!(String::from_utf8(decoded).unwrap(), "id\n1\n");
+		assert_eq!(used, UTF_16LE);
+	}
+
+	#[test]
+	fn test_invalid_utf8_is_reported() {
+		let result = decode_to_utf8(b"caf\xe9", "utf-8");
+		assert!(result.unwrap_err().to_string().contains("not valid UTF-8"));
+	}
+
+	#[test]
+	fn test_decode_across_buffer_boundaries() {
+		let text = "é".repeat(DECODE_BUFFER_BYTES) + "\n";
+		let bytes = encode_from_utf8(&text, "latin1").unwrap();
+		let (decoded, _) = decode_to_utf8(&bytes, "latin1").unwrap();
+		assert_eq!(String::from_utf8(decoded).unwrap(), text);
+	}
+
+	#[test]
+	fn test_encode_writer_splits_characters_across_writes() {
+		let mut writer = EncodeWriter::new(Vec::new(), resolve_encoding("latin1").unwrap()).unwrap();
+		let bytes = "aé".as_bytes();
+		writer.write_all(&bytes[..2]).unwrap();
+		writer.write_all(&bytes[2..]).unwrap();
+		assert_eq!(writer.finish().unwrap(), b"a\xe9");
+
+		let mut writer = EncodeWriter::new(Vec::new(), UTF_16BE).unwrap();
+		writer.write_all("é".as_bytes()).unwrap();
+		assert_eq!(writer.finish().unwrap(), b"\xfe\xff\x00\xe9");
+	}
+
+	#[test]
+	fn test_transcode_file_in_place() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("out.csv");
+		let text = "name\n".to_string() + &"café\n".repeat(DECODE_BUFFER_BYTES / 4);
+		std::fs::write(&path, &text).unwrap();
+		transcode_file(&path, "windows-1252").unwrap();
+		assert_eq!(std::fs::read(&path).unwrap(), encode_from_utf8(&text, "latin1").unwrap());
+		assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
+
+	#[test]
+	fn test_encode_unmappable_character() {
+		assert_eq!(encode_from_utf8("café", "latin1").unwrap(), b"caf\xe9");
+		let result = encode_from_utf8("日本", "windows-1252");
+		assert!(result.unwrap_err().to_string().contains("cannot be represented"));
+	}
+}
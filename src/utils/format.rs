@@ -1,6 +1,7 @@
 use datafusion::prelude::*;
 use datafusion::arrow::array::*;
 use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::util::display::array_value_to_string;
 use std::path::Path;
 use crate::error::NailResult;
 use crate::cli::OutputFormat;
@@ -47,6 +48,7 @@ pub async fn display_dataframe(
 					match path.extension().and_then(|s| s.to_str()) {
 						Some("json") => Some(FileFormat::Json),
 						Some("csv") => Some(FileFormat::Csv),
+						Some("tsv") | Some("tab") => Some(FileFormat::Tsv),
 						Some("parquet") => Some(FileFormat::Parquet),
 						Some("xlsx") => Some(FileFormat::Excel),
 						_ => Some(FileFormat::Parquet),
@@ -373,22 +375,9 @@ fn format_cell_value_with_field_color(column: &dyn Array, row_idx: usize, data_t
 					});
 				datetime.format("%Y-%m-%d").to_string()
 			},
-			DataType::Timestamp(_, _) => {
-				// Handle timestamp types
-				"timestamp".to_string() // Simplified for now
-			},
 			_ => {
-				// Fallback for other types - try to get a string representation
-				format!("{:?}", column.slice(row_idx, 1))
-					.lines()
-					.next()
-					.unwrap_or("unknown")
-					.trim_start_matches('[')
-					.trim_end_matches(']')
-					.trim_start_matches("\"")
-					.trim_end_matches("\"")
-					.trim()
-					.to_string()
+				// Fallback for other types (timestamps, small integers, decimals, ...)
+				array_value_to_string(column, row_idx).unwrap_or_else(|_| "unknown".to_string())
 			},
 		};
 		// Don't apply color here - it will be applied during wrapping
@@ -486,28 +475,54 @@ fn format_json_value(column: &dyn Array, row_idx: usize, data_type: &DataType) -
 					"\"1970-01-01\"".to_string()
 				}
 			},
-			DataType::Timestamp(_, _) => {
-				"\"timestamp\"".to_string()
+			DataType::Int8 | DataType::Int16 | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => {
+				array_value_to_string(column, row_idx).unwrap_or_else(|_| "0".to_string())
+			},
+			DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _) => {
+				let values = match data_type {
+					DataType::List(_) => column.as_list::<i32>().value(row_idx),
+					DataType::LargeList(_) => column.as_list::<i64>().value(row_idx),
+					_ => column.as_fixed_size_list().value(row_idx),
+				};
+				let items: Vec<String> = (0..values.len())
+					.map(|i| format_json_value(&values, i, values.data_type()))
+					.collect();
+				format!("[{}]", items.join(", "))
+			},
+			DataType::Struct(fields) => {
+				let children = column.as_struct();
+				let members: Vec<String> = fields.iter().zip(children.columns())
+					.map(|(field, child)| format!(
+						"{}: {}", json_string(field.name()), format_json_value(child, row_idx, field.data_type())
+					))
+					.collect();
+				format!("{{{}}}", members.join(", "))
+			},
+			DataType::Map(_, _) => {
+				let entries = column.as_map().value(row_idx);
+				let (keys, values) = (entries.column(0), entries.column(1));
+				let members: Vec<String> = (0..entries.len())
+					.map(|i| format!(
+						"{}: {}",
+						json_string(&array_value_to_string(keys, i).unwrap_or_default()),
+						format_json_value(values, i, values.data_type())
+					))
+					.collect();
+				format!("{{{}}}", members.join(", "))
 			},
 			_ => {
 				// Safe fallback for any other type
-				let debug_str = format!("{:?}", column.slice(row_idx, 1));
-				let val = debug_str
-					.lines()
-					.next()
-					.unwrap_or("unknown")
-					.trim_start_matches('[')
-					.trim_end_matches(']')
-					.trim_start_matches("\"")
-					.trim_end_matches("\"")
-					.trim()
-					.to_string();
+				let val = array_value_to_string(column, row_idx).unwrap_or_else(|_| "unknown".to_string());
 				format!("\"{}\"", val.replace("\"", "\\\""))
 			},
 		}
 	}
 }
 
+fn json_string(text: &str) -> String {
+	serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text))
+}
+
 pub fn is_correlation_matrix(df: &DataFrame) -> bool {
 	let schema = df.schema();
 	if let Some(first_field) = schema.fields().first() {
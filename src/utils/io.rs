@@ -1,29 +1,142 @@
 use datafusion::prelude::{SessionContext, CsvReadOptions as DataFusionCsvReadOptions, ParquetReadOptions, NdJsonReadOptions};
 use datafusion::dataframe::{DataFrame as DataFusionDataFrame, DataFrameWriteOptions};
-use std::path::Path;
+use datafusion::config::CsvOptions;
+use std::path::{Path, PathBuf};
+use crate::cli::BadRowPolicy;
 use crate::error::{NailError, NailResult};
-use crate::utils::{create_context, detect_file_format, FileFormat};
+use crate::utils::{create_context, detect_file_format, FileFormat, SessionOptions};
+use crate::utils::bad_rows::{read_csv_lenient, read_ndjson_lenient, SCHEMA_INFER_MAX_RECORDS};
+use crate::utils::encoding::decode_reader;
+use crate::utils::predicate::parse_predicate;
+use crate::utils::schema_file::load_schema_file;
 use datafusion::arrow::array::{Array, ArrayRef, StringArray, Float64Array, Int64Array, BooleanArray, Date32Array, Date64Array, RecordBatch};
-use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use calamine::{Reader, Xlsx, open_workbook, Data};
 use rust_xlsxwriter::{Workbook, Format};
 use std::sync::Arc;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufReader, Read, Write};
+use datafusion::datasource::streaming::StreamingTable;
+use datafusion::error::DataFusionError;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchReceiverStream;
+use datafusion::physical_plan::streaming::PartitionStream;
+use encoding_rs::Encoding;
+
+/// Options controlling how input files are parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+	pub on_bad_rows: BadRowPolicy,
+	pub rejects_file: Option<PathBuf>,
+	pub encoding: Option<String>,
+	pub delimiter: Option<u8>,
+	pub quote: Option<u8>,
+	pub no_header: bool,
+	pub schema_file: Option<PathBuf>,
+	pub session: SessionOptions,
+	pub verbose: bool,
+	/// Row condition applied as the file is read, so it reaches Parquet row group pruning.
+	pub where_clause: Option<String>,
+}
+
+/// Delimited text dialect resolved from the file format and read options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvDialect {
+	pub delimiter: u8,
+	pub quote: u8,
+	pub has_header: bool,
+}
+
+impl ReadOptions {
+	pub fn csv_dialect(&self, format: &FileFormat) -> CsvDialect {
+		CsvDialect {
+			delimiter: self.delimiter.unwrap_or_else(|| csv_delimiter(format)),
+			quote: self.quote.unwrap_or(b'"'),
+			has_header: !self.no_header,
+		}
+	}
+}
 
 pub async fn read_data(path: &Path) -> NailResult<DataFusionDataFrame> {
-	let ctx = create_context().await?;
+	read_data_with_options(path, &ReadOptions::default()).await
+}
+
+pub async fn read_data_with_options(path: &Path, options: &ReadOptions) -> NailResult<DataFusionDataFrame> {
+	let df = read_input(path, options).await?;
+	
+	match &options.where_clause {
+		Some(condition) => {
+			let schema = df.schema().clone().into();
+			let predicate = parse_predicate(condition, &schema)?;
+			if options.verbose {
+				eprintln!("Keeping rows where {}", condition);
+			}
+			Ok(df.filter(predicate)?)
+		},
+		None => Ok(df),
+	}
+}
+
+async fn read_input(path: &Path, options: &ReadOptions) -> NailResult<DataFusionDataFrame> {
+	let ctx = create_context(&options.session).await?;
 	let format = detect_file_format(path)?;
+	let lenient = options.on_bad_rows != BadRowPolicy::Fail;
+	let schema = match &options.schema_file {
+		Some(schema_path) => {
+			if matches!(format, FileFormat::Parquet | FileFormat::Excel) {
+				return Err(NailError::InvalidArgument(
+					"--schema only applies to CSV, TSV and JSON input".to_string()
+				));
+			}
+			Some(Arc::new(load_schema_file(schema_path)?))
+		},
+		None => None,
+	};
+	
+	match format {
+		FileFormat::Csv | FileFormat::Tsv if lenient || options.encoding.is_some() => {
+			let dialect = options.csv_dialect(&format);
+			let (input, used) = open_text_input(path, options.encoding.as_deref())?;
+			if let (Some(used), true) = (used, options.verbose) {
+				eprintln!("Decoding input as {}", used.name());
+			}
+			return if lenient {
+				read_csv_lenient(&ctx, path, input, &dialect, schema, options).await
+			} else {
+				read_decoded_csv(&ctx, path, input, &dialect, schema, options)
+			};
+		},
+		FileFormat::Json if lenient => {
+			return read_ndjson_lenient(&ctx, path, schema, options).await;
+		},
+		_ => {},
+	}
 	
 	let result = match format {
 		FileFormat::Parquet => {
 			ctx.read_parquet(path.to_str().unwrap(), ParquetReadOptions::default()).await
 		},
-		FileFormat::Csv => {
-			ctx.read_csv(path.to_str().unwrap(), DataFusionCsvReadOptions::default()).await
+		FileFormat::Csv | FileFormat::Tsv => {
+			let dialect = options.csv_dialect(&format);
+			let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("csv");
+			let file_extension = format!(".{}", extension);
+			let mut csv_options = DataFusionCsvReadOptions::default()
+				.delimiter(dialect.delimiter)
+				.quote(dialect.quote)
+				.has_header(dialect.has_header)
+				.file_extension(&file_extension);
+			if let Some(schema) = &schema {
+				csv_options = csv_options.schema(schema);
+			}
+			ctx.read_csv(path.to_str().unwrap(), csv_options).await
 		},
 		FileFormat::Json => {
-			ctx.read_json(path.to_str().unwrap(), NdJsonReadOptions::default()).await
+			let mut json_options = NdJsonReadOptions::default();
+			if let Some(schema) = &schema {
+				json_options = json_options.schema(schema);
+			}
+			ctx.read_json(path.to_str().unwrap(), json_options).await
 		},
 		FileFormat::Excel => {
 			read_excel_file(path, &ctx).await
@@ -33,6 +146,109 @@ pub async fn read_data(path: &Path) -> NailResult<DataFusionDataFrame> {
 	result.map_err(NailError::DataFusion)
 }
 
+pub(crate) fn csv_delimiter(format: &FileFormat) -> u8 {
+	match format {
+		FileFormat::Tsv => b'\t',
+		_ => b',',
+	}
+}
+
+/// Opens a text input file for streaming, transcoding it to UTF-8 as it is read when an
+/// input encoding is given. Returns the encoding in use, if any.
+pub(crate) fn open_text_input(path: &Path, encoding: Option<&str>) -> NailResult<(Box<dyn Read + Send>, Option<&'static Encoding>)> {
+	let file = BufReader::new(File::open(path)?);
+	match encoding {
+		Some(label) => {
+			let (reader, used) = decode_reader(file, label)?;
+			Ok((Box::new(reader), Some(used)))
+		},
+		None => Ok((Box::new(file), None)),
+	}
+}
+
+/// Infers a CSV schema from the leading records, naming columns `column_N` when there is no header.
+pub(crate) fn infer_csv_schema(input: impl Read, dialect: &CsvDialect) -> NailResult<Schema> {
+	let (schema, _) = arrow::csv::reader::Format::default()
+		.with_header(dialect.has_header)
+		.with_delimiter(dialect.delimiter)
+		.with_quote(dialect.quote)
+		.infer_schema(input, Some(SCHEMA_INFER_MAX_RECORDS))?;
+	Ok(schema)
+}
+
+/// Reads transcoded CSV lazily: the schema comes from the leading records of `input` and
+/// every execution streams the file again, so it is never held in memory as text.
+fn read_decoded_csv(
+	ctx: &SessionContext,
+	path: &Path,
+	input: Box<dyn Read + Send>,
+	dialect: &CsvDialect,
+	schema: Option<SchemaRef>,
+	options: &ReadOptions,
+) -> NailResult<DataFusionDataFrame> {
+	let schema = match schema {
+		Some(schema) => schema,
+		None => Arc::new(infer_csv_schema(input, dialect)?),
+	};
+	let partition = DecodedCsvPartition {
+		schema: schema.clone(),
+		path: path.to_path_buf(),
+		encoding: options.encoding.clone(),
+		dialect: dialect.clone(),
+	};
+	let table = StreamingTable::try_new(schema, vec![Arc::new(partition)])?;
+	Ok(ctx.read_table(Arc::new(table))?)
+}
+
+struct DecodedCsvPartition {
+	schema: SchemaRef,
+	path: PathBuf,
+	encoding: Option<String>,
+	dialect: CsvDialect,
+}
+
+impl PartitionStream for DecodedCsvPartition {
+	fn schema(&self) -> &SchemaRef {
+		&self.schema
+	}
+
+	fn execute(&self, ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+		let mut builder = RecordBatchReceiverStream::builder(self.schema.clone(), 2);
+		let tx = builder.tx();
+		let schema = self.schema.clone();
+		let path = self.path.clone();
+		let encoding = self.encoding.clone();
+		let dialect = self.dialect.clone();
+		let batch_size = ctx.session_config().batch_size();
+		builder.spawn_blocking(move || {
+			let (input, _) = open_text_input(&path, encoding.as_deref())
+				.map_err(|e| DataFusionError::External(Box::new(e)))?;
+			let reader = arrow::csv::ReaderBuilder::new(schema)
+				.with_header(dialect.has_header)
+				.with_delimiter(dialect.delimiter)
+				.with_quote(dialect.quote)
+				.with_batch_size(batch_size)
+				.build(input)?;
+			for batch in reader {
+				let failed = batch.is_err();
+				// The receiver is gone once the query has what it needs
+				if tx.blocking_send(batch.map_err(DataFusionError::from)).is_err() || failed {
+					break;
+				}
+			}
+			Ok(())
+		});
+		builder.build()
+	}
+}
+
+pub(crate) fn dataframe_from_batches(ctx: &SessionContext, schema: SchemaRef, batches: Vec<RecordBatch>) -> NailResult<DataFusionDataFrame> {
+	if batches.is_empty() {
+		return Ok(ctx.read_batch(RecordBatch::new_empty(schema))?);
+	}
+	Ok(ctx.read_batches(batches)?)
+}
+
 async fn read_excel_file(path: &Path, ctx: &SessionContext) -> Result<DataFusionDataFrame, datafusion::error::DataFusionError> {
 	let mut workbook: Xlsx<_> = open_workbook(path)
 		.map_err(|e| datafusion::error::DataFusionError::External(Box::new(e)))?;
@@ -183,18 +399,26 @@ pub async fn write_data(df: &DataFusionDataFrame, path: &Path, format: Option<&F
 				).await.map_err(NailError::DataFusion)?;
 			}
 		},
-		FileFormat::Csv => {
+		FileFormat::Csv | FileFormat::Tsv => {
+			if let Some(field) = df.schema().fields().iter().find(|f| f.data_type().is_nested()) {
+				return Err(NailError::InvalidArgument(format!(
+					"Column '{}' holds nested values and cannot be written as CSV. Write Parquet or JSON, or use 'nail flatten' or 'nail explode' first",
+					field.name()
+				)));
+			}
+			let delimiter = csv_delimiter(&output_format);
 			// Check if DataFrame is empty by collecting batches and checking row count
 			let batches = df.clone().collect().await.map_err(NailError::DataFusion)?;
 			let row_count: usize = batches.iter().map(|batch| batch.num_rows()).sum();
 			if row_count == 0 {
 				// Create empty CSV file with headers
-				write_empty_csv_file(df, path).await?;
+				write_empty_csv_file(df, path, delimiter).await?;
 			} else {
+				let csv_options = CsvOptions::default().with_delimiter(delimiter);
 				df.clone().write_csv(
 					path.to_str().unwrap(),
 					DataFrameWriteOptions::new(),
-					None,
+					Some(csv_options),
 				).await.map_err(NailError::DataFusion)?;
 			}
 		},
@@ -321,7 +545,7 @@ async fn write_excel_file(df: &DataFusionDataFrame, path: &Path) -> Result<(), d
 	Ok(())
 }
 
-async fn write_empty_csv_file(df: &DataFusionDataFrame, path: &Path) -> NailResult<()> {
+async fn write_empty_csv_file(df: &DataFusionDataFrame, path: &Path, delimiter: u8) -> NailResult<()> {
 	let mut file = File::create(path)
 		.map_err(|e| NailError::Io(e))?;
 	
@@ -331,7 +555,7 @@ async fn write_empty_csv_file(df: &DataFusionDataFrame, path: &Path) -> NailResu
 		.map(|f| f.name().clone())
 		.collect();
 	
-	writeln!(file, "{}", header.join(","))
+	writeln!(file, "{}", header.join(&(delimiter as char).to_string()))
 		.map_err(|e| NailError::Io(e))?;
 	
 	Ok(())
@@ -347,20 +571,7 @@ pub(crate) async fn write_empty_parquet_file(df: &DataFusionDataFrame, path: &Pa
 	
 	// Create an empty RecordBatch with the same schema
 	let empty_arrays: Vec<std::sync::Arc<dyn arrow::array::Array>> = arrow_schema.fields().iter()
-		.map(|field| {
-			use arrow::array::*;
-			use arrow::datatypes::DataType;
-			
-			match field.data_type() {
-				DataType::Int64 => std::sync::Arc::new(Int64Array::from(Vec::<i64>::new())) as std::sync::Arc<dyn arrow::array::Array>,
-				DataType::Int32 => std::sync::Arc::new(Int32Array::from(Vec::<i32>::new())),
-				DataType::Float64 => std::sync::Arc::new(Float64Array::from(Vec::<f64>::new())),
-				DataType::Float32 => std::sync::Arc::new(Float32Array::from(Vec::<f32>::new())),
-				DataType::Boolean => std::sync::Arc::new(BooleanArray::from(Vec::<bool>::new())),
-				DataType::Utf8 => std::sync::Arc::new(StringArray::from(Vec::<String>::new())),
-				_ => std::sync::Arc::new(StringArray::from(Vec::<String>::new())), // Default to string for other types
-			}
-		})
+		.map(|field| arrow::array::new_empty_array(field.data_type()))
 		.collect();
 	
 	let empty_batch = arrow::record_batch::RecordBatch::try_new(
@@ -1,53 +1,144 @@
 pub mod io;
 pub mod format;
-pub mod stats;
 pub mod parquet_utils;
 pub mod output;
 pub mod column;
+pub mod bad_rows;
+pub mod encoding;
+pub mod schema_file;
+pub mod config;
+pub mod predicate;
+pub mod datetime;
 
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::memory_pool::FairSpillPool;
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use crate::error::{NailError, NailResult};
 
+/// The process-wide runtime and the options it was built from.
+static RUNTIME_ENV: OnceLock<(RuntimeOptions, Arc<RuntimeEnv>)> = OnceLock::new();
 
-pub async fn create_context() -> NailResult<SessionContext> {
-	let cpu_count = num_cpus::get();
-	let target_partitions = std::cmp::max(1, cpu_count);
-	
-	let config = SessionConfig::new()
-		.with_batch_size(32768)  // Increased for better throughput
-		.with_target_partitions(target_partitions)
-		.with_collect_statistics(false)  // Disable stats collection for faster reads
-		.with_parquet_pruning(true)  // Enable predicate pushdown
-		.with_repartition_joins(false)  // Disable for small operations
-		.with_repartition_aggregations(false)  // Disable for small operations
-		.with_prefer_existing_sort(true);  // Use existing sort orders
+/// Memory and spill settings shared by every DataFusion context in the process.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuntimeOptions {
+	pub memory_limit: Option<usize>,
+	pub temp_dir: Option<PathBuf>,
+}
+
+/// Builds the process-wide runtime. With a memory limit, sorts, aggregations and
+/// joins share a fair-spill pool and spill to the temp directory instead of growing unbounded.
+/// The runtime can be configured once; configuring it again with different options fails.
+pub fn configure_runtime(options: &RuntimeOptions) -> NailResult<()> {
+	if let Some((current, _)) = RUNTIME_ENV.get() {
+		return check_runtime_options(current, options);
+	}
+
+	if let Some(dir) = &options.temp_dir {
+		if !dir.is_dir() {
+			return Err(NailError::InvalidArgument(
+				format!("Temp directory does not exist: {}", dir.display())
+			));
+		}
+	}
 	
-	let ctx = SessionContext::new_with_config(config);
+	let mut config = RuntimeConfig::new();
+	if let Some(limit) = options.memory_limit {
+		config = config.with_memory_pool(Arc::new(FairSpillPool::new(limit)));
+	}
+	if let Some(dir) = &options.temp_dir {
+		config = config.with_disk_manager(DiskManagerConfig::new_specified(vec![dir.clone()]));
+	}
 	
-	// Register optimizations for better performance
-	Ok(ctx)
+	let runtime = Arc::new(RuntimeEnv::new(config)?);
+	// Another thread may have set the runtime since the check above
+	if let Err((options, _)) = RUNTIME_ENV.set((options.clone(), runtime)) {
+		let (current, _) = RUNTIME_ENV.get().expect("runtime was just set");
+		return check_runtime_options(current, &options);
+	}
+	Ok(())
 }
 
-pub async fn create_context_with_jobs(jobs: Option<usize>) -> NailResult<SessionContext> {
-	let cpu_count = num_cpus::get();
-	let target_partitions = if let Some(j) = jobs {
-		std::cmp::max(1, std::cmp::min(j, cpu_count))
-	} else {
-		std::cmp::max(1, cpu_count / 2)
-	};
-	
+fn check_runtime_options(current: &RuntimeOptions, requested: &RuntimeOptions) -> NailResult<()> {
+	if current != requested {
+		return Err(NailError::InvalidArgument(format!(
+			"The runtime is already configured with {:?}; --memory-limit and --temp-dir cannot be changed to {:?} within one process",
+			current, requested
+		)));
+	}
+	Ok(())
+}
+
+fn runtime_env() -> Arc<RuntimeEnv> {
+	RUNTIME_ENV.get_or_init(|| (RuntimeOptions::default(), Arc::new(RuntimeEnv::default()))).1.clone()
+}
+
+
+pub const DEFAULT_BATCH_SIZE: usize = 8192;
+
+/// Session settings built from the command line and shared by reading, processing and writing.
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+	pub jobs: Option<usize>,
+	pub batch_size: usize,
+	pub collect_statistics: bool,
+	pub parquet_pruning: bool,
+}
+
+impl Default for SessionOptions {
+	fn default() -> Self {
+		Self {
+			jobs: None,
+			batch_size: DEFAULT_BATCH_SIZE,
+			collect_statistics: false,  // Skip stats collection for faster reads
+			parquet_pruning: true,  // Enable predicate pushdown
+		}
+	}
+}
+
+impl SessionOptions {
+	/// Requested jobs capped at the CPU count, or half of the cores by default.
+	pub fn target_partitions(&self) -> usize {
+		let cpu_count = num_cpus::get();
+		match self.jobs {
+			Some(j) => std::cmp::max(1, std::cmp::min(j, cpu_count)),
+			None => std::cmp::max(1, cpu_count / 2),
+		}
+	}
+}
+
+pub async fn create_context(options: &SessionOptions) -> NailResult<SessionContext> {
 	let config = SessionConfig::new()
-		.with_batch_size(8192)
-		.with_target_partitions(target_partitions);
+		.with_batch_size(options.batch_size)
+		.with_target_partitions(options.target_partitions())
+		.with_collect_statistics(options.collect_statistics)
+		.with_parquet_pruning(options.parquet_pruning)
+		.with_prefer_existing_sort(true);  // Use existing sort orders
 	
-	Ok(SessionContext::new_with_config(config))
+	Ok(SessionContext::new_with_config_rt(config, runtime_env()))
+}
+
+/// Returns a context on the session `df` was planned in, for frames built next to it.
+pub fn session_context(df: &DataFrame) -> SessionContext {
+	SessionContext::new_with_state(df.clone().into_parts().0)
+}
+
+/// Collects `df` and returns a context on the session it was planned in, so frames rebuilt
+/// from the transformed batches keep its settings (jobs, batch size, memory limit).
+pub async fn collect_in_session(df: DataFrame) -> NailResult<(SessionContext, Vec<RecordBatch>)> {
+	let (state, plan) = df.into_parts();
+	let batches = DataFrame::new(state.clone(), plan).collect().await?;
+	Ok((SessionContext::new_with_state(state), batches))
 }
 
 pub fn detect_file_format(path: &Path) -> NailResult<FileFormat> {
 	match path.extension().and_then(|s| s.to_str()) {
 		Some("parquet") => Ok(FileFormat::Parquet),
 		Some("csv") => Ok(FileFormat::Csv),
+		Some("tsv") | Some("tab") => Ok(FileFormat::Tsv),
 		Some("json") => Ok(FileFormat::Json),
 		Some("xlsx") => Ok(FileFormat::Excel),
 		_ => Err(NailError::UnsupportedFormat(
@@ -60,6 +151,22 @@ pub fn detect_file_format(path: &Path) -> NailResult<FileFormat> {
 pub enum FileFormat {
 	Parquet,
 	Csv,
+	Tsv,
 	Json,
 	Excel,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_runtime_cannot_be_reconfigured() {
+		// Other tests create contexts with the default runtime, so it may already be set
+		configure_runtime(&RuntimeOptions::default()).unwrap();
+		configure_runtime(&RuntimeOptions::default()).unwrap();
+
+		let limited = RuntimeOptions { memory_limit: Some(1 << 20), temp_dir: None };
+		let error = configure_runtime(&limited).unwrap_err().to_string();
+		assert!(error.contains("already configured"), "{}", error);
+	}
+}
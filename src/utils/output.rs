@@ -1,7 +1,8 @@
+use datafusion::arrow::array::{Array, StringArray};
 use datafusion::prelude::DataFrame;
-use crate::error::NailResult;
+use crate::error::{NailError, NailResult};
 use crate::cli::{CommonArgs, OutputFormat};
-use crate::utils::{format::display_dataframe, io::write_data, FileFormat};
+use crate::utils::{detect_file_format, encoding::transcode_file, format::display_dataframe, io::write_data, FileFormat};
 
 pub struct OutputHandler<'a> {
     common_args: &'a CommonArgs,
@@ -15,10 +16,26 @@ impl<'a> OutputHandler<'a> {
     pub async fn handle_output(&self, df: &DataFrame, operation_name: &str) -> NailResult<()> {
         self.common_args.log_if_verbose(&format!("Completing {} operation", operation_name));
 
+        if self.common_args.explain_requested() {
+            return explain_plan(df, self.common_args.explain_analyze).await;
+        }
+
         match &self.common_args.output {
             Some(output_path) => {
                 let file_format = self.map_output_format(&self.common_args.format);
+                if self.common_args.output_encoding.is_some() {
+                    let written_format = file_format.clone().or_else(|| detect_file_format(output_path).ok());
+                    if !matches!(written_format, Some(FileFormat::Csv) | Some(FileFormat::Tsv)) {
+                        return Err(NailError::InvalidArgument(
+                            "--output-encoding only applies to CSV and TSV output files".to_string()
+                        ));
+                    }
+                }
                 write_data(df, output_path, file_format.as_ref()).await?;
+                if let Some(encoding) = &self.common_args.output_encoding {
+                    transcode_file(output_path, encoding)?;
+                    self.common_args.log_if_verbose(&format!("Output encoded as {}", encoding));
+                }
                 self.common_args.log_if_verbose(&format!("Output written to: {}", output_path.display()));
             }
             None => {
@@ -39,4 +56,27 @@ impl<'a> OutputHandler<'a> {
             Some(OutputFormat::Text) | None => None,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Prints the plans of `df`. With `analyze`, the plan is executed and each operator
+/// is annotated with its metrics (output rows, elapsed time, spills, pruned row groups).
+pub async fn explain_plan(df: &DataFrame, analyze: bool) -> NailResult<()> {
+    let batches = df.clone().explain(false, analyze)?.collect().await?;
+
+    for batch in &batches {
+        let plan_types = batch.column(0).as_any().downcast_ref::<StringArray>();
+        let plans = batch.column(1).as_any().downcast_ref::<StringArray>();
+        if let (Some(plan_types), Some(plans)) = (plan_types, plans) {
+            for i in 0..batch.num_rows() {
+                if plan_types.is_null(i) || plans.is_null(i) {
+                    continue;
+                }
+                println!("== {} ==", plan_types.value(i));
+                println!("{}", plans.value(i).trim_end());
+                println!();
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,762 @@
+//! Row predicate language used by `filter -c`, `drop -r` and the global `--where` option.
+//!
+//! ```text
+//! expr      := or
+//! or        := and (("or" | "||") and)*
+//! and       := not (("and" | "&&" | ",") not)*
+//! not       := ("not" | "!") not | "(" expr ")" | predicate
+//! predicate := operand (("=" | "==" | "!=" | "<>" | "<" | "<=" | ">" | ">=") operand
+//!            | ("~" | "~*" | "!~" | "!~*") 'regex'
+//!            | ["not"] "in" "(" operand ("," operand)* ")"
+//!            | ["not"] "between" operand "and" operand
+//!            | ["not"] ("like" | "ilike") 'pattern'
+//!            | "is" ["not"] "null")?
+//! ```
+//!
+//! A bare word on the left of a predicate is a column name (case-insensitive when
+//! unambiguous); `"name"` or `` `name` `` quotes a column name exactly. Values are
+//! `'single-quoted'` strings, numbers, `true`, `false`, `null`, or bare words, so the
+//! older `age>25,status=active` form still parses. Literals are converted to the type
+//! of the column they are compared with, so `zip='02134'` stays a string and
+//! `score>'80'` compares numerically.
+//!
+//! Date and timestamp columns take ISO dates and times (`2024-03-01`, `2024-03-01 12:30`,
+//! `2024-03-01T12:30:00Z`) or relative times: `now`, `today`, `yesterday` or `tomorrow`,
+//! optionally shifted by amounts such as `- 7d`, `+ 2h30m` or `- 1mo` (units `s`, `m`,
+//! `h`, `d`, `w`, `mo`, `y`). Times without an offset are local time.
+
+use chrono::{DateTime, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone};
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::{Column, DFSchema, DFSchemaRef};
+use datafusion::logical_expr::{BinaryExpr, Operator};
+use datafusion::prelude::*;
+use datafusion::scalar::ScalarValue;
+use regex::Regex;
+use std::sync::LazyLock;
+use crate::error::{NailError, NailResult};
+use crate::utils::column::resolve_column_name;
+
+/// Parses `input` into a boolean expression over the columns of `schema`.
+pub fn parse_predicate(input: &str, schema: &DFSchemaRef) -> NailResult<Expr> {
+	let tokens = tokenize(input).map_err(|e| e.into_error(input))?;
+	let mut parser = Parser { tokens, index: 0, schema };
+	let expr = parser.parse_expression().map_err(|e| e.into_error(input))?;
+	Ok(expr)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	Word(String),
+	Str(String),
+	Ident(String),
+	Op(&'static str),
+	LParen,
+	RParen,
+	Comma,
+	End,
+}
+
+impl Token {
+	fn describe(&self) -> String {
+		match self {
+			Token::Word(word) => format!("'{}'", word),
+			Token::Str(text) => format!("string '{}'", text),
+			Token::Ident(name) => format!("column \"{}\"", name),
+			Token::Op(op) => format!("'{}'", op),
+			Token::LParen => "'('".to_string(),
+			Token::RParen => "')'".to_string(),
+			Token::Comma => "','".to_string(),
+			Token::End => "end of expression".to_string(),
+		}
+	}
+
+	fn is_keyword(&self, keyword: &str) -> bool {
+		matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+	}
+}
+
+/// Operators, longest first so `>=` wins over `>`.
+const OPERATORS: &[&str] = &["!~*", "==", "!=", "<>", "<=", ">=", "~*", "!~", "&&", "||", "=", "<", ">", "~", "!"];
+
+const KEYWORDS: &[&str] = &["and", "or", "not", "in", "between", "is", "like", "ilike"];
+
+fn is_word_char(c: char) -> bool {
+	!c.is_whitespace() && !"=!<>(),'\"`~&|".contains(c)
+}
+
+#[derive(Debug)]
+struct ParseError {
+	position: usize,
+	message: String,
+	/// Unknown columns keep their error kind so exit codes and hints still apply.
+	missing_column: bool,
+}
+
+impl ParseError {
+	fn new(position: usize, message: impl Into<String>) -> Self {
+		ParseError { position, message: message.into(), missing_column: false }
+	}
+
+	fn into_error(self, input: &str) -> NailError {
+		let column = input[..self.position.min(input.len())].chars().count();
+		let message = format!(
+			"Invalid expression at position {}: {}\n  {}\n  {}^",
+			column + 1, self.message, input, " ".repeat(column)
+		);
+		if self.missing_column {
+			NailError::ColumnNotFound(message)
+		} else {
+			NailError::InvalidArgument(message)
+		}
+	}
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+	let mut tokens = Vec::new();
+	let mut chars = input.char_indices().peekable();
+
+	while let Some(&(start, c)) = chars.peek() {
+		if c.is_whitespace() {
+			chars.next();
+			continue;
+		}
+		let token = match c {
+			'(' => { chars.next(); Token::LParen },
+			')' => { chars.next(); Token::RParen },
+			',' => { chars.next(); Token::Comma },
+			'\'' | '"' | '`' => {
+				chars.next();
+				let mut text = String::new();
+				loop {
+					match chars.next() {
+						Some((_, ch)) if ch == c => {
+							// A doubled quote is an escaped quote
+							if matches!(chars.peek(), Some(&(_, next)) if next == c) {
+								chars.next();
+								text.push(c);
+							} else {
+								break;
+							}
+						},
+						Some((_, ch)) => text.push(ch),
+						None => return Err(ParseError::new(start, format!("unterminated {} quote", c))),
+					}
+				}
+				if c == '\'' { Token::Str(text) } else { Token::Ident(text) }
+			},
+			_ if is_word_char(c) => {
+				let mut end = start;
+				while let Some(&(i, ch)) = chars.peek() {
+					if !is_word_char(ch) {
+						break;
+					}
+					end = i + ch.len_utf8();
+					chars.next();
+				}
+				Token::Word(input[start..end].to_string())
+			},
+			_ => {
+				let rest = &input[start..];
+				let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) else {
+					return Err(ParseError::new(start, format!("unexpected character '{}'", c)));
+				};
+				for _ in 0..op.len() {
+					chars.next();
+				}
+				Token::Op(op)
+			},
+		};
+		tokens.push((token, start));
+	}
+
+	tokens.push((Token::End, input.len()));
+	Ok(tokens)
+}
+
+/// One side of a predicate before it is turned into an expression.
+#[derive(Debug, Clone)]
+enum Operand {
+	Column { name: String, data_type: DataType },
+	/// `None` is `null`; `quoted` literals are never read as numbers or booleans.
+	Literal { text: Option<String>, quoted: bool, position: usize },
+}
+
+struct Parser<'a> {
+	tokens: Vec<(Token, usize)>,
+	index: usize,
+	schema: &'a DFSchemaRef,
+}
+
+impl Parser<'_> {
+	fn peek(&self) -> &Token {
+		&self.tokens[self.index].0
+	}
+
+	fn position(&self) -> usize {
+		self.tokens[self.index].1
+	}
+
+	fn next(&mut self) -> (Token, usize) {
+		let token = self.tokens[self.index].clone();
+		if self.index < self.tokens.len() - 1 {
+			self.index += 1;
+		}
+		token
+	}
+
+	fn eat_keyword(&mut self, keyword: &str) -> bool {
+		if self.peek().is_keyword(keyword) {
+			self.next();
+			true
+		} else {
+			false
+		}
+	}
+
+	fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+		if self.eat_keyword(keyword) {
+			Ok(())
+		} else {
+			Err(self.unexpected(&format!("'{}'", keyword)))
+		}
+	}
+
+	fn unexpected(&self, expected: &str) -> ParseError {
+		ParseError::new(self.position(), format!("expected {}, found {}", expected, self.peek().describe()))
+	}
+
+	fn parse_expression(&mut self) -> Result<Expr, ParseError> {
+		if *self.peek() == Token::End {
+			return Err(ParseError::new(0, "empty expression"));
+		}
+		let expr = self.parse_or()?;
+		match self.peek() {
+			Token::End => Ok(expr),
+			Token::RParen => Err(ParseError::new(self.position(), "unmatched ')'")),
+			_ => Err(self.unexpected("'and', 'or' or end of expression")),
+		}
+	}
+
+	fn parse_or(&mut self) -> Result<Expr, ParseError> {
+		let mut expr = self.parse_and()?;
+		while self.peek().is_keyword("or") || *self.peek() == Token::Op("||") {
+			self.next();
+			expr = expr.or(self.parse_and()?);
+		}
+		Ok(expr)
+	}
+
+	fn parse_and(&mut self) -> Result<Expr, ParseError> {
+		let mut expr = self.parse_not()?;
+		while self.peek().is_keyword("and") || matches!(self.peek(), Token::Op("&&") | Token::Comma) {
+			self.next();
+			expr = expr.and(self.parse_not()?);
+		}
+		Ok(expr)
+	}
+
+	fn parse_not(&mut self) -> Result<Expr, ParseError> {
+		if self.peek().is_keyword("not") || *self.peek() == Token::Op("!") {
+			self.next();
+			return Ok(self.parse_not()?.not());
+		}
+		if *self.peek() == Token::LParen {
+			let open = self.position();
+			self.next();
+			let expr = self.parse_or()?;
+			if *self.peek() != Token::RParen {
+				return Err(ParseError::new(open, format!("unclosed '(' (found {})", self.peek().describe())));
+			}
+			self.next();
+			return Ok(expr);
+		}
+		self.parse_predicate()
+	}
+
+	fn parse_predicate(&mut self) -> Result<Expr, ParseError> {
+		let start = self.position();
+		let left = self.parse_operand(true)?;
+
+		let negated = if self.peek().is_keyword("not") {
+			let (token, position) = self.next();
+			if !["in", "between", "like", "ilike"].iter().any(|k| self.peek().is_keyword(k)) {
+				return Err(ParseError::new(position, format!("{} must be followed by 'in', 'between', 'like' or 'ilike'", token.describe())));
+			}
+			true
+		} else {
+			false
+		};
+
+		let before = self.index;
+		let (token, position) = self.next();
+		match token {
+			Token::Op(op @ ("=" | "==" | "!=" | "<>" | "<" | "<=" | ">" | ">=")) => {
+				let right = self.parse_operand(false)?;
+				comparison(left, op, right)
+			},
+			Token::Op(op @ ("~" | "~*" | "!~" | "!~*")) => {
+				let pattern_position = self.position();
+				let pattern = self.parse_pattern()?;
+				validate_regex(&pattern, pattern_position)?;
+				let operator = match op {
+					"~" => Operator::RegexMatch,
+					"~*" => Operator::RegexIMatch,
+					"!~" => Operator::RegexNotMatch,
+					_ => Operator::RegexNotIMatch,
+				};
+				Ok(Expr::BinaryExpr(BinaryExpr::new(Box::new(as_text(left)?), operator, Box::new(lit(pattern)))))
+			},
+			Token::Word(word) if word.eq_ignore_ascii_case("in") => {
+				if *self.peek() != Token::LParen {
+					return Err(self.unexpected("'(' after 'in'"));
+				}
+				self.next();
+				let target = column_type(&left);
+				let mut list = vec![operand_expr(self.parse_operand(false)?, target.as_ref())?];
+				while *self.peek() == Token::Comma {
+					self.next();
+					list.push(operand_expr(self.parse_operand(false)?, target.as_ref())?);
+				}
+				if *self.peek() != Token::RParen {
+					return Err(self.unexpected("',' or ')' in the 'in' list"));
+				}
+				self.next();
+				Ok(operand_expr(left, None)?.in_list(list, negated))
+			},
+			Token::Word(word) if word.eq_ignore_ascii_case("between") => {
+				let low = self.parse_operand(false)?;
+				self.expect_keyword("and")?;
+				let high = self.parse_operand(false)?;
+				let target = column_type(&left);
+				let low = operand_expr(low, target.as_ref())?;
+				let high = operand_expr(high, target.as_ref())?;
+				let expr = operand_expr(left, None)?;
+				Ok(if negated { expr.not_between(low, high) } else { expr.between(low, high) })
+			},
+			Token::Word(word) if word.eq_ignore_ascii_case("like") || word.eq_ignore_ascii_case("ilike") => {
+				let pattern = lit(self.parse_pattern()?);
+				let expr = as_text(left)?;
+				Ok(match (word.eq_ignore_ascii_case("ilike"), negated) {
+					(false, false) => expr.like(pattern),
+					(false, true) => expr.not_like(pattern),
+					(true, false) => expr.ilike(pattern),
+					(true, true) => expr.not_ilike(pattern),
+				})
+			},
+			Token::Word(word) if word.eq_ignore_ascii_case("is") => {
+				let not = self.eat_keyword("not");
+				self.expect_keyword("null")?;
+				let expr = operand_expr(left, None)?;
+				Ok(if not { expr.is_not_null() } else { expr.is_null() })
+			},
+			token => {
+				// A boolean column or literal on its own is a predicate too
+				self.index = before;
+				match &left {
+					Operand::Column { data_type: DataType::Boolean, .. } => operand_expr(left, None),
+					Operand::Literal { text: Some(text), quoted: false, .. } if parse_bool(text).is_some() => {
+						operand_expr(left, Some(&DataType::Boolean))
+					},
+					_ if matches!(token, Token::End | Token::RParen | Token::Comma) || token.is_keyword("and") || token.is_keyword("or") => {
+						Err(ParseError::new(start, "expected a comparison such as 'column = value'"))
+					},
+					_ => Err(ParseError::new(position, format!("expected an operator, found {}", token.describe()))),
+				}
+			},
+		}
+	}
+
+	/// Parses a column or literal. Bare words name columns on the left of a predicate
+	/// and are values elsewhere; consecutive bare words form one value (`city=New York`).
+	fn parse_operand(&mut self, left: bool) -> Result<Operand, ParseError> {
+		let (token, position) = self.next();
+		match token {
+			Token::Ident(name) => self.column(&format!("\"{}\"", name.replace('"', "\"\"")), position),
+			Token::Str(text) => Ok(Operand::Literal { text: Some(text), quoted: true, position }),
+			Token::Word(word) if word.eq_ignore_ascii_case("null") => Ok(Operand::Literal { text: None, quoted: false, position }),
+			Token::Word(word) if KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k)) => {
+				Err(ParseError::new(position, format!("expected a column or value, found keyword '{}'", word)))
+			},
+			Token::Word(word) if left && word.parse::<f64>().is_err() && parse_bool(&word).is_none() => self.column(&word, position),
+			Token::Word(word) => {
+				let mut text = word;
+				if !left {
+					while let Token::Word(next) = self.peek() {
+						if KEYWORDS.iter().any(|k| next.eq_ignore_ascii_case(k)) {
+							break;
+						}
+						text.push(' ');
+						text.push_str(next);
+						self.next();
+					}
+				}
+				Ok(Operand::Literal { text: Some(text), quoted: false, position })
+			},
+			token => Err(ParseError::new(position, format!("expected a column or value, found {}", token.describe()))),
+		}
+	}
+
+	fn parse_pattern(&mut self) -> Result<String, ParseError> {
+		match self.next() {
+			(Token::Str(text), _) | (Token::Word(text), _) => Ok(text),
+			(token, position) => Err(ParseError::new(position, format!("expected a quoted pattern, found {}", token.describe()))),
+		}
+	}
+
+	fn column(&self, name: &str, position: usize) -> Result<Operand, ParseError> {
+		let name = resolve_column_name(self.schema, name).map_err(|e| ParseError {
+			missing_column: matches!(e, NailError::ColumnNotFound(_)),
+			..ParseError::new(position, e.to_string())
+		})?;
+		let data_type = field_type(self.schema, &name);
+		Ok(Operand::Column { name, data_type })
+	}
+}
+
+fn field_type(schema: &DFSchema, name: &str) -> DataType {
+	schema.field_with_unqualified_name(name)
+		.map(|field| field.data_type().clone())
+		.unwrap_or(DataType::Null)
+}
+
+fn column_type(operand: &Operand) -> Option<DataType> {
+	match operand {
+		Operand::Column { data_type, .. } => Some(data_type.clone()),
+		Operand::Literal { .. } => None,
+	}
+}
+
+fn comparison(left: Operand, op: &str, right: Operand) -> Result<Expr, ParseError> {
+	// `= null` means "is null" here; SQL's always-unknown comparison is never what is meant
+	let null_side = match (&left, &right) {
+		(_, Operand::Literal { text: None, position, .. }) => Some((left.clone(), *position)),
+		(Operand::Literal { text: None, position, .. }, _) => Some((right.clone(), *position)),
+		_ => None,
+	};
+	if let Some((other, position)) = null_side {
+		let expr = operand_expr(other, None)?;
+		return match op {
+			"=" | "==" => Ok(expr.is_null()),
+			"!=" | "<>" => Ok(expr.is_not_null()),
+			_ => Err(ParseError::new(position, format!("null cannot be compared with '{}'; use 'is null'", op))),
+		};
+	}
+
+	let left_type = column_type(&left);
+	let right_type = column_type(&right);
+	let left = operand_expr(left, right_type.as_ref())?;
+	let right = operand_expr(right, left_type.as_ref())?;
+	Ok(match op {
+		"=" | "==" => left.eq(right),
+		"!=" | "<>" => left.not_eq(right),
+		"<" => left.lt(right),
+		"<=" => left.lt_eq(right),
+		">" => left.gt(right),
+		_ => left.gt_eq(right),
+	})
+}
+
+/// Regex and LIKE patterns match against the text form of non-string columns.
+fn as_text(operand: Operand) -> Result<Expr, ParseError> {
+	let text_column = matches!(&operand, Operand::Column { data_type, .. } if is_string(data_type));
+	let expr = operand_expr(operand, Some(&DataType::Utf8))?;
+	Ok(if text_column { expr } else { cast(expr, DataType::Utf8) })
+}
+
+/// Turns an operand into an expression, converting literals to `target` when given.
+fn operand_expr(operand: Operand, target: Option<&DataType>) -> Result<Expr, ParseError> {
+	let (text, quoted, position) = match operand {
+		Operand::Column { name, .. } => return Ok(Expr::Column(Column::new(None::<String>, name))),
+		Operand::Literal { text: None, .. } => return Ok(lit(ScalarValue::Null)),
+		Operand::Literal { text: Some(text), quoted, position } => (text, quoted, position),
+	};
+
+	let mismatch = |kind: &str| ParseError::new(position, format!("expected {} to compare with {} values, found '{}'", kind, type_label(target), text));
+	match target {
+		Some(t) if t.is_integer() => match (text.trim().parse::<i64>(), text.trim().parse::<f64>()) {
+			(Ok(value), _) => Ok(lit(value)),
+			(_, Ok(value)) => Ok(lit(value)),
+			_ => Err(mismatch("a number")),
+		},
+		Some(t) if t.is_floating() || matches!(t, DataType::Decimal128(..) | DataType::Decimal256(..)) => {
+			text.trim().parse::<f64>().map(lit).map_err(|_| mismatch("a number"))
+		},
+		Some(DataType::Boolean) => parse_bool(&text).map(lit).ok_or_else(|| mismatch("true or false")),
+		Some(t) if is_string(t) => Ok(lit(text)),
+		Some(t) if t.is_temporal() => temporal_literal(&text, t).ok_or_else(|| mismatch(TIME_FORMATS)),
+		_ if quoted => Ok(lit(text)),
+		_ => Ok(if let Ok(value) = text.parse::<i64>() {
+			lit(value)
+		} else if let Ok(value) = text.parse::<f64>() {
+			lit(value)
+		} else if let Some(value) = parse_bool(&text) {
+			lit(value)
+		} else {
+			lit(text)
+		}),
+	}
+}
+
+const TIME_FORMATS: &str = "a date or time such as '2024-03-01', '2024-03-01 12:30', 'today' or 'now - 7d'";
+
+/// `column >= start and column < end` over a date or timestamp column, where `start`
+/// and `end` use the same date and relative-time forms as predicates.
+pub fn date_window(schema: &DFSchemaRef, column: &str, start: &str, end: &str) -> NailResult<Expr> {
+	let name = resolve_column_name(schema, column)?;
+	let data_type = field_type(schema, &name);
+	if !matches!(data_type, DataType::Date32 | DataType::Date64 | DataType::Timestamp(..)) {
+		return Err(NailError::InvalidArgument(format!(
+			"Column '{}' is {}, not a date or timestamp", name, data_type
+		)));
+	}
+
+	let bound = |text: &str, which: &str| temporal_literal(text, &data_type).ok_or_else(|| NailError::InvalidArgument(format!(
+		"Invalid {} date '{}': expected {}", which, text, TIME_FORMATS
+	)));
+	let column = Expr::Column(Column::new(None::<String>, name.clone()));
+	Ok(column.clone().gt_eq(bound(start, "start")?).and(column.lt(bound(end, "end")?)))
+}
+
+/// Converts a date or time literal to a value of the temporal `data_type`.
+fn temporal_literal(text: &str, data_type: &DataType) -> Option<Expr> {
+	// Columns with a time zone store UTC instants; naive columns store wall-clock time
+	let utc = matches!(data_type, DataType::Timestamp(_, Some(_)));
+	let time = parse_time(text, utc)?;
+	let value = match data_type {
+		DataType::Date32 => {
+			let days = time.date().signed_duration_since(NaiveDate::default()).num_days();
+			ScalarValue::Date32(Some(i32::try_from(days).ok()?))
+		},
+		DataType::Date64 => ScalarValue::Date64(Some(time.date().and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis())),
+		DataType::Timestamp(unit, tz) => {
+			let instant = time.and_utc();
+			match unit {
+				TimeUnit::Second => ScalarValue::TimestampSecond(Some(instant.timestamp()), tz.clone()),
+				TimeUnit::Millisecond => ScalarValue::TimestampMillisecond(Some(instant.timestamp_millis()), tz.clone()),
+				TimeUnit::Microsecond => ScalarValue::TimestampMicrosecond(Some(instant.timestamp_micros()), tz.clone()),
+				TimeUnit::Nanosecond => ScalarValue::TimestampNanosecond(Some(instant.timestamp_nanos_opt()?), tz.clone()),
+			}
+		},
+		// Times of day and intervals are left to DataFusion's string casts
+		_ => return Some(cast(lit(text), data_type.clone())),
+	};
+	Some(lit(value))
+}
+
+/// Parses an absolute or relative time as local wall-clock time, or as UTC when `utc` is set.
+fn parse_time(text: &str, utc: bool) -> Option<NaiveDateTime> {
+	let text = text.trim();
+	if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+		return Some(if utc { time.naive_utc() } else { time.with_timezone(&Local).naive_local() });
+	}
+
+	let local = if text.starts_with(|c: char| c.is_ascii_alphabetic()) {
+		parse_relative_time(text)?
+	} else {
+		parse_naive_time(text)?
+	};
+	if utc {
+		Some(Local.from_local_datetime(&local).earliest()?.naive_utc())
+	} else {
+		Some(local)
+	}
+}
+
+fn parse_naive_time(text: &str) -> Option<NaiveDateTime> {
+	const DATETIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"];
+	const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+
+	DATETIME_FORMATS.iter()
+		.find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+		.or_else(|| DATE_FORMATS.iter()
+			.find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+			.and_then(|date| date.and_hms_opt(0, 0, 0)))
+}
+
+/// A signed run of offsets in a relative time, e.g. `-7d` or `+1w2d`.
+static RELATIVE_OFFSET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([+-])((?:\d+[a-z]+)+)").unwrap());
+/// One amount and unit within an offset, e.g. `1w`.
+static RELATIVE_AMOUNT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)([a-z]+)").unwrap());
+
+/// `now`, `today`, `yesterday` or `tomorrow` followed by offsets like `- 7d` or `+ 1w2d`.
+fn parse_relative_time(text: &str) -> Option<NaiveDateTime> {
+	let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+	let base_len = compact.find(['+', '-']).unwrap_or(compact.len());
+	let now = Local::now().naive_local();
+	let midnight = now.date().and_hms_opt(0, 0, 0)?;
+	let mut time = match &compact[..base_len] {
+		"now" => now,
+		"today" => midnight,
+		"yesterday" => midnight - Duration::days(1),
+		"tomorrow" => midnight + Duration::days(1),
+		_ => return None,
+	};
+
+	let mut rest = &compact[base_len..];
+	while !rest.is_empty() {
+		let captures = RELATIVE_OFFSET.captures(rest)?;
+		let negative = &captures[1] == "-";
+		for amount in RELATIVE_AMOUNT.captures_iter(&captures[2]) {
+			let count: u32 = amount[1].parse().ok()?;
+			time = shift_time(time, count, &amount[2], negative)?;
+		}
+		rest = &rest[captures[0].len()..];
+	}
+	Some(time)
+}
+
+fn shift_time(time: NaiveDateTime, count: u32, unit: &str, negative: bool) -> Option<NaiveDateTime> {
+	let months = match unit {
+		"mo" | "mon" | "month" | "months" => Some(count),
+		"y" | "yr" | "year" | "years" => count.checked_mul(12),
+		_ => None,
+	};
+	if let Some(months) = months {
+		return if negative {
+			time.checked_sub_months(Months::new(months))
+		} else {
+			time.checked_add_months(Months::new(months))
+		};
+	}
+
+	let count = i64::from(count);
+	let duration = match unit {
+		"s" | "sec" | "secs" | "second" | "seconds" => Duration::seconds(count),
+		"m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(count),
+		"h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(count),
+		"d" | "day" | "days" => Duration::days(count),
+		"w" | "week" | "weeks" => Duration::weeks(count),
+		_ => return None,
+	};
+	if negative {
+		time.checked_sub_signed(duration)
+	} else {
+		time.checked_add_signed(duration)
+	}
+}
+
+fn type_label(data_type: Option<&DataType>) -> String {
+	data_type.map(|t| t.to_string()).unwrap_or_default()
+}
+
+fn is_string(data_type: &DataType) -> bool {
+	matches!(data_type, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View)
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+	match text.trim().to_lowercase().as_str() {
+		"true" => Some(true),
+		"false" => Some(false),
+		_ => None,
+	}
+}
+
+/// Checks regex patterns up front so a bad pattern is reported at its position
+/// instead of failing halfway through execution.
+fn validate_regex(pattern: &str, position: usize) -> Result<(), ParseError> {
+	Regex::new(pattern)
+		.map(|_| ())
+		.map_err(|e| ParseError::new(position, format!("invalid regex: {}", e.to_string().lines().last().unwrap_or_default())))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use datafusion::arrow::datatypes::{Field, Schema};
+	use std::sync::Arc;
+
+	fn schema() -> DFSchemaRef {
+		let schema = Schema::new(vec![
+			Field::new("id", DataType::Int64, false),
+			Field::new("name", DataType::Utf8, true),
+			Field::new("Score", DataType::Float64, true),
+			Field::new("active", DataType::Boolean, true),
+			Field::new("zip code", DataType::Utf8, true),
+			Field::new("day", DataType::Date32, true),
+			Field::new("created_at", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+		]);
+		Arc::new(DFSchema::try_from(schema).unwrap())
+	}
+
+	fn parse(input: &str) -> String {
+		parse_predicate(input, &schema()).unwrap().to_string()
+	}
+
+	fn error(input: &str) -> String {
+		parse_predicate(input, &schema()).unwrap_err().to_string()
+	}
+
+	#[test]
+	fn test_legacy_conditions() {
+		assert_eq!(parse("id>3,name=Alice"), "id > Int64(3) AND name = Utf8(\"Alice\")");
+		assert_eq!(parse("score>=80"), "Score >= Float64(80)");
+		assert_eq!(parse("active=true"), "active = Boolean(true)");
+		assert_eq!(parse("name=John Smith"), "name = Utf8(\"John Smith\")");
+	}
+
+	#[test]
+	fn test_boolean_grammar() {
+		assert_eq!(
+			parse("(name in ('a','b') or id >= 65) and not name ~ '^test'"),
+			"(name IN ([Utf8(\"a\"), Utf8(\"b\")]) OR id >= Int64(65)) AND NOT name ~ Utf8(\"^test\")"
+		);
+		assert_eq!(parse("id between 1 and 5 and active"), "id BETWEEN Int64(1) AND Int64(5) AND active");
+		assert_eq!(parse("name is not null || id not in (1, 2)"), "name IS NOT NULL OR id NOT IN ([Int64(1), Int64(2)])");
+		assert_eq!(parse("name not like 'A%'"), "name NOT LIKE Utf8(\"A%\")");
+		assert_eq!(parse("name = null"), "name IS NULL");
+	}
+
+	#[test]
+	fn test_literal_coercion() {
+		assert_eq!(parse("\"zip code\" = 02134"), "zip code = Utf8(\"02134\")");
+		assert_eq!(parse("id = '7'"), "id = Int64(7)");
+		assert_eq!(parse("name = 'a=b, c'"), "name = Utf8(\"a=b, c\")");
+		assert_eq!(parse("id ~ '^1'"), "CAST(id AS Utf8) ~ Utf8(\"^1\")");
+	}
+
+	#[test]
+	fn test_error_positions() {
+		let message = error("(id > 1 or name = 'x'");
+		assert!(message.contains("position 1: unclosed '('"), "{}", message);
+		assert!(message.ends_with("\n  ^"), "{}", message);
+
+		let message = error("id > 1 and score > abc");
+		assert!(message.contains("position 20: expected a number"), "{}", message);
+		assert!(error("id >").contains("expected a column or value, found end of expression"));
+		assert!(error("name ~ '('").contains("invalid regex"));
+		assert!(error("nmae = 1").contains("Did you mean 'name'?"));
+		assert!(error("name = 'open").contains("unterminated ' quote"));
+		assert!(error("id").contains("expected a comparison"));
+	}
+
+	#[test]
+	fn test_temporal_literals() {
+		assert_eq!(temporal_literal("2024-03-01", &DataType::Date32), Some(lit(ScalarValue::Date32(Some(19783)))));
+		assert_eq!(
+			temporal_literal("2024-03-01 12:30", &DataType::Timestamp(TimeUnit::Microsecond, None)),
+			Some(lit(ScalarValue::TimestampMicrosecond(Some((19783 * 86400 + 45000) * 1_000_000), None)))
+		);
+		assert_eq!(
+			temporal_literal("2024-03-01T12:30:00Z", &DataType::Timestamp(TimeUnit::Second, Some("UTC".into()))),
+			Some(lit(ScalarValue::TimestampSecond(Some(19783 * 86400 + 45000), Some("UTC".into()))))
+		);
+		assert_eq!(parse_relative_time("today - 1d"), parse_relative_time("yesterday"));
+		assert_eq!(parse_relative_time("Today+1w-7d"), parse_relative_time("today"));
+		let today = Local::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+		assert_eq!(parse_relative_time("today + 1mo"), today.checked_add_months(Months::new(1)));
+		assert!(parse_relative_time("now - 7x").is_none());
+		assert!(parse_relative_time("soon").is_none());
+	}
+
+	#[test]
+	fn test_temporal_predicates() {
+		assert_eq!(parse("day >= 2024-03-01"), "day >= Date32(\"2024-03-01\")");
+		assert!(parse("created_at > now - 7d and day between yesterday and today").contains("created_at > TimestampMicrosecond("));
+		assert!(error("day > someday").contains("expected a date or time"));
+
+		let window = date_window(&schema(), "Day", "2024-03-01", "2024-04-01").unwrap();
+		assert_eq!(window.to_string(), "day >= Date32(\"2024-03-01\") AND day < Date32(\"2024-04-01\")");
+		assert!(date_window(&schema(), "name", "today", "now").unwrap_err().to_string().contains("not a date or timestamp"));
+		assert!(date_window(&schema(), "day", "today", "later").unwrap_err().to_string().contains("Invalid end date 'later'"));
+	}
+}
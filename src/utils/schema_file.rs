@@ -0,0 +1,49 @@
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use std::path::Path;
+use std::str::FromStr;
+use crate::error::{NailError, NailResult};
+
+/// One column entry of a schema file, as written by `nail schema -f json`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct SchemaField {
+	pub name: String,
+	pub data_type: String,
+	pub nullable: bool,
+}
+
+impl SchemaField {
+	pub fn from_field(field: &Field) -> Self {
+		SchemaField {
+			name: field.name().clone(),
+			data_type: format!("{:?}", field.data_type()),
+			nullable: field.is_nullable(),
+		}
+	}
+}
+
+/// Loads a JSON schema file (a list of `{name, data_type, nullable}` entries).
+pub fn load_schema_file(path: &Path) -> NailResult<Schema> {
+	let content = std::fs::read_to_string(path)?;
+	let entries: Vec<SchemaField> = serde_json::from_str(&content)
+		.map_err(|e| NailError::InvalidArgument(format!("Invalid schema file {}: {}", path.display(), e)))?;
+	
+	let fields = entries.iter()
+		.map(|entry| {
+			let data_type = DataType::from_str(&entry.data_type)
+				.map_err(|e| NailError::InvalidArgument(format!(
+					"Invalid data type '{}' for column '{}': {}", entry.data_type, entry.name, e
+				)))?;
+			Ok(Field::new(&entry.name, data_type, entry.nullable))
+		})
+		.collect::<NailResult<Vec<_>>>()?;
+	
+	Ok(Schema::new(fields))
+}
+
+pub fn write_schema_file(path: &Path, schema: &Schema) -> NailResult<()> {
+	let entries: Vec<SchemaField> = schema.fields().iter()
+		.map(|f| SchemaField::from_field(f))
+		.collect();
+	std::fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+	Ok(())
+}
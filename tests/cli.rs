@@ -106,6 +106,16 @@ mod inspection_tests {
 		let fixtures = TestFixtures::new();
 		nail().args(["size", fixtures.sample_parquet.to_str().unwrap(), "--columns", "--rows", "--bits"]).assert().success().stdout(predicate::str::contains("Per-column sizes").and(predicate::str::contains("Average bits per row")));
 	}
+
+	#[test]
+	fn test_metadata_renamed_flags() {
+		let fixtures = TestFixtures::new();
+		let input = fixtures.sample_parquet.to_str().unwrap();
+		nail().args(["metadata", input, "--encodings"]).assert().success().stdout(predicate::str::contains("encoding_plain_usage_count"));
+		nail().args(["metadata", input, "--schema-info"]).assert().success();
+		nail().args(["metadata", input, "--encoding", "latin1"]).assert().failure().stderr(predicate::str::contains("--encodings"));
+		nail().args(["metadata", input, "--schema", "schema.json"]).assert().failure().stderr(predicate::str::contains("--schema-info"));
+	}
 }
 
 // ---- DATA MANIPULATION COMMANDS ----
@@ -163,6 +173,66 @@ mod manipulation_tests {
 		assert_eq!(get_row_count(&out_rows).await, 2, "Two rows should have no NaN values");
 	}
 
+	#[tokio::test]
+	async fn test_filter_boolean_expressions() {
+		let fixtures = TestFixtures::new();
+		let input = fixtures.sample_parquet.to_str().unwrap();
+		let out_filter = fixtures.get_output_path("filtered_expr.parquet");
+		nail().args(["filter", input, "-c", "(category in ('A','B') or value >= 500) and not name ~ '^D'", "-o", out_filter.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_filter).await, 4);
+
+		let out_drop = fixtures.get_output_path("dropped_expr.parquet");
+		nail().args(["drop", input, "-r", "name like 'A%' or value between 200 and 300", "-o", out_drop.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_drop).await, 2);
+
+		nail().args(["filter", input, "-c", "id > 1 and value > abc"]).assert().code(2)
+			.stderr(predicate::str::contains("position 20: expected a number"));
+		nail().args(["filter", input, "-c", "(id > 1"]).assert().failure()
+			.stderr(predicate::str::contains("unclosed '('"));
+	}
+
+	#[test]
+	fn test_create_chained_expressions() {
+		let fixtures = TestFixtures::new();
+		let output = nail().args([
+			"create", fixtures.sample_parquet.to_str().unwrap(),
+			"-c", "rounded=round(value / 3, 1)",
+			"-c", "tier=if(rounded > 100, 'high', 'low'), is_a=category = 'A'",
+			"-c", "label=concat(lower(name), '-', tier)",
+			"-f", "json",
+		]).assert().success().get_output().stdout.clone();
+		let output = String::from_utf8(output).unwrap();
+		assert!(output.contains("\"rounded\": 33.3"), "{}", output);
+		assert!(output.contains("\"label\": \"eve-high\""), "{}", output);
+		assert!(output.contains("\"is_a\": true"), "{}", output);
+
+		nail().args(["create", fixtures.sample_parquet.to_str().unwrap(), "-c", "x=valeu*2"]).assert().code(5)
+			.stderr(predicate::str::contains("Did you mean 'value'?"));
+	}
+
+	#[tokio::test]
+	async fn test_filter_dates() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("events.csv");
+		fs::write(&csv_path, "id,day,created_at\n1,2024-01-05,2024-01-05T10:00:00\n2,2024-02-10,2024-02-10T08:30:00\n3,2024-03-01,2099-01-01T00:00:00\n").unwrap();
+		let input = csv_path.to_str().unwrap();
+
+		let out_after = fixtures.get_output_path("after.parquet");
+		nail().args(["filter", input, "-c", "day >= 2024-02-01", "-o", out_after.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_after).await, 2);
+
+		let out_recent = fixtures.get_output_path("recent.parquet");
+		nail().args(["filter", input, "-c", "created_at > now - 7d", "-o", out_recent.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_recent).await, 1);
+
+		let out_window = fixtures.get_output_path("window.parquet");
+		nail().args(["filter", input, "--between-dates", "day", "2024-01-01", "2024-02-10", "-o", out_window.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_window).await, 1);
+
+		nail().args(["filter", input, "-c", "day < tomorow"]).assert().failure()
+			.stderr(predicate::str::contains("expected a date or time"));
+	}
+
 	#[tokio::test]
 	async fn test_search_all_options() {
 		let fixtures = TestFixtures::new();
@@ -221,6 +291,21 @@ mod transformation_tests {
 		nail().args(["id", fixtures.sample_parquet.to_str().unwrap(), "--create", "--id-col-name", "name"]).assert().failure().stderr(predicate::str::contains("already exists"));
 	}
 
+	#[test]
+	fn test_rename_uses_column_name_rules() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("rename_quoted.csv");
+		fs::write(&csv_path, "\"a,b\",Price\n1,2\n").unwrap();
+		nail().args(["rename", csv_path.to_str().unwrap(), "-c", "\"a,b\"=x,price=\"unit, price\"", "-f", "json"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("\"x\"").and(predicate::str::contains("\"unit, price\"")));
+		nail().args(["rename", csv_path.to_str().unwrap(), "-c", "prise=x"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("Did you mean 'Price'?"));
+	}
+
 	#[tokio::test]
 	async fn test_shuffle_reproducible() {
 		let fixtures = TestFixtures::new();
@@ -263,6 +348,154 @@ mod transformation_tests {
 		}
 	}
 
+	#[tokio::test]
+	async fn test_cast_types() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("prices.csv");
+		fs::write(&csv_path, "id,price,pct\n1,\"$1,200.50\",12%\n2,(30),5%\n3,n/a,x\n").unwrap();
+		let input = csv_path.to_str().unwrap();
+
+		nail().args(["cast", input, "-c", "price=float64"]).assert().code(2)
+			.stderr(predicate::str::contains("1 value(s) in 'price'"));
+
+		let out_null = fixtures.get_output_path("cast_null.json");
+		nail().args(["cast", input, "-c", "price=float64,pct=float64", "--on-error", "null", "-f", "json", "-o", out_null.to_str().unwrap()])
+			.assert().success().stderr(predicate::str::contains("set to null"));
+		let content = fs::read_to_string(out_null).unwrap();
+		let rows: Vec<Value> = content.trim().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(rows[0]["price"], 1200.5);
+		assert_eq!(rows[1]["price"], -30.0);
+		assert_eq!(rows[0]["pct"], 0.12);
+		assert!(rows[2]["price"].is_null());
+
+		let out_keep = fixtures.get_output_path("cast_keep.parquet");
+		nail().args(["cast", input, "-c", "price=float64,id=int32", "--on-error", "keep-as-string", "-o", out_keep.to_str().unwrap()]).assert().success();
+		let df = SessionContext::new().read_parquet(out_keep.to_str().unwrap(), ParquetReadOptions::default()).await.unwrap();
+		assert_eq!(df.schema().field_with_name(None, "price").unwrap().data_type(), &datafusion::arrow::datatypes::DataType::Utf8);
+		assert_eq!(df.schema().field_with_name(None, "id").unwrap().data_type(), &datafusion::arrow::datatypes::DataType::Int32);
+	}
+
+	#[tokio::test]
+	async fn test_strings_operations() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("people.csv");
+		fs::write(&csv_path, "id,name,code\n1,\"  jos\u{e9}  GARC\u{cd}A \",AB-2024-05\n2,o'neil,CD-1999-12\n3,,bad\n").unwrap();
+		let input = csv_path.to_str().unwrap();
+
+		let out_clean = fixtures.get_output_path("strings_clean.json");
+		nail().args(["strings", input, "-c", "name", "--normalize-whitespace", "--strip-accents", "--case", "title", "-f", "json", "-o", out_clean.to_str().unwrap()]).assert().success();
+		let content = fs::read_to_string(out_clean).unwrap();
+		let rows: Vec<Value> = content.trim().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(rows[0]["name"], "Jose Garcia");
+		assert_eq!(rows[1]["name"], "O'neil");
+		assert!(rows[2]["name"].is_null());
+
+		let out_parts = fixtures.get_output_path("strings_parts.json");
+		nail().args(["strings", input, "-c", "code", "--extract", "(?P<prefix>[A-Z]+)-(?P<year>\\d{4})", "--split", "-", "--split-into", "2", "-f", "json", "-o", out_parts.to_str().unwrap()]).assert().success();
+		let content = fs::read_to_string(out_parts).unwrap();
+		let rows: Vec<Value> = content.trim().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(rows[0]["code_year"], "2024");
+		assert_eq!(rows[0]["code_2"], "2024-05");
+		assert!(rows[2]["code_prefix"].is_null());
+
+		nail().args(["strings", input, "-c", "name"]).assert().failure()
+			.stderr(predicate::str::contains("No string operation given"));
+	}
+
+	#[tokio::test]
+	async fn test_dates_parse_and_extract() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("orders.csv");
+		fs::write(&csv_path, "id,ordered\n1,31.01.2024 23:30\n2,10.02.2024 08:00\n3,\n").unwrap();
+		let input = csv_path.to_str().unwrap();
+
+		let out_parts = fixtures.get_output_path("dates_parts.json");
+		nail().args(["dates", input, "-c", "ordered", "--truncate", "month", "--extract", "quarter,weekday,is_weekend", "-f", "json", "-o", out_parts.to_str().unwrap()]).assert().success();
+		let content = fs::read_to_string(out_parts).unwrap();
+		let rows: Vec<Value> = content.trim().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(rows[0]["ordered"], "2024-01-01T00:00:00");
+		assert_eq!(rows[0]["ordered_weekday"], 3);
+		assert_eq!(rows[1]["ordered_is_weekend"], true);
+		assert!(rows[2]["ordered_quarter"].is_null());
+
+		let out_tz = fixtures.get_output_path("dates_tz.json");
+		nail().args(["dates", input, "-c", "ordered", "--date-format", "dd.mm.yyyy HH:mm", "--from-tz", "Europe/Paris", "--tz", "UTC", "--suffix", "_utc", "-f", "json", "-o", out_tz.to_str().unwrap()]).assert().success();
+		let content = fs::read_to_string(out_tz).unwrap();
+		let first: Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+		assert_eq!(first["ordered"], "31.01.2024 23:30");
+		assert_eq!(first["ordered_utc"], "2024-01-31T22:30:00Z");
+
+		nail().args(["dates", input, "-c", "id"]).assert().failure()
+			.stderr(predicate::str::contains("dates works on string, date and timestamp columns"));
+	}
+
+	#[tokio::test]
+	async fn test_flatten_and_explode() {
+		let fixtures = TestFixtures::new();
+		let nested_path = fixtures.get_output_path("nested.parquet");
+		SessionContext::new()
+			.sql("SELECT 1 AS id, named_struct('user', named_struct('name', 'ann'), 'ok', true) AS event, make_array('a', 'b') AS tags \
+				UNION ALL SELECT 2, named_struct('user', named_struct('name', 'bob'), 'ok', false), make_array('c')")
+			.await.unwrap()
+			.write_parquet(nested_path.to_str().unwrap(), datafusion::dataframe::DataFrameWriteOptions::new(), None)
+			.await.unwrap();
+		let input = nested_path.to_str().unwrap();
+
+		let out_flat = fixtures.get_output_path("flat.parquet");
+		nail().args(["flatten", input, "-o", out_flat.to_str().unwrap()]).assert().success();
+		let df = SessionContext::new().read_parquet(out_flat.to_str().unwrap(), ParquetReadOptions::default()).await.unwrap();
+		let names: Vec<String> = df.schema().fields().iter().map(|f| f.name().clone()).collect();
+		assert_eq!(names, vec!["id", "event.user.name", "event.ok", "tags"]);
+
+		let out_rows = fixtures.get_output_path("exploded.parquet");
+		nail().args(["explode", input, "-c", "tags", "--position", "tag_index", "-o", out_rows.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_rows).await, 3);
+
+		nail().args(["head", input, "-f", "json"]).assert().success()
+			.stdout(predicate::str::contains(r#""event": {"user": {"name": "ann"}, "ok": true}, "tags": ["a", "b"]"#));
+	}
+
+	#[tokio::test]
+	async fn test_nest_pack_and_collect() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("line_items.csv");
+		fs::write(&csv_path, "user_id,sku,qty\n2,pen,3\n1,ink,1\n2,cap,5\n").unwrap();
+		let input = csv_path.to_str().unwrap();
+
+		let out_nested = fixtures.get_output_path("by_user.parquet");
+		nail().args(["nest", input, "--pack", "item=sku,qty", "--by", "user_id", "--collect", "item", "-o", out_nested.to_str().unwrap()]).assert().success();
+		assert_eq!(get_row_count(&out_nested).await, 2);
+		let df = SessionContext::new().read_parquet(out_nested.to_str().unwrap(), ParquetReadOptions::default()).await.unwrap();
+		let item_type = df.schema().field_with_name(None, "item").unwrap().data_type().to_string();
+		assert!(item_type.starts_with("List(") && item_type.contains("Struct("), "{}", item_type);
+
+		nail().args(["nest", out_nested.to_str().unwrap(), "-f", "json"]).assert().failure()
+			.stderr(predicate::str::contains("Nothing to do"));
+		nail().args(["nest", input, "--pack", "item=sku,qty", "-o", fixtures.get_output_path("packed.csv").to_str().unwrap()]).assert().failure()
+			.stderr(predicate::str::contains("cannot be written as CSV"));
+	}
+
+	#[test]
+	fn test_json_extract_paths_and_infer() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("events.csv");
+		fs::write(&csv_path, "id,payload\n1,\"{\"\"user\"\": {\"\"id\"\": 7}, \"\"amount\"\": 12.5}\"\n2,{broken\n").unwrap();
+		let input = csv_path.to_str().unwrap();
+
+		nail().args(["json-extract", input, "-c", "payload", "--paths", "$.user.id,$.amount", "-f", "json"]).assert().failure()
+			.stderr(predicate::str::contains("row 2").and(predicate::str::contains("--on-error null")));
+
+		let output = nail().args(["json-extract", input, "-c", "payload", "--infer", "--on-error", "null", "-f", "json"]).output().unwrap();
+		assert!(output.status.success());
+		assert!(String::from_utf8_lossy(&output.stderr).contains("Warning: 1 value(s) in 'payload'"));
+		let rows: Vec<serde_json::Value> = String::from_utf8(output.stdout).unwrap().lines()
+			.filter(|line| line.trim_start().starts_with('{'))
+			.map(|line| serde_json::from_str(line.trim().trim_end_matches(',')).unwrap())
+			.collect();
+		assert_eq!(rows[0], serde_json::json!({"id": 1, "payload.amount": 12.5, "payload.user.id": 7}));
+		assert_eq!(rows[1], serde_json::json!({"id": 2, "payload.amount": null, "payload.user.id": null}));
+	}
+
 	#[tokio::test]
 	async fn test_dedup_row_wise() {
 		let fixtures = TestFixtures::new();
@@ -294,6 +527,98 @@ mod transformation_tests {
 mod combination_tests {
 	use super::*;
 
+	#[test]
+	fn test_pipe_and_run_recipe() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("people.csv");
+		fs::write(&csv_path, "id,name,age\n1,Ann,40\n2,Bob,\n2,Bob,\n3,Cy,25\n").unwrap();
+
+		nail().args([
+			"pipe", csv_path.to_str().unwrap(),
+			"filter -c \"id>1\" | fill --method value --value 0 | dedup --row-wise | select -c \"name,age\"",
+			"-f", "json",
+		])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("{\"name\": \"Bob\", \"age\": 0}"))
+			.stdout(predicate::str::contains("Ann").not());
+
+		let recipe_path = fixtures.get_output_path("recipe.yaml");
+		let out_path = fixtures.get_output_path("recipe_out.csv");
+		fs::write(&recipe_path, "input: people.csv\noutput: recipe_out.csv\nsteps:\n  - dedup --row-wise\n  - head -n 2\n").unwrap();
+		nail().args(["run", recipe_path.to_str().unwrap()]).assert().success();
+		assert_eq!(fs::read_to_string(&out_path).unwrap(), "id,name,age\n1,Ann,40\n2,Bob,\n");
+
+		// Common options apply to the whole recipe, and --input overrides the recipe's input
+		nail().args(["run", recipe_path.to_str().unwrap(), "--input", csv_path.to_str().unwrap(), "--where", "age > 30"])
+			.assert()
+			.success();
+		assert_eq!(fs::read_to_string(&out_path).unwrap(), "id,name,age\n1,Ann,40\n");
+
+		nail().args(["pipe", csv_path.to_str().unwrap(), "head --where 'age > 30'"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("belong to the whole pipeline"));
+
+		nail().args(["pipe", csv_path.to_str().unwrap(), "head | stats"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("cannot be used in a pipeline"));
+	}
+
+	#[test]
+	fn test_shell_session() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("people.csv");
+		let out_path = fixtures.get_output_path("older.csv");
+		fs::write(&csv_path, "id,name,age\n1,Ann,40\n2,Bob,20\n3,Cy,55\n").unwrap();
+
+		let script = format!(
+			"older = filter -c \"age>30\" | sort -c age -d true\ncount older\nbogus\nsave older {}\nexit\n",
+			out_path.display()
+		);
+		nail().args(["shell", csv_path.to_str().unwrap()])
+			.env("HOME", fixtures._temp_dir.path())
+			.write_stdin(script)
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("2\n"))
+			.stderr(predicate::str::contains("Unknown command 'bogus'"));
+		assert_eq!(fs::read_to_string(&out_path).unwrap(), "id,name,age\n3,Cy,55\n1,Ann,40\n");
+	}
+
+	#[test]
+	fn test_sql_join_and_default_table() {
+		let fixtures = TestFixtures::new();
+		let users = fixtures.get_output_path("users.csv");
+		let orders = fixtures.get_output_path("orders.csv");
+		fs::write(&users, "user_id,name\n1,Ann\n2,Bob\n").unwrap();
+		fs::write(&orders, "order_id,user_id,amount\n10,1,5.5\n11,1,2\n12,2,7\n").unwrap();
+
+		let users_table = format!("users={}", users.display());
+		let orders_table = format!("orders={}", orders.display());
+		nail().args([
+			"sql", "SELECT u.name, SUM(o.amount) AS total FROM orders o JOIN users u ON o.user_id = u.user_id GROUP BY u.name ORDER BY u.name",
+			"--table", &orders_table, "--table", &users_table, "-f", "json",
+		]).assert().success().stdout(predicate::str::contains("{\"name\": \"Ann\", \"total\": 7.5}"));
+
+		let query_path = fixtures.get_output_path("count.sql");
+		fs::write(&query_path, "SELECT COUNT(*) AS n FROM t;\n").unwrap();
+		nail().args(["sql", "--file", query_path.to_str().unwrap(), "-i", fixtures.sample_parquet.to_str().unwrap(), "-f", "json"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("{\"n\": 5}"));
+
+		// Read options apply to every registered table
+		let legacy = fixtures.get_output_path("legacy.csv");
+		fs::write(&legacy, b"user_id;city\n1;M\xfcnchen\n2;Z\xfcrich\n").unwrap();
+		let legacy_table = format!("cities={}", legacy.display());
+		nail().args([
+			"sql", "SELECT city FROM cities WHERE user_id = 1",
+			"--table", &legacy_table, "--delimiter", ";", "--encoding", "latin1", "-f", "json",
+		]).assert().success().stdout(predicate::str::contains("{\"city\": \"M\u{fc}nchen\"}"));
+	}
+
 	#[tokio::test]
 	async fn test_merge_inner_and_left() {
 		let fixtures = TestFixtures::new();
@@ -370,6 +695,47 @@ mod format_and_analysis_tests {
 		assert_eq!(get_row_count(&parquet_output).await, 5);
 	}
 
+	#[test]
+	fn test_explain_and_explain_analyze() {
+		let fixtures = TestFixtures::new();
+		let output = fixtures.get_output_path("explained.csv");
+
+		nail().args(["filter", fixtures.sample_parquet.to_str().unwrap(), "-c", "value>1", "--explain"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("== logical_plan =="))
+			.stdout(predicate::str::contains("FilterExec"));
+
+		nail().args(["sort", fixtures.sample_parquet.to_str().unwrap(), "-c", "value", "--explain-analyze", "-o", output.to_str().unwrap()])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("Plan with Metrics"))
+			.stdout(predicate::str::contains("output_rows=5"));
+		assert!(!output.exists());
+
+		// Commands that print their own summaries show the plan instead and nothing else
+		let input = fixtures.sample_parquet.to_str().unwrap();
+		for args in [vec!["count"], vec!["size"], vec!["headers"], vec!["frequency", "-c", "name"], vec!["stats"], vec!["preview", "-n", "2"]] {
+			nail().args(&args).args([input, "--explain"])
+				.assert()
+				.success()
+				.stdout(predicate::str::contains("== physical_plan =="))
+				.stdout(predicate::str::contains("count |").not());
+		}
+
+		let split_dir = fixtures.get_output_path("explained_splits");
+		nail().args(["split", input, "--ratio", "50,50", "--output-dir", split_dir.to_str().unwrap(), "--explain"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("-- Split 2:"));
+		assert!(!split_dir.exists());
+
+		nail().args(["metadata", input, "--explain"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("not supported by 'metadata'"));
+	}
+
 	#[test]
 	fn test_stats_all_types() {
 		let fixtures = TestFixtures::new();
@@ -467,7 +833,7 @@ mod format_and_analysis_tests {
 		nail().args(["frequency", fixtures.sample_parquet.to_str().unwrap(), "-c", "nonexistent"])
 			.assert()
 			.failure()
-			.stderr(predicate::str::contains("Columns not found"));
+			.stderr(predicate::str::contains("Column 'nonexistent' not found"));
 	}
 }
 
@@ -484,7 +850,78 @@ mod error_and_edge_case_tests {
 	#[test]
 	fn test_invalid_column() {
 		let fixtures = TestFixtures::new();
-		nail().args(["select", fixtures.sample_parquet.to_str().unwrap(), "-c", "bad_col"]).assert().failure().stderr(predicate::str::contains("Columns not found"));
+		nail().args(["select", fixtures.sample_parquet.to_str().unwrap(), "-c", "bad_col"]).assert().failure().stderr(predicate::str::contains("Column 'bad_col' not found"));
+	}
+
+	#[test]
+	fn test_unknown_column_suggestions() {
+		let fixtures = TestFixtures::new();
+		let input = fixtures.sample_parquet.to_str().unwrap();
+		nail().args(["filter", input, "-c", "valeu>10"]).assert().failure().stderr(predicate::str::contains("Did you mean 'value'?"));
+		nail().args(["sort", input, "-c", "Categry"]).assert().failure().stderr(predicate::str::contains("Did you mean 'category'?"));
+		nail().args(["stats", input, "-c", "id,nmae"]).assert().failure().stderr(predicate::str::contains("Did you mean 'name'?"));
+		nail().args(["merge", input, "--right", fixtures.sample2_parquet.to_str().unwrap(), "--key", "ID_"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("Did you mean 'id'?"));
+		nail().args(["merge", input, "--right", fixtures.sample2_parquet.to_str().unwrap(), "--key-mapping", "idd=user_id"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("Join key 'idd' not found in left table").and(predicate::str::contains("Did you mean 'id'?")));
+		nail().args(["merge", input, "--right", fixtures.sample2_parquet.to_str().unwrap(), "--key-mapping", "ID=\"user_id\"", "-f", "json"])
+			.assert()
+			.success();
+	}
+
+	#[test]
+	fn test_json_errors_and_exit_codes() {
+		let fixtures = TestFixtures::new();
+		let output = nail()
+			.args(["select", fixtures.sample_parquet.to_str().unwrap(), "-c", "bad_col", "--error-format", "json"])
+			.assert()
+			.code(5)
+			.get_output()
+			.stderr
+			.clone();
+		let report: Value = serde_json::from_slice(&output).unwrap();
+		assert_eq!(report["code"], "column_not_found");
+		assert_eq!(report["category"], "schema");
+		assert_eq!(report["column"], "bad_col");
+		assert!(report["hints"].as_array().is_some_and(|hints| !hints.is_empty()));
+
+		nail().args(["head", "nonexistent.parquet", "--error-format=json"])
+			.assert()
+			.code(3)
+			.stderr(predicate::str::contains("\"category\":\"not_found\""));
+		nail().args(["head", fixtures.sample_parquet.to_str().unwrap(), "--bogus", "--error-format", "json"])
+			.assert()
+			.code(2)
+			.stderr(predicate::str::contains("\"argument\":\"--bogus\""));
+		nail().args(["head", "data.unknown"]).assert().code(4).stderr(predicate::str::starts_with("Error: "));
+
+		// The format can come from config or the environment; the command line still wins
+		let dir = fixtures._temp_dir.path();
+		fs::write(dir.join(".nail.toml"), "error-format = \"json\"\n").unwrap();
+		nail().args(["head", "nonexistent.parquet"])
+			.current_dir(dir)
+			.env("HOME", dir)
+			.assert()
+			.code(3)
+			.stderr(predicate::str::contains("\"category\":\"not_found\""));
+		nail().args(["head", "nonexistent.parquet", "--error-format", "text"])
+			.current_dir(dir)
+			.env("HOME", dir)
+			.assert()
+			.code(3)
+			.stderr(predicate::str::starts_with("Error: "));
+		fs::remove_file(dir.join(".nail.toml")).unwrap();
+		nail().args(["head", "--bogus"])
+			.current_dir(dir)
+			.env("HOME", dir)
+			.env("NAIL_ERROR_FORMAT", "json")
+			.assert()
+			.code(2)
+			.stderr(predicate::str::contains("\"argument\":\"--bogus\""));
 	}
 
 	#[test]
@@ -525,6 +962,24 @@ mod error_and_edge_case_tests {
 		nail().args(["stats", empty]).assert().success();
 		nail().args(["count", empty]).assert().success().stdout("0\n");
 	}
+
+	#[test]
+	fn test_memory_limit_options() {
+		let fixtures = TestFixtures::new();
+		let input = fixtures.sample_parquet.to_str().unwrap();
+		let temp_dir = fixtures._temp_dir.path().to_str().unwrap();
+		nail().args(["sort", input, "-c", "value", "--memory-limit", "64M", "--temp-dir", temp_dir]).assert().success();
+		nail().args(["sort", input, "--memory-limit", "8X"]).assert().failure().stderr(predicate::str::contains("invalid memory size unit"));
+		nail().args(["sort", input, "--temp-dir", "/nonexistent/spill"]).assert().failure().stderr(predicate::str::contains("Temp directory does not exist"));
+	}
+
+	#[test]
+	fn test_session_options() {
+		let fixtures = TestFixtures::new();
+		let input = fixtures.sample_parquet.to_str().unwrap();
+		nail().args(["count", input, "-j", "1", "--batch-size", "2", "--collect-stats", "--no-pruning"]).assert().success().stdout("5\n");
+		nail().args(["dedup", input, "--row-wise", "-j", "2", "--batch-size", "3", "-f", "json"]).assert().success();
+	}
 }
 
 // ---- NEW COMMAND TESTS ----
@@ -1113,4 +1568,198 @@ mod new_command_tests {
 		let content = fs::read_to_string(pivot_path).unwrap();
 		assert!(!content.trim().is_empty());
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_config_file_and_env_defaults() {
+		let fixtures = TestFixtures::new();
+		let dir = fixtures._temp_dir.path();
+		let csv_path = fixtures.get_output_path("people.csv");
+		fs::write(&csv_path, "id,name\n1,Ann\n2,Bob\n3,Cy\n").unwrap();
+		fs::write(dir.join(".nail.toml"), "format = \"json\"\n\n[head]\nnumber = 1\n").unwrap();
+
+		// Project defaults apply, the command line wins over them
+		nail().args(["head", csv_path.to_str().unwrap()])
+			.current_dir(dir)
+			.env("HOME", dir)
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("Ann").and(predicate::str::contains("Bob").not()));
+		nail().args(["head", csv_path.to_str().unwrap(), "-n", "2"])
+			.current_dir(dir)
+			.env("HOME", dir)
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("Bob"));
+
+		// Environment variables win over config files
+		nail().args(["config", "show", "head"])
+			.current_dir(dir)
+			.env("HOME", dir)
+			.env("NAIL_HEAD_NUMBER", "3")
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("head.number = 3").and(predicate::str::contains("environment NAIL_HEAD_NUMBER")))
+			.stdout(predicate::str::contains("format = json").and(predicate::str::contains("project config")));
+	}
+
+	#[test]
+	fn test_completions_and_manpage() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("people.csv");
+		fs::write(&csv_path, "id,full name\n1,Ann\n").unwrap();
+
+		for shell in ["bash", "zsh", "fish", "powershell", "elvish"] {
+			nail().args(["completions", shell]).assert().success().stdout(predicate::str::contains("sniff"));
+		}
+		nail().args(["completions", "bash"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("select:--columns").and(predicate::str::contains("create:-c").not()));
+		nail().args(["completions", "--list-columns", csv_path.to_str().unwrap()])
+			.assert()
+			.success()
+			.stdout("id\nfull name\n");
+
+		let man_dir = fixtures.get_output_path("man");
+		nail().args(["manpage", "-o", man_dir.to_str().unwrap()]).assert().success();
+		assert!(man_dir.join("nail.1").exists());
+		assert!(fs::read_to_string(man_dir.join("nail-head.1")).unwrap().contains("number"));
+	}
+}
+// ---- INPUT HANDLING TESTS ----
+#[cfg(test)]
+mod input_handling_tests {
+	use super::*;
+
+	#[test]
+	fn test_bad_rows_fail_by_default() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("malformed.csv");
+		fs::write(&csv_path, "id,name\n1,Alice\n2,Bob,extra\n3,Carol\n").unwrap();
+		nail().args(["count", csv_path.to_str().unwrap()]).assert().failure();
+	}
+
+	#[test]
+	fn test_bad_rows_skip() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("malformed.csv");
+		fs::write(&csv_path, "id,name\n1,Alice\n2,Bob,extra\n3,Carol\n").unwrap();
+		nail().args(["count", csv_path.to_str().unwrap(), "--on-bad-rows", "skip", "-v"])
+			.assert()
+			.success()
+			.stdout("2\n")
+			.stderr(predicate::str::contains("Skipped 1 malformed rows"));
+	}
+
+	#[test]
+	fn test_bad_rows_quarantine() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("malformed.csv");
+		let rejects_path = fixtures.get_output_path("rejects.csv");
+		fs::write(&csv_path, "id,name\n1,Alice\n2,Bob,extra\n3,Carol\n").unwrap();
+		nail().args([
+			"head", csv_path.to_str().unwrap(),
+			"--on-bad-rows", "quarantine",
+			"--rejects-file", rejects_path.to_str().unwrap(),
+		]).assert().success().stdout(predicate::str::contains("Carol"));
+
+		let rejects = fs::read_to_string(rejects_path).unwrap();
+		assert!(rejects.starts_with("line,error,raw"));
+		assert!(rejects.contains("3,\"expected 2 fields, found 3\",\"2,Bob,extra\""));
+	}
+
+	#[test]
+	fn test_encoding_windows_1252_input() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("legacy.csv");
+		fs::write(&csv_path, b"id,name\n1,caf\xe9\n").unwrap();
+		nail().args(["head", csv_path.to_str().unwrap()]).assert().failure().stderr(predicate::str::contains("--encoding"));
+		nail().args(["head", csv_path.to_str().unwrap(), "--encoding", "windows-1252", "-f", "json"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("café"));
+	}
+
+	#[test]
+	fn test_output_encoding_latin1() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("utf8.csv");
+		let out_path = fixtures.get_output_path("latin1.csv");
+		fs::write(&csv_path, "id,name\n1,café\n").unwrap();
+		nail().args(["head", csv_path.to_str().unwrap(), "-o", out_path.to_str().unwrap(), "--output-encoding", "latin1"]).assert().success();
+		assert_eq!(fs::read(&out_path).unwrap(), b"id,name\n1,caf\xe9\n");
+
+		let convert_path = fixtures.get_output_path("converted_latin1.csv");
+		nail().args(["convert", csv_path.to_str().unwrap(), "-o", convert_path.to_str().unwrap(), "--output-encoding", "latin1"]).assert().success();
+		assert_eq!(fs::read(&convert_path).unwrap(), b"id,name\n1,caf\xe9\n");
+
+		let parquet_path = fixtures.get_output_path("not_text.parquet");
+		nail().args(["convert", csv_path.to_str().unwrap(), "-o", parquet_path.to_str().unwrap(), "--output-encoding", "latin1"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("--output-encoding only applies to CSV and TSV output"));
+		assert!(!parquet_path.exists());
+		nail().args(["head", csv_path.to_str().unwrap(), "-o", parquet_path.to_str().unwrap(), "--output-encoding", "latin1"])
+			.assert()
+			.failure()
+			.stderr(predicate::str::contains("--output-encoding only applies to CSV and TSV output"));
+	}
+
+	#[test]
+	fn test_sniff_emits_usable_options() {
+		let fixtures = TestFixtures::new();
+		let csv_path = fixtures.get_output_path("semicolon.csv");
+		let schema_path = fixtures.get_output_path("semicolon_schema.json");
+		fs::write(&csv_path, "id;name;score\n1;Alice;9.5\n2;Bob;7\n").unwrap();
+
+		nail().args(["sniff", csv_path.to_str().unwrap(), "--emit-options", "--write-schema", schema_path.to_str().unwrap()])
+			.assert()
+			.success()
+			.stdout(predicate::str::starts_with("--delimiter ';'"));
+
+		let schema = fs::read_to_string(&schema_path).unwrap();
+		assert!(schema.contains("\"Int64\""));
+		assert!(schema.contains("\"Float64\""));
+
+		nail().args([
+			"head", csv_path.to_str().unwrap(),
+			"--delimiter", ";",
+			"--schema", schema_path.to_str().unwrap(),
+			"-f", "json",
+		]).assert().success().stdout(predicate::str::contains("Alice"));
+
+		// A column typed from most of its values is written as Utf8, so the schema reads the whole sample
+		let mostly_path = fixtures.get_output_path("mostly_numeric.csv");
+		let mostly_schema = fixtures.get_output_path("mostly_numeric_schema.json");
+		let rows: String = (1..=40).map(|i| if i == 7 { "n/a\n".to_string() } else { format!("{}\n", i) }).collect();
+		fs::write(&mostly_path, format!("qty\n{}", rows)).unwrap();
+		nail().args(["sniff", mostly_path.to_str().unwrap(), "--write-schema", mostly_schema.to_str().unwrap(), "-f", "json"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("\"Int64\""))
+			.stderr(predicate::str::contains("column 'qty' is written as Utf8"));
+		assert!(fs::read_to_string(&mostly_schema).unwrap().contains("\"Utf8\""));
+		nail().args(["count", mostly_path.to_str().unwrap(), "--schema", mostly_schema.to_str().unwrap()])
+			.assert()
+			.success()
+			.stdout("40\n");
+	}
+
+	#[test]
+	fn test_where_prefilter() {
+		let fixtures = TestFixtures::new();
+		let input = fixtures.sample_parquet.to_str().unwrap();
+
+		nail().args(["count", input, "--where", "category = 'A'"]).assert().success().stdout("2\n");
+		nail().args(["stats", input, "-c", "value", "--where", "category in ('B', 'C')", "-f", "json"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("\"count\": 3"));
+		nail().args(["head", input, "--where", "value > 400", "--explain"])
+			.assert()
+			.success()
+			.stdout(predicate::str::contains("pruning_predicate"));
+		nail().args(["count", input, "--where", "categry = 'A'"]).assert().code(5)
+			.stderr(predicate::str::contains("Did you mean 'category'?"));
+	}
+}